@@ -4,7 +4,7 @@ set -e
 
 mkdir -p build
 
-CIRCUITS="create move"
+CIRCUITS="create move move_sunk"
 
 for circuit in $CIRCUITS; do
   echo "Building $circuit"
@@ -13,12 +13,15 @@ for circuit in $CIRCUITS; do
 
   ~/.cargo/bin/circom $circuit.circom --r1cs --wasm -o build/$circuit
   cd build/$circuit
-  npx snarkjs powersoftau new bn128 12 pot12_0000.ptau
-  npx snarkjs powersoftau contribute pot12_0000.ptau pot12_0001.ptau --name="First contribution" -e="$(openssl rand -base64 20)"
-  npx snarkjs powersoftau prepare phase2 pot12_0001.ptau pot12_final.ptau
-  npx snarkjs groth16 setup ${circuit}.r1cs pot12_final.ptau ${circuit}_0000.zkey
+  # 2^13 constraints covers move_sunk looking up the prior hits of every ship cell
+  npx snarkjs powersoftau new bn128 13 pot13_0000.ptau
+  npx snarkjs powersoftau contribute pot13_0000.ptau pot13_0001.ptau --name="First contribution" -e="$(openssl rand -base64 20)"
+  npx snarkjs powersoftau prepare phase2 pot13_0001.ptau pot13_final.ptau
+  npx snarkjs groth16 setup ${circuit}.r1cs pot13_final.ptau ${circuit}_0000.zkey
   # NB: This trusted setup isn't suitable for production usage
   npx snarkjs zkey contribute ${circuit}_0000.zkey ${circuit}_0001.zkey --name="Second contribution" -e="$(openssl rand -base64 20)"
   npx snarkjs zkey export verificationkey ${circuit}_0001.zkey verification_key.json
+  # A proof of the fixture inputs, which the zome's verifier tests check against this build's key
+  npx snarkjs groth16 fullprove ../../fixtures/$circuit.json ${circuit}_js/$circuit.wasm ${circuit}_0001.zkey proof.json public.json
   cd ../..
 done
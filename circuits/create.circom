@@ -20,11 +20,11 @@ template BattleshipCreate() {
     // validate starting position
     assert(ships[i][0] >= 0 && ships[i][0] < boardSize);
     assert(ships[i][1] >= 0 && ships[i][1] < boardSize);
-    // validate boats don't overflow off board
+    // validate boats don't overflow off board (last cell is start + len - 1)
     if (ships[i][2] == 0) { // Down
-      assert(ships[i][1] + len < boardSize);
+      assert(ships[i][1] + len <= boardSize);
     } else {
-      assert(ships[i][0] + len < boardSize);
+      assert(ships[i][0] + len <= boardSize);
     }
     // validate no overlap
     for (var l = 0; l < len; l++) {
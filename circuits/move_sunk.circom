@@ -0,0 +1,123 @@
+// Extends move.circom to additionally prove whether the shot sank a ship. Unlike move.circom
+// every output is constrained, so a proof can't claim any other outcome than the committed
+// fleet gives.
+
+pragma circom 2.0.0;
+
+include "./node_modules/circomlib/circuits/poseidon.circom";
+include "./node_modules/circomlib/circuits/bitify.circom";
+include "./node_modules/circomlib/circuits/comparators.circom";
+include "./node_modules/circomlib/circuits/multiplexer.circom";
+include "./consts.circom";
+
+// Constrains a coordinate to the board
+template OnBoard() {
+  signal input in;
+
+  component bits = Num2Bits(4);
+  bits.in <== in;
+  component lessThan = LessThan(4);
+  lessThan.in[0] <== in;
+  lessThan.in[1] <== getBoardSize();
+  lessThan.out === 1;
+}
+
+template BattleshipMoveSunk() {
+  // Public Inputs:
+  signal input boardHash;
+  signal input guess[2]; // [x,y]
+  signal input priorHits; // bitmask of cells already hit, bit (y * boardSize + x)
+  // Private Inputs:
+  signal input nonce;
+  signal input ships[5][3]; // [x,y,direction]
+
+  signal output isHit;
+  signal output sunkLength; // length of the ship sunk by this shot, 0 if none
+
+  var boardSize = getBoardSize();
+  var lengths[5] = getShipLengths();
+  var cells = 0;
+  for (var i = 0; i < 5; i++) {
+    cells += lengths[i];
+  }
+
+  // 1. validate the guess is actually valid
+  component guessOnBoard[2];
+  for (var j = 0; j < 2; j++) {
+    guessOnBoard[j] = OnBoard();
+    guessOnBoard[j].in <== guess[j];
+  }
+
+  // 2. validate the inputted ships matches the public hash
+  component poseidon = Poseidon(6);
+  poseidon.inputs[0] <== nonce;
+  for (var i = 0; i < 5; i++) {
+    poseidon.inputs[i+1] <== ships[i][0] + (ships[i][1] * (10 ** 1)) + (ships[i][2] * (10 ** 2));
+  }
+  boardHash === poseidon.out;
+
+  // 3. unpack the prior hits
+  component priorHitBits = Num2Bits(boardSize * boardSize);
+  priorHitBits.in <== priorHits;
+
+  // 4. for every cell of every ship, whether it's the guess and whether it was already hit
+  component cellOnBoard[cells][2];
+  component cellIndex[cells];
+  component isGuess[cells];
+  signal priorHitTerms[cells][boardSize * boardSize];
+  signal cellPriorHit[cells];
+  signal cellAfloat[cells];
+  component shipCleared[5];
+  signal shipHit[5];
+  signal shipSunk[5];
+
+  var cell = 0;
+  var hit = 0;
+  var sunk = 0;
+  for (var i = 0; i < 5; i++) {
+    ships[i][2] * (ships[i][2] - 1) === 0;
+    var shipHits = 0;
+    var afloat = 0;
+    for (var l = 0; l < lengths[i]; l++) {
+      // Down (0) runs along y, Right (1) along x
+      var x = ships[i][0] + ships[i][2] * l;
+      var y = ships[i][1] + (1 - ships[i][2]) * l;
+      cellOnBoard[cell][0] = OnBoard();
+      cellOnBoard[cell][0].in <== x;
+      cellOnBoard[cell][1] = OnBoard();
+      cellOnBoard[cell][1].in <== y;
+
+      cellIndex[cell] = Decoder(boardSize * boardSize);
+      cellIndex[cell].inp <== y * boardSize + x;
+      cellIndex[cell].success === 1;
+      var priorHit = 0;
+      for (var k = 0; k < boardSize * boardSize; k++) {
+        priorHitTerms[cell][k] <== cellIndex[cell].out[k] * priorHitBits.out[k];
+        priorHit += priorHitTerms[cell][k];
+      }
+      cellPriorHit[cell] <== priorHit;
+
+      isGuess[cell] = IsEqual();
+      isGuess[cell].in[0] <== y * boardSize + x;
+      isGuess[cell].in[1] <== guess[1] * boardSize + guess[0];
+      // Neither this shot nor an earlier one hit it
+      cellAfloat[cell] <== (1 - isGuess[cell].out) * (1 - cellPriorHit[cell]);
+
+      shipHits += isGuess[cell].out;
+      afloat += cellAfloat[cell];
+      cell++;
+    }
+    shipHit[i] <== shipHits;
+    shipCleared[i] = IsZero();
+    shipCleared[i].in <== afloat;
+    shipSunk[i] <== shipHit[i] * shipCleared[i].out;
+    hit += shipHit[i];
+    sunk += shipSunk[i] * lengths[i];
+  }
+  isHit <== hit;
+  // Ships don't overlap, so no more than one can be hit
+  isHit * (isHit - 1) === 0;
+  sunkLength <== sunk;
+}
+
+component main {public [boardHash, guess, priorHits]} = BattleshipMoveSunk();
@@ -0,0 +1,99 @@
+// Checks the move_sunk circuit rejects outcomes its fleet doesn't give. Needs the circuits
+// built with build_all.sh first.
+//
+//   npm test --prefix circuits
+
+import { test } from "node:test";
+import assert from "node:assert/strict";
+import { readFileSync } from "fs";
+import { dirname, join } from "path";
+import { fileURLToPath } from "url";
+import * as snarkjs from "snarkjs";
+
+const build = join(dirname(fileURLToPath(import.meta.url)), "../build");
+const circuit = (name) => ({
+  wasm: join(build, name, `${name}_js`, `${name}.wasm`),
+  zkey: join(build, name, `${name}_0001.zkey`),
+  vkey: JSON.parse(readFileSync(join(build, name, "verification_key.json"))),
+});
+
+// Horizontal ships on rows 0, 2, 4, 6, 8, so the destroyer covers (0,8) and (1,8)
+const NONCE = "1234567";
+const SHIPS = [5, 4, 3, 3, 2].map((_, i) => ["0", (i * 2).toString(), "1"]);
+// Witness signal 0 is the constant 1, followed by the outputs isHit and sunkLength
+const SUNK_LENGTH = 2;
+
+async function boardHash() {
+  const { publicSignals } = await snarkjs.groth16.fullProve(
+    { nonce: NONCE, ships: SHIPS },
+    circuit("create").wasm,
+    circuit("create").zkey
+  );
+  return publicSignals[0];
+}
+
+// Overwrites one signal of an in-memory witness file
+function setWitness(wtns, index, value) {
+  const data = Buffer.from(wtns.data.buffer, wtns.data.byteOffset, wtns.data.byteLength);
+  // "wtns", version and section count, then sections of type (u32), size (u64) and body
+  let offset = 12;
+  let n8;
+  for (;;) {
+    const type = data.readUInt32LE(offset);
+    const size = Number(data.readBigUInt64LE(offset + 4));
+    const body = offset + 12;
+    if (type === 1) n8 = data.readUInt32LE(body);
+    if (type === 2) {
+      const start = body + index * n8;
+      data.fill(0, start, start + n8);
+      data.writeBigUInt64LE(BigInt(value), start);
+      return;
+    }
+    offset = body + size;
+  }
+}
+
+async function proveMoveSunk(guess, priorHits, tamper) {
+  const moveSunk = circuit("move_sunk");
+  const wtns = { type: "mem" };
+  await snarkjs.wtns.calculate(
+    { nonce: NONCE, ships: SHIPS, boardHash: await boardHash(), guess, priorHits },
+    moveSunk.wasm,
+    wtns
+  );
+  if (tamper) tamper(wtns);
+  const { proof, publicSignals } = await snarkjs.groth16.prove(moveSunk.zkey, wtns);
+  return {
+    publicSignals,
+    verified: await snarkjs.groth16.verify(moveSunk.vkey, publicSignals, proof),
+  };
+}
+
+test("sinking the destroyer proves its length", async () => {
+  const priorHits = (1n << 80n).toString();
+  const { publicSignals, verified } = await proveMoveSunk(["1", "8"], priorHits);
+  assert.equal(publicSignals[0], "1");
+  assert.equal(publicSignals[1], "2");
+  assert.ok(verified);
+});
+
+test("claiming a sinking that didn't happen is rejected", async () => {
+  const honest = await proveMoveSunk(["1", "8"], "0");
+  assert.equal(honest.publicSignals[1], "0");
+  assert.ok(honest.verified);
+
+  const forged = await proveMoveSunk(["1", "8"], "0", (wtns) =>
+    setWitness(wtns, SUNK_LENGTH, 2)
+  );
+  assert.equal(forged.publicSignals[1], "2");
+  assert.ok(!forged.verified);
+});
+
+test("hiding a sinking is rejected", async () => {
+  const priorHits = (1n << 80n).toString();
+  const forged = await proveMoveSunk(["1", "8"], priorHits, (wtns) =>
+    setWitness(wtns, SUNK_LENGTH, 0)
+  );
+  assert.equal(forged.publicSignals[1], "0");
+  assert.ok(!forged.verified);
+});
@@ -0,0 +1,148 @@
+use std::{
+    collections::BTreeSet,
+    fmt::Debug,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ed25519_dalek::{Signer, SigningKey};
+use holochain_client::{AdminWebsocket, AppWebsocket, ZomeCall};
+use holochain_conductor_api::CellInfo;
+use holochain_types::prelude::*;
+use rand::Rng;
+use serde::{de::DeserializeOwned, Serialize};
+
+const ZOME_NAME: &str = "battleships";
+const ROLE_NAME: &str = "battleships";
+
+// The only zome functions the bot's signing key is allowed to call
+const BOT_FUNCTIONS: [&str; 9] = [
+    "get_my_games",
+    "create_ship_deployment",
+    "get_your_ship_deployment_for_invite",
+    "create_ship_deployment_proof",
+    "get_your_ship_deployment_proof_for_invite",
+    "reveal_coin_flip",
+    "get_latest_game_transcript_for_game_invite",
+    "fire_salvo",
+    "prove_salvo",
+];
+
+// How long a signed zome call stays valid
+const CALL_EXPIRY: Duration = Duration::from_secs(5 * 60);
+
+/// A connection to the bot's cell on a local conductor. Zome calls are signed with a key
+/// generated at startup and granted a capability through the admin interface, as the
+/// launcher does for the UI.
+pub struct BotClient {
+    app_ws: AppWebsocket,
+    cell_id: CellId,
+    signing_key: SigningKey,
+    cap_secret: CapSecret,
+}
+
+fn now_micros() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system clock is after 1970")
+        .as_micros() as i64
+}
+
+impl BotClient {
+    pub async fn connect(admin_port: u16, app_port: u16, app_id: &str) -> Result<Self, String> {
+        let mut admin_ws = AdminWebsocket::connect(format!("ws://localhost:{}", admin_port))
+            .await
+            .map_err(|e| format!("Could not connect to the admin interface: {:?}", e))?;
+        let mut app_ws = AppWebsocket::connect(format!("ws://localhost:{}", app_port))
+            .await
+            .map_err(|e| format!("Could not connect to the app interface: {:?}", e))?;
+
+        let app_info = app_ws
+            .app_info(app_id.to_string())
+            .await
+            .map_err(|e| format!("{:?}", e))?
+            .ok_or_else(|| format!("App {} is not installed", app_id))?;
+        let cell_id = app_info
+            .cell_info
+            .get(ROLE_NAME)
+            .into_iter()
+            .flatten()
+            .find_map(|cell_info| match cell_info {
+                CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+                _ => None,
+            })
+            .ok_or_else(|| format!("App {} has no {} cell", app_id, ROLE_NAME))?;
+
+        let mut rng = rand::thread_rng();
+        let signing_key = SigningKey::from_bytes(&rng.gen());
+        let mut cap_secret = [0; CAP_SECRET_BYTES];
+        rng.fill(&mut cap_secret[..]);
+        let cap_secret = CapSecret::from(cap_secret);
+        let signing_agent =
+            AgentPubKey::from_raw_32(signing_key.verifying_key().to_bytes().to_vec());
+        admin_ws
+            .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+                cell_id: cell_id.clone(),
+                cap_grant: ZomeCallCapGrant {
+                    tag: "battleships_bot".into(),
+                    access: CapAccess::Assigned {
+                        secret: cap_secret,
+                        assignees: BTreeSet::from([signing_agent]),
+                    },
+                    functions: BOT_FUNCTIONS
+                        .iter()
+                        .map(|fn_name| (ZomeName::from(ZOME_NAME), FunctionName::from(*fn_name)))
+                        .collect(),
+                },
+            })
+            .await
+            .map_err(|e| format!("Could not authorize the bot's signing key: {:?}", e))?;
+
+        Ok(BotClient {
+            app_ws,
+            cell_id,
+            signing_key,
+            cap_secret,
+        })
+    }
+
+    pub fn agent_pub_key(&self) -> &AgentPubKey {
+        self.cell_id.agent_pubkey()
+    }
+
+    pub async fn call<I, O>(&mut self, fn_name: &str, payload: I) -> Result<O, String>
+    where
+        I: Serialize + Debug,
+        O: DeserializeOwned + Debug,
+    {
+        let unsigned = ZomeCallUnsigned {
+            provenance: AgentPubKey::from_raw_32(
+                self.signing_key.verifying_key().to_bytes().to_vec(),
+            ),
+            cell_id: self.cell_id.clone(),
+            zome_name: ZOME_NAME.into(),
+            fn_name: fn_name.into(),
+            cap_secret: Some(self.cap_secret),
+            payload: ExternIO::encode(payload).map_err(|e| e.to_string())?,
+            nonce: Nonce256Bits::from(rand::thread_rng().gen::<[u8; 32]>()),
+            expires_at: Timestamp::from_micros(now_micros() + CALL_EXPIRY.as_micros() as i64),
+        };
+        let data_to_sign = unsigned.data_to_sign().map_err(|e| e.to_string())?;
+        let signature = Signature::from(self.signing_key.sign(&data_to_sign).to_bytes());
+        let response = self
+            .app_ws
+            .call_zome(ZomeCall {
+                cell_id: unsigned.cell_id,
+                zome_name: unsigned.zome_name,
+                fn_name: unsigned.fn_name,
+                payload: unsigned.payload,
+                cap_secret: unsigned.cap_secret,
+                provenance: unsigned.provenance,
+                signature,
+                nonce: unsigned.nonce,
+                expires_at: unsigned.expires_at,
+            })
+            .await
+            .map_err(|e| format!("{} failed: {:?}", fn_name, e))?;
+        response.decode().map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,70 @@
+//! Plays Battleships unattended from an agent installed on a local conductor.
+//!
+//! ```text
+//! battleships_bot <admin-port> <app-port>
+//! ```
+//!
+//! Every game waiting on the bot's agent is polled for: invites addressed to it are accepted by
+//! deploying a random fleet, and shots are chosen by `strategy::choose_salvo` from the game's
+//! transcript. Proofs are made with snarkjs from the circuits in `BATTLESHIPS_CIRCUITS`
+//! (default `circuits/build`), run as `BATTLESHIPS_SNARKJS` (default `npx snarkjs`). The app is
+//! looked up by `BATTLESHIPS_APP_ID` (default `battleships`).
+
+mod client;
+mod player;
+mod prover;
+mod strategy;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use client::BotClient;
+use hdk::prelude::AgentPubKeyB64;
+use player::play_waiting_games;
+use prover::Prover;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name).unwrap_or_else(|_| default.to_string())
+}
+
+fn parse_ports() -> Option<(u16, u16)> {
+    let mut args = std::env::args().skip(1);
+    let admin_port = args.next()?.parse().ok()?;
+    let app_port = args.next()?.parse().ok()?;
+    args.next().is_none().then_some((admin_port, app_port))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let (admin_port, app_port) = match parse_ports() {
+        Some(ports) => ports,
+        None => {
+            eprintln!("Usage: battleships_bot <admin-port> <app-port>");
+            return ExitCode::from(2);
+        }
+    };
+    let app_id = env_or("BATTLESHIPS_APP_ID", "battleships");
+    let prover = Prover::new(
+        PathBuf::from(env_or("BATTLESHIPS_CIRCUITS", "circuits/build")),
+        &env_or("BATTLESHIPS_SNARKJS", "npx snarkjs"),
+    );
+    let mut client = match BotClient::connect(admin_port, app_port, &app_id).await {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Could not connect to the conductor: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    println!(
+        "Playing as {}",
+        AgentPubKeyB64::from(client.agent_pub_key().clone())
+    );
+
+    loop {
+        if let Err(e) = play_waiting_games(&mut client, &prover).await {
+            eprintln!("Could not list games: {}", e);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
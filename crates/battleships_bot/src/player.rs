@@ -0,0 +1,262 @@
+use battleships::{
+    game_state::GameState,
+    my_games::{MyGame, MyGamesFilter},
+};
+use battleships_integrity::{
+    allowed_salvo_size, GameTranscript, GameTurn, HitOrMissProof, HitOrMissProofKind, Player,
+    ShipDeployment, ShipDeploymentProof, Shot, Transcript,
+};
+use hdk::prelude::*;
+
+use crate::{
+    client::BotClient,
+    prover::Prover,
+    strategy::{choose_salvo, random_fleet},
+};
+
+// Mirror the coordinator's inputs, whose fields are private
+#[derive(Serialize, Debug)]
+struct FireSalvoInput {
+    game_invite_hash: ActionHash,
+    shots: Vec<Shot>,
+}
+
+#[derive(Serialize, Debug)]
+struct ProveSalvoInput {
+    game_invite_hash: ActionHash,
+    hit_or_miss_proofs: Vec<HitOrMissProof>,
+}
+
+fn entry<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
+    record: &Record,
+) -> Result<T, String> {
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Record {} has no entry", record.action_address()))
+}
+
+/// Plays one side of a game, one move at a time
+pub struct BotPlayer<'a> {
+    client: &'a mut BotClient,
+    prover: &'a Prover,
+    game: &'a MyGame,
+    me: Player,
+}
+
+impl<'a> BotPlayer<'a> {
+    pub fn new(client: &'a mut BotClient, prover: &'a Prover, game: &'a MyGame) -> Self {
+        let me = if &game.game_invite.home_player == client.agent_pub_key() {
+            Player::Home
+        } else {
+            Player::Away
+        };
+        BotPlayer {
+            client,
+            prover,
+            game,
+            me,
+        }
+    }
+
+    fn invite(&self) -> ActionHash {
+        self.game.game_invite_hash.clone()
+    }
+
+    /// Makes whatever move the game is waiting on the bot for
+    pub async fn take_turn(&mut self) -> Result<(), String> {
+        let game = self.game;
+        match &game.state {
+            GameState::AwaitingBothDeployments
+            | GameState::AwaitingHomeDeployment
+            | GameState::AwaitingAwayDeployment => self.deploy().await,
+            GameState::AwaitingCoinFlip { .. } => {
+                let _: Record = self.client.call("reveal_coin_flip", self.invite()).await?;
+                Ok(())
+            }
+            GameState::GameStarted { turn, .. } => match (turn, &self.me) {
+                (GameTurn::HomeShot, Player::Home) | (GameTurn::AwayShot, Player::Away) => {
+                    self.fire().await
+                }
+                (GameTurn::HomeProof, Player::Home) | (GameTurn::AwayProof, Player::Away) => {
+                    self.prove().await
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    /// Our private deployment, if we have made one for this game
+    async fn ship_deployment(&mut self) -> Result<Option<(ActionHash, ShipDeployment)>, String> {
+        let record: Option<Record> = self
+            .client
+            .call("get_your_ship_deployment_for_invite", self.invite())
+            .await?;
+        record
+            .map(|record| Ok((record.action_address().clone(), entry(&record)?)))
+            .transpose()
+    }
+
+    async fn deploy(&mut self) -> Result<(), String> {
+        // Reuse a deployment left without a proof by an earlier run
+        let (private_entry, ship_deployment) = match self.ship_deployment().await? {
+            Some(ship_deployment) => ship_deployment,
+            None => {
+                let ship_deployment = ShipDeployment {
+                    invite: self.invite(),
+                    ships: random_fleet(&mut rand::thread_rng()),
+                    salt: rand::random::<u64>().to_string(),
+                    // Generated by the zome
+                    coin_secret: Vec::new(),
+                };
+                let record: Record = self
+                    .client
+                    .call("create_ship_deployment", ship_deployment.clone())
+                    .await?;
+                (record.action_address().clone(), ship_deployment)
+            }
+        };
+        let (commitment, proof) = self
+            .prover
+            .prove_deployment(&ship_deployment.ships, &ship_deployment.salt)
+            .await?;
+        let _: Record = self
+            .client
+            .call(
+                "create_ship_deployment_proof",
+                ShipDeploymentProof {
+                    invite: self.invite(),
+                    private_entry,
+                    commitment,
+                    proof,
+                    coin_commitment: Vec::new(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn transcript(&mut self) -> Result<Option<GameTranscript>, String> {
+        let record: Option<Record> = self
+            .client
+            .call("get_latest_game_transcript_for_game_invite", self.invite())
+            .await?;
+        record.as_ref().map(entry).transpose()
+    }
+
+    async fn fire(&mut self) -> Result<(), String> {
+        let game_transcript = self.transcript().await?;
+        let (shots, outcomes) = match &game_transcript {
+            Some(game_transcript) => (
+                game_transcript.shots(&self.me),
+                game_transcript.outcomes(&self.me.opponent()),
+            ),
+            None => (&[][..], &[][..]),
+        };
+        let salvo_size = allowed_salvo_size(
+            &self.game.game_invite.rules,
+            game_transcript
+                .as_ref()
+                .map(|game_transcript| game_transcript.outcomes(&self.me))
+                .unwrap_or_default(),
+            shots,
+        );
+        let shots = choose_salvo(&mut rand::thread_rng(), shots, outcomes, salvo_size);
+        let _: Record = self
+            .client
+            .call(
+                "fire_salvo",
+                FireSalvoInput {
+                    game_invite_hash: self.invite(),
+                    shots,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn prove(&mut self) -> Result<(), String> {
+        let game_transcript = self
+            .transcript()
+            .await?
+            .ok_or("The opponent has not fired yet")?;
+        let (_, ship_deployment) = self
+            .ship_deployment()
+            .await?
+            .ok_or("We have no deployment for this game")?;
+        let deployment_proof_record: Record = self
+            .client
+            .call::<_, Option<Record>>("get_your_ship_deployment_proof_for_invite", self.invite())
+            .await?
+            .ok_or("We have no deployment proof for this game")?;
+        let deployment_proof: ShipDeploymentProof = entry(&deployment_proof_record)?;
+
+        let opponent_salvos = game_transcript.shots(&self.me.opponent());
+        let (salvo, earlier_salvos) = opponent_salvos
+            .split_last()
+            .ok_or("The opponent has not fired yet")?;
+        let mut prior_hits: Vec<Shot> = earlier_salvos
+            .iter()
+            .flatten()
+            .zip(game_transcript.outcomes(&self.me).iter().flatten())
+            .filter(|(_, shot_outcome)| shot_outcome.hit)
+            .map(|(shot, _)| shot.clone())
+            .collect();
+        let mut hit_or_miss_proofs = Vec::new();
+        for shot in salvo {
+            let (result, proof) = self
+                .prover
+                .prove_shot(
+                    &ship_deployment.ships,
+                    &ship_deployment.salt,
+                    &deployment_proof.commitment,
+                    &prior_hits,
+                    shot,
+                )
+                .await?;
+            hit_or_miss_proofs.push(HitOrMissProof {
+                deployment_proof: deployment_proof_record.action_address().clone(),
+                deployment_commitment: deployment_proof.commitment.clone(),
+                shot: shot.clone(),
+                hit: result.hit,
+                proof,
+                kind: HitOrMissProofKind::WithSunk {
+                    prior_hits: prior_hits.clone(),
+                    sunk_ship_length: result.sunk_ship_length,
+                },
+            });
+            if result.hit {
+                prior_hits.push(shot.clone());
+            }
+        }
+        let _: Record = self
+            .client
+            .call(
+                "prove_salvo",
+                ProveSalvoInput {
+                    game_invite_hash: self.invite(),
+                    hit_or_miss_proofs,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Makes a move in every game waiting on the bot, including accepting new invites by
+/// deploying a fleet for them
+pub async fn play_waiting_games(client: &mut BotClient, prover: &Prover) -> Result<(), String> {
+    let games: Vec<MyGame> = client.call("get_my_games", MyGamesFilter::MyTurn).await?;
+    for game in &games {
+        // One broken game shouldn't stop the others
+        if let Err(e) = BotPlayer::new(client, prover, game).take_turn().await {
+            eprintln!(
+                "Could not move in game {}: {}",
+                ActionHashB64::from(game.game_invite_hash.clone()),
+                e
+            );
+        }
+    }
+    Ok(())
+}
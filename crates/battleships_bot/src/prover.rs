@@ -0,0 +1,179 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use battleships_integrity::{hits_bitmask, shot_result, Ship, Shot, ShotResult};
+use serde_json::{json, Value};
+use tokio::process::Command;
+
+/// Proves deployments and shots by running snarkjs on the circuits built by
+/// `circuits/build_all.sh`, the same way the UI does in the browser
+pub struct Prover {
+    circuits: PathBuf,
+    snarkjs: Vec<String>,
+    proofs: AtomicUsize,
+}
+
+fn ship_inputs(ships: &[Ship]) -> Value {
+    ships
+        .iter()
+        .map(|ship| {
+            json!([
+                ship.x.to_string(),
+                ship.y.to_string(),
+                if ship.horizontal { "1" } else { "0" }
+            ])
+        })
+        .collect()
+}
+
+// Same layout as proofToCommaSeparated in the UI
+fn proof_to_comma_separated(proof: &Value) -> Result<String, String> {
+    [
+        &proof["pi_a"][0],
+        &proof["pi_a"][1],
+        &proof["pi_b"][0][0],
+        &proof["pi_b"][0][1],
+        &proof["pi_b"][1][0],
+        &proof["pi_b"][1][1],
+        &proof["pi_c"][0],
+        &proof["pi_c"][1],
+    ]
+    .iter()
+    .map(|part| {
+        part.as_str()
+            .map(String::from)
+            .ok_or_else(|| format!("Badly formed proof: {}", proof))
+    })
+    .collect::<Result<Vec<String>, String>>()
+    .map(|parts| parts.join(","))
+}
+
+impl Prover {
+    /// `snarkjs` is the command to run snarkjs with, e.g. `npx snarkjs`
+    pub fn new(circuits: PathBuf, snarkjs: &str) -> Self {
+        Prover {
+            circuits,
+            snarkjs: snarkjs.split_whitespace().map(String::from).collect(),
+            proofs: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the comma separated proof and the circuit's public signals
+    async fn full_prove(
+        &self,
+        circuit: &str,
+        input: Value,
+    ) -> Result<(String, Vec<String>), String> {
+        let dir = std::env::temp_dir().join(format!(
+            "battleships_bot_{}_{}",
+            std::process::id(),
+            self.proofs.fetch_add(1, Ordering::Relaxed)
+        ));
+        tokio::fs::create_dir_all(&dir)
+            .await
+            .map_err(|e| e.to_string())?;
+        let result = self.full_prove_in(&dir, circuit, input).await;
+        tokio::fs::remove_dir_all(&dir).await.ok();
+        result
+    }
+
+    async fn full_prove_in(
+        &self,
+        dir: &PathBuf,
+        circuit: &str,
+        input: Value,
+    ) -> Result<(String, Vec<String>), String> {
+        let circuit_dir = self.circuits.join(circuit);
+        tokio::fs::write(dir.join("input.json"), input.to_string())
+            .await
+            .map_err(|e| e.to_string())?;
+        let output = Command::new(&self.snarkjs[0])
+            .args(&self.snarkjs[1..])
+            .args(["groth16", "fullprove", "input.json"])
+            .arg(circuit_dir.join(format!("{}_js/{}.wasm", circuit, circuit)))
+            .arg(circuit_dir.join(format!("{}_0001.zkey", circuit)))
+            .args(["proof.json", "public.json"])
+            .current_dir(dir)
+            .output()
+            .await
+            .map_err(|e| format!("Could not run snarkjs: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "snarkjs failed to prove {}: {}",
+                circuit,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        let read_json = |name: &str| {
+            let path = dir.join(name);
+            async move {
+                let json = tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                serde_json::from_str::<Value>(&json).map_err(|e| e.to_string())
+            }
+        };
+        let proof = proof_to_comma_separated(&read_json("proof.json").await?)?;
+        let public_signals =
+            serde_json::from_value(read_json("public.json").await?).map_err(|e| e.to_string())?;
+        Ok((proof, public_signals))
+    }
+
+    /// Proves the fleet is legal, returning its commitment and the proof
+    pub async fn prove_deployment(
+        &self,
+        ships: &[Ship],
+        salt: &str,
+    ) -> Result<(String, String), String> {
+        let (proof, public_signals) = self
+            .full_prove(
+                "create",
+                json!({ "nonce": salt, "ships": ship_inputs(ships) }),
+            )
+            .await?;
+        let commitment = public_signals
+            .into_iter()
+            .next()
+            .ok_or("The create circuit gave no commitment")?;
+        Ok((commitment, proof))
+    }
+
+    /// Proves the outcome of a shot at our fleet with the move_sunk circuit, given the earlier
+    /// hits on it
+    pub async fn prove_shot(
+        &self,
+        ships: &[Ship],
+        salt: &str,
+        commitment: &str,
+        prior_hits: &[Shot],
+        shot: &Shot,
+    ) -> Result<(ShotResult, String), String> {
+        let (proof, public_signals) = self
+            .full_prove(
+                "move_sunk",
+                json!({
+                    "boardHash": commitment,
+                    "guess": [shot.x.to_string(), shot.y.to_string()],
+                    "priorHits": hits_bitmask(prior_hits).to_string(),
+                    "nonce": salt,
+                    "ships": ship_inputs(ships),
+                }),
+            )
+            .await?;
+        // The circuit outputs isHit and sunkLength ahead of its public inputs
+        let result = shot_result(ships, prior_hits, shot);
+        let expected = [
+            if result.hit { "1" } else { "0" }.to_string(),
+            result.sunk_ship_length.unwrap_or(0).to_string(),
+        ];
+        if public_signals.get(..2) != Some(&expected[..]) {
+            return Err(format!(
+                "move_sunk disagrees with the rules about {:?}: {:?}",
+                shot, public_signals
+            ));
+        }
+        Ok((result, proof))
+    }
+}
@@ -0,0 +1,198 @@
+//! Where to deploy and where to shoot, with no knowledge of the opponent beyond the transcript.
+//!
+//! Shots go wherever `shot_heatmap` counts the most fleet placements. Those have to explain
+//! every hit on a ship not yet sunk, so the bot hunts across the board until it finds a ship
+//! and then targets around it.
+
+use battleships_rules::{
+    all_placements,
+    constants::{BOARD_SIZE, SHIP_LENGTHS},
+    ship_squares, shot_heatmap, validate_placement, Outcome, Ship, Shot,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// A legal fleet placed at random, one ship at a time
+pub fn random_fleet<R: Rng>(rng: &mut R) -> Vec<Ship> {
+    let mut fleet = Vec::new();
+    let mut occupied: Vec<(usize, usize)> = Vec::new();
+    for ship_length in SHIP_LENGTHS {
+        let candidates: Vec<(Ship, Vec<(usize, usize)>)> = all_placements()
+            .filter_map(|ship| {
+                let squares = ship_squares(&ship, ship_length)?;
+                squares
+                    .iter()
+                    .all(|square| !occupied.contains(square))
+                    .then_some((ship, squares))
+            })
+            .collect();
+        let (ship, squares) = candidates
+            .choose(rng)
+            .expect("a fleet always fits on the board")
+            .clone();
+        fleet.push(ship);
+        occupied.extend(squares);
+    }
+    debug_assert_eq!(validate_placement(&fleet), Ok(()));
+    fleet
+}
+
+/// The squares to fire at next, most likely hits first. `shots` are our earlier salvos and
+/// `outcomes` the opponent's proofs of them.
+pub fn choose_salvo<O: Outcome, R: Rng>(
+    rng: &mut R,
+    shots: &[Vec<Shot>],
+    outcomes: &[Vec<O>],
+    salvo_size: usize,
+) -> Vec<Shot> {
+    let heatmap = shot_heatmap(shots, outcomes);
+    let mut candidates: Vec<(u64, Shot)> = (0..BOARD_SIZE * BOARD_SIZE)
+        .map(|i| Shot {
+            x: i % BOARD_SIZE,
+            y: i / BOARD_SIZE,
+        })
+        .filter(|shot| !shots.iter().flatten().any(|fired| fired == shot))
+        .map(|shot| (heatmap[shot.x][shot.y], shot))
+        .collect();
+    // Shuffling first breaks ties at random, since the sort is stable
+    candidates.shuffle(rng);
+    candidates.sort_by(|(a, _), (b, _)| b.cmp(a));
+    candidates
+        .into_iter()
+        .take(salvo_size)
+        .map(|(_, shot)| shot)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use battleships_rules::{
+        constants::FLEET_SQUARES, first_invalid_shot, shot_result, Game, GameRules, Player,
+        ShotResult, Transcript,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn miss() -> ShotResult {
+        ShotResult {
+            hit: false,
+            sunk_ship_length: None,
+        }
+    }
+
+    fn hit() -> ShotResult {
+        ShotResult {
+            hit: true,
+            sunk_ship_length: None,
+        }
+    }
+
+    /// Plays the bot against a random fleet whose owner always misses, returning the number
+    /// of shots the bot took to sink it
+    fn shots_to_sink(rng: &mut StdRng, rules: GameRules) -> usize {
+        let fleet = random_fleet(rng);
+        let mut game = Game::new(rules, Player::Home);
+        let mut hits = Vec::new();
+        let mut opponent_shots = (0..BOARD_SIZE * BOARD_SIZE).map(|i| Shot {
+            x: i % BOARD_SIZE,
+            y: i / BOARD_SIZE,
+        });
+        loop {
+            let salvo = choose_salvo(
+                rng,
+                game.shots(&Player::Home),
+                game.outcomes(&Player::Away),
+                game.salvo_size(&Player::Home),
+            );
+            assert_eq!(first_invalid_shot(game.shots(&Player::Home), &salvo), None);
+            game.fire(&Player::Home, salvo.clone()).unwrap();
+            let outcomes = salvo
+                .iter()
+                .map(|shot| {
+                    let result = shot_result(&fleet, &hits, shot);
+                    if result.hit {
+                        hits.push(shot.clone());
+                    }
+                    result
+                })
+                .collect();
+            game.record_outcomes(&Player::Away, outcomes).unwrap();
+            if game.turn().is_finished() {
+                break;
+            }
+
+            let salvo_size = game.salvo_size(&Player::Away);
+            let salvo: Vec<Shot> = opponent_shots.by_ref().take(salvo_size).collect();
+            game.fire(&Player::Away, salvo).unwrap();
+            game.record_outcomes(&Player::Home, vec![miss(); salvo_size])
+                .unwrap();
+        }
+        assert_eq!(game.turn().winner(), Some(Player::Home));
+        assert_eq!(hits.len(), FLEET_SQUARES);
+        game.shots(&Player::Home).iter().map(Vec::len).sum()
+    }
+
+    #[test]
+    fn random_fleets_are_legal() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..100 {
+            assert_eq!(validate_placement(&random_fleet(&mut rng)), Ok(()));
+        }
+    }
+
+    #[test]
+    fn follows_up_on_a_hit() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let shots = vec![vec![Shot { x: 5, y: 5 }]];
+        let salvo = choose_salvo(&mut rng, &shots, &[vec![hit()]], 1);
+        let Shot { x, y } = salvo[0];
+        assert_eq!(x.abs_diff(5) + y.abs_diff(5), 1);
+    }
+
+    #[test]
+    fn avoids_squares_no_ship_fits() {
+        let mut rng = StdRng::seed_from_u64(0);
+        // (0, 0) is walled in by misses, so no ship can cover it
+        let shots = vec![vec![Shot { x: 1, y: 0 }], vec![Shot { x: 0, y: 1 }]];
+        let outcomes = vec![vec![miss()], vec![miss()]];
+        for _ in 0..20 {
+            let salvo = choose_salvo(&mut rng, &shots, &outcomes, 5);
+            assert!(!salvo.contains(&Shot { x: 0, y: 0 }));
+        }
+    }
+
+    #[test]
+    fn never_fires_at_a_square_twice() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let shots: Vec<Vec<Shot>> = (0..BOARD_SIZE * BOARD_SIZE - 3)
+            .map(|i| {
+                vec![Shot {
+                    x: i % BOARD_SIZE,
+                    y: i / BOARD_SIZE,
+                }]
+            })
+            .collect();
+        let outcomes = vec![vec![miss()]; shots.len() - 1];
+        let salvo = choose_salvo(&mut rng, &shots, &outcomes, 5);
+        assert_eq!(first_invalid_shot(&shots, &salvo), None);
+        assert_eq!(salvo.len(), 3);
+    }
+
+    #[test]
+    fn sinks_fleets_much_faster_than_random_fire() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for rules in [GameRules::Classic, GameRules::Salvo] {
+            let games = 20;
+            let total: usize = (0..games)
+                .map(|_| shots_to_sink(&mut rng, rules.clone()))
+                .sum();
+            // Random fire needs about 96 shots on average
+            assert!(
+                total / games < 70,
+                "{:?} took {} shots",
+                rules,
+                total / games
+            );
+        }
+    }
+}
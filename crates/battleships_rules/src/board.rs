@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::constants::BOARD_SIZE;
+
+#[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub struct Shot {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Ship {
+    pub x: usize,
+    pub y: usize,
+    pub horizontal: bool,
+}
+
+pub fn shot_is_on_board(shot: &Shot) -> bool {
+    shot.x < BOARD_SIZE && shot.y < BOARD_SIZE
+}
+
+// Matches the priorHits encoding in move_sunk.circom
+pub fn hits_bitmask(shots: &[Shot]) -> u128 {
+    shots.iter().fold(0, |mask, shot| {
+        mask | 1u128 << (shot.y * BOARD_SIZE + shot.x)
+    })
+}
+
+/// First shot in the salvo that is off the board or repeats an earlier shot
+pub fn first_invalid_shot<'a>(
+    existing_salvos: &[Vec<Shot>],
+    salvo: &'a [Shot],
+) -> Option<&'a Shot> {
+    salvo.iter().enumerate().find_map(|(i, shot)| {
+        let is_valid = shot_is_on_board(shot)
+            && !existing_salvos
+                .iter()
+                .flatten()
+                .any(|existing| existing == shot)
+            && !salvo[..i].contains(shot);
+        (!is_valid).then_some(shot)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shots_off_the_board_are_invalid() {
+        assert!(shot_is_on_board(&Shot { x: 0, y: 0 }));
+        assert!(shot_is_on_board(&Shot {
+            x: BOARD_SIZE - 1,
+            y: BOARD_SIZE - 1
+        }));
+        assert!(!shot_is_on_board(&Shot {
+            x: BOARD_SIZE,
+            y: 0
+        }));
+        assert!(!shot_is_on_board(&Shot {
+            x: 0,
+            y: usize::MAX
+        }));
+    }
+
+    #[test]
+    fn repeated_shots_are_invalid() {
+        let existing = vec![vec![Shot { x: 1, y: 2 }]];
+        let salvo = [Shot { x: 3, y: 4 }, Shot { x: 1, y: 2 }];
+        assert_eq!(first_invalid_shot(&existing, &salvo), Some(&salvo[1]));
+        let salvo = [
+            Shot { x: 3, y: 4 },
+            Shot { x: 5, y: 6 },
+            Shot { x: 3, y: 4 },
+        ];
+        assert_eq!(first_invalid_shot(&existing, &salvo), Some(&salvo[2]));
+        let salvo = [Shot { x: 3, y: 4 }, Shot { x: 5, y: 6 }];
+        assert_eq!(first_invalid_shot(&existing, &salvo), None);
+    }
+
+    #[test]
+    fn hits_bitmask_sets_one_bit_per_square() {
+        assert_eq!(hits_bitmask(&[]), 0);
+        assert_eq!(hits_bitmask(&[Shot { x: 0, y: 0 }]), 1);
+        assert_eq!(hits_bitmask(&[Shot { x: 3, y: 1 }]), 1 << (BOARD_SIZE + 3));
+        assert_eq!(
+            hits_bitmask(&[
+                Shot {
+                    x: BOARD_SIZE - 1,
+                    y: BOARD_SIZE - 1
+                },
+                Shot { x: 1, y: 0 }
+            ]),
+            1 << (BOARD_SIZE * BOARD_SIZE - 1) | 1 << 1
+        );
+    }
+}
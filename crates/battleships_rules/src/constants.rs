@@ -0,0 +1,4 @@
+pub const BOARD_SIZE: usize = 10;
+pub const SHIP_LENGTHS: [usize; 5] = [5, 4, 3, 3, 2];
+// Total number of squares covered by a fleet, i.e. number of hits required to win
+pub const FLEET_SQUARES: usize = 17;
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    allowed_salvo_size, check_salvo, game_turn, GameRules, GameTurn, MoveError, Player, Shot,
+    ShotResult, Transcript,
+};
+
+/// A game played out in memory, for offline tools and bots. Follows the same turn order and
+/// salvo rules as the transcripts the integrity zome validates, without any proofs.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Game {
+    pub rules: GameRules,
+    pub first_player: Player,
+    pub home_player_shots: Vec<Vec<Shot>>,
+    pub away_player_shots: Vec<Vec<Shot>>,
+    pub home_player_outcomes: Vec<Vec<ShotResult>>,
+    pub away_player_outcomes: Vec<Vec<ShotResult>>,
+}
+
+impl Transcript for Game {
+    type Outcome = ShotResult;
+
+    fn first_player(&self) -> &Player {
+        &self.first_player
+    }
+
+    fn shots(&self, player: &Player) -> &[Vec<Shot>] {
+        match player {
+            Player::Home => &self.home_player_shots,
+            Player::Away => &self.away_player_shots,
+        }
+    }
+
+    fn outcomes(&self, player: &Player) -> &[Vec<ShotResult>] {
+        match player {
+            Player::Home => &self.home_player_outcomes,
+            Player::Away => &self.away_player_outcomes,
+        }
+    }
+}
+
+impl Game {
+    pub fn new(rules: GameRules, first_player: Player) -> Self {
+        Game {
+            rules,
+            first_player,
+            home_player_shots: Vec::new(),
+            away_player_shots: Vec::new(),
+            home_player_outcomes: Vec::new(),
+            away_player_outcomes: Vec::new(),
+        }
+    }
+
+    pub fn turn(&self) -> GameTurn {
+        game_turn(self)
+    }
+
+    /// Number of shots the given player must fire in their next salvo
+    pub fn salvo_size(&self, player: &Player) -> usize {
+        allowed_salvo_size(&self.rules, self.outcomes(player), self.shots(player))
+    }
+
+    /// The most recent salvo fired at the given player's fleet
+    pub fn incoming_salvo(&self, player: &Player) -> Option<&Vec<Shot>> {
+        self.shots(&player.opponent()).last()
+    }
+
+    fn check_turn(&self, player: &Player, proving: bool) -> Result<(), MoveError> {
+        let turn = self.turn();
+        let expected = match (player, proving) {
+            (Player::Home, false) => GameTurn::HomeShot,
+            (Player::Home, true) => GameTurn::HomeProof,
+            (Player::Away, false) => GameTurn::AwayShot,
+            (Player::Away, true) => GameTurn::AwayProof,
+        };
+        match turn {
+            GameTurn::HomeWon | GameTurn::AwayWon => Err(MoveError::GameFinished),
+            GameTurn::Corrupt => Err(MoveError::CorruptTranscript),
+            turn if turn != expected => Err(MoveError::NotYourTurn { turn }),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn fire(&mut self, player: &Player, salvo: Vec<Shot>) -> Result<(), MoveError> {
+        self.check_turn(player, false)?;
+        check_salvo(
+            &self.rules,
+            self.outcomes(player),
+            self.shots(player),
+            &salvo,
+        )?;
+        match player {
+            Player::Home => self.home_player_shots.push(salvo),
+            Player::Away => self.away_player_shots.push(salvo),
+        }
+        Ok(())
+    }
+
+    /// Records the outcomes of the latest salvo fired at the given player's fleet, one per shot
+    pub fn record_outcomes(
+        &mut self,
+        player: &Player,
+        outcomes: Vec<ShotResult>,
+    ) -> Result<(), MoveError> {
+        self.check_turn(player, true)?;
+        let expected = self.incoming_salvo(player).map(Vec::len).unwrap_or(0);
+        if outcomes.len() != expected {
+            return Err(MoveError::WrongOutcomeCount {
+                expected,
+                actual: outcomes.len(),
+            });
+        }
+        match player {
+            Player::Home => self.home_player_outcomes.push(outcomes),
+            Player::Away => self.away_player_outcomes.push(outcomes),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn miss() -> ShotResult {
+        ShotResult {
+            hit: false,
+            sunk_ship_length: None,
+        }
+    }
+
+    #[test]
+    fn moves_out_of_turn_are_rejected() {
+        let mut game = Game::new(GameRules::Classic, Player::Away);
+        assert_eq!(
+            game.fire(&Player::Home, vec![Shot { x: 0, y: 0 }]),
+            Err(MoveError::NotYourTurn {
+                turn: GameTurn::AwayShot
+            })
+        );
+        game.fire(&Player::Away, vec![Shot { x: 0, y: 0 }]).unwrap();
+        assert_eq!(
+            game.fire(&Player::Away, vec![Shot { x: 1, y: 0 }]),
+            Err(MoveError::NotYourTurn {
+                turn: GameTurn::HomeProof
+            })
+        );
+        assert_eq!(
+            game.record_outcomes(&Player::Away, vec![miss()]),
+            Err(MoveError::NotYourTurn {
+                turn: GameTurn::HomeProof
+            })
+        );
+        game.record_outcomes(&Player::Home, vec![miss()]).unwrap();
+        assert_eq!(game.turn(), GameTurn::HomeShot);
+    }
+
+    #[test]
+    fn outcomes_must_match_the_incoming_salvo() {
+        let mut game = Game::new(GameRules::Salvo, Player::Home);
+        let salvo: Vec<Shot> = (0..game.salvo_size(&Player::Home))
+            .map(|x| Shot { x, y: 0 })
+            .collect();
+        game.fire(&Player::Home, salvo.clone()).unwrap();
+        assert_eq!(game.incoming_salvo(&Player::Away), Some(&salvo));
+        assert_eq!(
+            game.record_outcomes(&Player::Away, vec![miss()]),
+            Err(MoveError::WrongOutcomeCount {
+                expected: salvo.len(),
+                actual: 1
+            })
+        );
+        game.record_outcomes(&Player::Away, vec![miss(); salvo.len()])
+            .unwrap();
+        assert_eq!(game.turn(), GameTurn::AwayShot);
+    }
+
+    #[test]
+    fn finished_games_accept_no_moves() {
+        let mut game = Game::new(GameRules::Classic, Player::Home);
+        game.home_player_shots = vec![vec![Shot { x: 0, y: 0 }]];
+        game.away_player_outcomes = vec![
+            vec![ShotResult {
+                hit: true,
+                sunk_ship_length: None,
+            }];
+            crate::constants::FLEET_SQUARES
+        ];
+        assert_eq!(game.turn(), GameTurn::HomeWon);
+        assert_eq!(
+            game.fire(&Player::Away, vec![Shot { x: 1, y: 1 }]),
+            Err(MoveError::GameFinished)
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Where the opponent's surviving ships could still be, from the public shots and outcomes
+//! alone.
+//!
+//! Every way the surviving fleet could still lie on the board is counted, and each square
+//! scores the fleet placements covering it. A fleet placement has to cover every hit no sunk
+//! ship accounts for, and may not pass through misses or sunk ships. The ships that explain the
+//! hits are placed together, so they never overlap, but the others are counted independently of
+//! each other, which keeps the count quick enough to take every move.
+
+use crate::{
+    constants::{BOARD_SIZE, SHIP_LENGTHS},
+    ship_squares, Outcome, Ship, Shot,
+};
+
+/// A score per square, indexed `heatmap[x][y]` like the fog-of-war boards
+pub type Heatmap = [[u64; BOARD_SIZE]; BOARD_SIZE];
+
+/// Every ship position and orientation, whether or not it fits on the board
+pub fn all_placements() -> impl Iterator<Item = Ship> {
+    (0..BOARD_SIZE * BOARD_SIZE * 2).map(|i| Ship {
+        x: i % BOARD_SIZE,
+        y: i / BOARD_SIZE % BOARD_SIZE,
+        horizontal: i >= BOARD_SIZE * BOARD_SIZE,
+    })
+}
+
+/// What is known about a square of the opponent's board
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SquareState {
+    Unknown,
+    Miss,
+    Hit,
+    // A hit on a ship that has since been sunk
+    Sunk,
+    // Fired at but not yet proven
+    Pending,
+}
+
+/// The opponent's board as far as one player's shots and their outcomes tell
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Sightings {
+    // Indexed `squares[x][y]`
+    pub squares: [[SquareState; BOARD_SIZE]; BOARD_SIZE],
+    pub surviving_ship_lengths: Vec<usize>,
+}
+
+impl Sightings {
+    /// Reads the board off `shots`, the shooter's salvos, and `outcomes`, the opponent's proofs
+    /// of them. A sunk ship's hits are only marked sunk when they can be told apart from the
+    /// other hits in line with the sinking shot.
+    pub fn new<O: Outcome>(shots: &[Vec<Shot>], outcomes: &[Vec<O>]) -> Self {
+        let mut sightings = Sightings {
+            squares: [[SquareState::Unknown; BOARD_SIZE]; BOARD_SIZE],
+            surviving_ship_lengths: SHIP_LENGTHS.to_vec(),
+        };
+        let mut outcomes = outcomes.iter().flatten();
+        for shot in shots.iter().flatten() {
+            let outcome = match outcomes.next() {
+                Some(outcome) => outcome,
+                None => {
+                    sightings.squares[shot.x][shot.y] = SquareState::Pending;
+                    continue;
+                }
+            };
+            sightings.squares[shot.x][shot.y] = if outcome.hit() {
+                SquareState::Hit
+            } else {
+                SquareState::Miss
+            };
+            if let Some(sunk_ship_length) = outcome.sunk_ship_length() {
+                sightings.record_sinking(shot, sunk_ship_length);
+            }
+        }
+        sightings
+    }
+
+    fn record_sinking(&mut self, shot: &Shot, sunk_ship_length: usize) {
+        if let Some(i) = self
+            .surviving_ship_lengths
+            .iter()
+            .position(|ship_length| *ship_length == sunk_ship_length)
+        {
+            self.surviving_ship_lengths.remove(i);
+        }
+        let mut candidates = all_placements()
+            .filter_map(|ship| ship_squares(&ship, sunk_ship_length))
+            .filter(|squares| {
+                squares.contains(&(shot.x, shot.y))
+                    && squares
+                        .iter()
+                        .all(|&(x, y)| self.squares[x][y] == SquareState::Hit)
+            });
+        if let (Some(squares), None) = (candidates.next(), candidates.next()) {
+            for (x, y) in squares {
+                self.squares[x][y] = SquareState::Sunk;
+            }
+        }
+    }
+
+    /// Hits that no sunk ship accounts for, so belong to a ship that is still afloat
+    pub fn unexplained_hits(&self) -> usize {
+        let hits = self
+            .squares
+            .iter()
+            .flatten()
+            .filter(|state| matches!(state, SquareState::Hit | SquareState::Sunk))
+            .count();
+        let sunk_squares =
+            SHIP_LENGTHS.iter().sum::<usize>() - self.surviving_ship_lengths.iter().sum::<usize>();
+        hits.saturating_sub(sunk_squares)
+    }
+
+    fn wreck_squares(&self) -> usize {
+        let sunk_squares =
+            SHIP_LENGTHS.iter().sum::<usize>() - self.surviving_ship_lengths.iter().sum::<usize>();
+        let marked_sunk = self
+            .squares
+            .iter()
+            .flatten()
+            .filter(|state| **state == SquareState::Sunk)
+            .count();
+        sunk_squares.saturating_sub(marked_sunk)
+    }
+
+    /// Every placement of each surviving ship that covers no miss and no square of a sunk ship,
+    /// as a mask of `square_bit`s
+    fn placements(&self) -> Vec<Vec<u128>> {
+        self.surviving_ship_lengths
+            .iter()
+            .map(|ship_length| {
+                all_placements()
+                    .filter_map(|ship| ship_squares(&ship, *ship_length))
+                    .filter(|squares| {
+                        squares.iter().all(|&(x, y)| {
+                            !matches!(self.squares[x][y], SquareState::Miss | SquareState::Sunk)
+                        })
+                    })
+                    .map(|squares| {
+                        squares
+                            .iter()
+                            .fold(0, |mask, &square| mask | square_bit(square))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn square_bit((x, y): (usize, usize)) -> u128 {
+    1 << (x * BOARD_SIZE + y)
+}
+
+fn mask_squares(mut mask: u128) -> impl Iterator<Item = (usize, usize)> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let i = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+        Some((i / BOARD_SIZE, i % BOARD_SIZE))
+    })
+}
+
+/// Ships placed so far to explain the hits, as a set of indices into the surviving ships, and
+/// the squares they and the wrecks among the hits take up
+#[derive(Clone, Copy)]
+struct Explanation {
+    ships: usize,
+    occupied: u128,
+    wrecks: u128,
+}
+
+/// Calls `explained` with every way of covering `hits[i..]` with surviving ships not yet in the
+/// explanation, leaving up to `wrecks_left` of them to ambiguous wrecks instead
+fn explain_hits<F: FnMut(Explanation)>(
+    placements: &[Vec<u128>],
+    hits: &[u128],
+    wrecks_left: usize,
+    explanation: Explanation,
+    explained: &mut F,
+) {
+    let (hit, rest) = match hits.split_first() {
+        Some(split) => split,
+        // Every wreck has to be among the hits
+        None if wrecks_left == 0 => return explained(explanation),
+        None => return,
+    };
+    if explanation.occupied & hit != 0 {
+        return explain_hits(placements, rest, wrecks_left, explanation, explained);
+    }
+    if wrecks_left > 0 {
+        let wrecked = Explanation {
+            wrecks: explanation.wrecks | hit,
+            ..explanation
+        };
+        explain_hits(placements, rest, wrecks_left - 1, wrecked, explained);
+    }
+    for (ship, ship_placements) in placements.iter().enumerate() {
+        if explanation.ships & (1 << ship) != 0 {
+            continue;
+        }
+        for placement in ship_placements {
+            if placement & hit == 0 || placement & (explanation.occupied | explanation.wrecks) != 0
+            {
+                continue;
+            }
+            let covered = Explanation {
+                ships: explanation.ships | 1 << ship,
+                occupied: explanation.occupied | placement,
+                wrecks: explanation.wrecks,
+            };
+            explain_hits(placements, rest, wrecks_left, covered, explained);
+        }
+    }
+}
+
+/// Scores every square not yet fired at by the number of legal placements of the surviving
+/// fleet that cover it. `shots` are the shooter's salvos and `outcomes` the opponent's proofs
+/// of them. Squares already fired at score zero.
+pub fn shot_heatmap<O: Outcome>(shots: &[Vec<Shot>], outcomes: &[Vec<O>]) -> Heatmap {
+    let sightings = Sightings::new(shots, outcomes);
+    let placements = sightings.placements();
+    let hits: Vec<u128> = (0..BOARD_SIZE * BOARD_SIZE)
+        .map(|i| (i / BOARD_SIZE, i % BOARD_SIZE))
+        .filter(|&(x, y)| sightings.squares[x][y] == SquareState::Hit)
+        .map(square_bit)
+        .collect();
+    let hit_mask = hits.iter().fold(0, |mask, hit| mask | hit);
+    let mut heatmap = [[0u64; BOARD_SIZE]; BOARD_SIZE];
+    let start = Explanation {
+        ships: 0,
+        occupied: 0,
+        wrecks: 0,
+    };
+    explain_hits(
+        &placements,
+        &hits,
+        sightings.wreck_squares(),
+        start,
+        &mut |explanation| {
+            // The other ships keep clear of the explaining ones and of every hit
+            let blocked = explanation.occupied | hit_mask;
+            let free: Vec<Vec<u128>> = placements
+                .iter()
+                .enumerate()
+                .filter(|(ship, _)| explanation.ships & (1 << ship) == 0)
+                .map(|(_, ship_placements)| {
+                    ship_placements
+                        .iter()
+                        .copied()
+                        .filter(|placement| placement & blocked == 0)
+                        .collect()
+                })
+                .collect();
+            let fleets: u64 = free
+                .iter()
+                .map(|ship_placements| ship_placements.len() as u64)
+                .product();
+            if fleets == 0 {
+                return;
+            }
+            for square in mask_squares(explanation.occupied) {
+                heatmap[square.0][square.1] += fleets;
+            }
+            for ship_placements in &free {
+                // Fleets with this ship in any one placement
+                let fleets_per_placement = fleets / ship_placements.len() as u64;
+                for placement in ship_placements {
+                    for (x, y) in mask_squares(*placement) {
+                        heatmap[x][y] += fleets_per_placement;
+                    }
+                }
+            }
+        },
+    );
+    for (x, column) in heatmap.iter_mut().enumerate() {
+        for (y, score) in column.iter_mut().enumerate() {
+            if sightings.squares[x][y] != SquareState::Unknown {
+                *score = 0;
+            }
+        }
+    }
+    heatmap
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ShotResult;
+
+    use super::*;
+
+    fn miss() -> ShotResult {
+        ShotResult {
+            hit: false,
+            sunk_ship_length: None,
+        }
+    }
+
+    fn hit() -> ShotResult {
+        ShotResult {
+            hit: true,
+            sunk_ship_length: None,
+        }
+    }
+
+    fn hottest(heatmap: &Heatmap) -> Vec<(usize, usize)> {
+        let max = heatmap.iter().flatten().max().copied().unwrap_or(0);
+        (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|i| (i % BOARD_SIZE, i / BOARD_SIZE))
+            .filter(|&(x, y)| heatmap[x][y] == max)
+            .collect()
+    }
+
+    fn placements_clear_of(
+        ship_length: usize,
+        blocked: &[(usize, usize)],
+    ) -> Vec<Vec<(usize, usize)>> {
+        all_placements()
+            .filter_map(|ship| ship_squares(&ship, ship_length))
+            .filter(|squares| squares.iter().all(|square| !blocked.contains(square)))
+            .collect()
+    }
+
+    /// Fleets of ships of the given lengths, each placed independently clear of `blocked`, that
+    /// cover the square
+    fn independent_fleets_covering(
+        square: (usize, usize),
+        ship_lengths: &[usize],
+        blocked: &[(usize, usize)],
+    ) -> u64 {
+        let placements: Vec<Vec<Vec<(usize, usize)>>> = ship_lengths
+            .iter()
+            .map(|ship_length| placements_clear_of(*ship_length, blocked))
+            .collect();
+        (0..placements.len())
+            .map(|ship| {
+                let covering = placements[ship]
+                    .iter()
+                    .filter(|squares| squares.contains(&square))
+                    .count() as u64;
+                let others: u64 = placements
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != ship)
+                    .map(|(_, placements)| placements.len() as u64)
+                    .product();
+                covering * others
+            })
+            .sum()
+    }
+
+    fn total(heatmap: &Heatmap) -> u128 {
+        heatmap.iter().flatten().map(|score| *score as u128).sum()
+    }
+
+    #[test]
+    fn empty_board_is_hottest_in_the_middle() {
+        let heatmap = shot_heatmap::<ShotResult>(&[], &[]);
+        assert_eq!(hottest(&heatmap), vec![(4, 4), (5, 4), (4, 5), (5, 5)]);
+        // Symmetric under reflection in either axis
+        for (x, y) in (0..BOARD_SIZE * BOARD_SIZE).map(|i| (i % BOARD_SIZE, i / BOARD_SIZE)) {
+            assert_eq!(heatmap[x][y], heatmap[BOARD_SIZE - 1 - x][y]);
+            assert_eq!(heatmap[x][y], heatmap[y][x]);
+        }
+        // A corner is covered by one horizontal and one vertical placement of each ship
+        assert_eq!(
+            heatmap[0][0],
+            independent_fleets_covering((0, 0), &SHIP_LENGTHS, &[])
+        );
+    }
+
+    #[test]
+    fn fired_at_squares_score_zero() {
+        let shots = vec![vec![Shot { x: 3, y: 3 }], vec![Shot { x: 6, y: 6 }]];
+        let heatmap = shot_heatmap(&shots, &[vec![miss()]]);
+        assert_eq!(heatmap[3][3], 0);
+        // Still waiting on its proof
+        assert_eq!(heatmap[6][6], 0);
+    }
+
+    #[test]
+    fn squares_no_ship_fits_score_zero() {
+        let shots = vec![vec![Shot { x: 1, y: 0 }], vec![Shot { x: 0, y: 1 }]];
+        let heatmap = shot_heatmap(&shots, &[vec![miss()], vec![miss()]]);
+        assert_eq!(heatmap[0][0], 0);
+    }
+
+    #[test]
+    fn hits_draw_fleets_to_their_neighbours() {
+        let empty = shot_heatmap::<ShotResult>(&[], &[]);
+        let heatmap = shot_heatmap(&[vec![Shot { x: 5, y: 5 }]], &[vec![hit()]]);
+        let neighbours = [(5, 4), (4, 5), (6, 5), (5, 6)];
+        let coolest_neighbour = neighbours.iter().map(|&(x, y)| heatmap[x][y]).min();
+        for (x, y) in (0..BOARD_SIZE * BOARD_SIZE).map(|i| (i % BOARD_SIZE, i / BOARD_SIZE)) {
+            if !neighbours.contains(&(x, y)) {
+                assert!(Some(heatmap[x][y]) < coolest_neighbour);
+            }
+        }
+        // Every neighbour takes a bigger share of the fleets than it did on the empty board
+        for (x, y) in neighbours {
+            assert!(heatmap[x][y] as u128 * total(&empty) > empty[x][y] as u128 * total(&heatmap));
+        }
+        // while a square no ship through the hit reaches takes a smaller one
+        assert!(heatmap[4][4] as u128 * total(&empty) < empty[4][4] as u128 * total(&heatmap));
+    }
+
+    #[test]
+    fn fleets_explain_every_hit() {
+        // Walled in by a miss below, the ship hit in the corner lies along the top edge
+        let shots = vec![vec![Shot { x: 0, y: 0 }], vec![Shot { x: 0, y: 1 }]];
+        let heatmap = shot_heatmap(&shots, &[vec![hit()], vec![miss()]]);
+        assert_eq!(hottest(&heatmap), vec![(1, 0)]);
+        // and with a miss beside it as well, no ship can have been hit there at all
+        let shots = vec![
+            vec![Shot { x: 0, y: 0 }],
+            vec![Shot { x: 0, y: 1 }],
+            vec![Shot { x: 1, y: 0 }],
+        ];
+        let heatmap = shot_heatmap(&shots, &[vec![hit()], vec![miss()], vec![miss()]]);
+        assert_eq!(total(&heatmap), 0);
+    }
+
+    fn sunk_destroyer() -> (Vec<Vec<Shot>>, Vec<Vec<ShotResult>>) {
+        let shots = vec![vec![Shot { x: 0, y: 0 }], vec![Shot { x: 1, y: 0 }]];
+        let outcomes = vec![
+            vec![hit()],
+            vec![ShotResult {
+                hit: true,
+                sunk_ship_length: Some(2),
+            }],
+        ];
+        (shots, outcomes)
+    }
+
+    #[test]
+    fn sunk_ships_are_not_counted() {
+        let (shots, outcomes) = sunk_destroyer();
+        let heatmap = shot_heatmap(&shots, &outcomes);
+        // The far corner is now only covered by the four surviving ships
+        let surviving_ship_lengths = [5, 4, 3, 3];
+        let wreck = [(0, 0), (1, 0)];
+        let far_corner = independent_fleets_covering(
+            (BOARD_SIZE - 1, BOARD_SIZE - 1),
+            &surviving_ship_lengths,
+            &wreck,
+        );
+        assert_eq!(heatmap[BOARD_SIZE - 1][BOARD_SIZE - 1], far_corner);
+        // and so is the square next to the wreck, which no placement may pass through
+        assert_eq!(heatmap[2][0], far_corner);
+    }
+
+    #[test]
+    fn sunk_ships_explain_their_hits() {
+        let (shots, outcomes) = sunk_destroyer();
+        let sightings = Sightings::new(&shots, &outcomes);
+        assert_eq!(sightings.squares[0][0], SquareState::Sunk);
+        assert_eq!(sightings.squares[1][0], SquareState::Sunk);
+        assert_eq!(sightings.unexplained_hits(), 0);
+
+        let sightings = Sightings::new(&shots[..1], &outcomes[..1]);
+        assert_eq!(sightings.unexplained_hits(), 1);
+    }
+
+    #[test]
+    fn ambiguous_wrecks_stay_hits() {
+        // The destroyer sunk at (1, 0) could be (0, 0)-(1, 0) or (1, 0)-(2, 0)
+        let shots = vec![
+            vec![Shot { x: 0, y: 0 }],
+            vec![Shot { x: 2, y: 0 }],
+            vec![Shot { x: 1, y: 0 }],
+        ];
+        let outcomes = vec![
+            vec![hit()],
+            vec![hit()],
+            vec![ShotResult {
+                hit: true,
+                sunk_ship_length: Some(2),
+            }],
+        ];
+        let sightings = Sightings::new(&shots, &outcomes);
+        assert_eq!(sightings.squares[1][0], SquareState::Hit);
+        assert_eq!(sightings.unexplained_hits(), 1);
+    }
+}
@@ -0,0 +1,19 @@
+//! The rules of battleships, free of any Holochain types so that the zomes, offline tools and
+//! bots all play by exactly the same ones.
+//!
+//! The integrity zome adds what only it can check on top of these: who authored each move and
+//! that every outcome is backed by a valid zero knowledge proof.
+
+pub mod board;
+pub mod constants;
+pub use board::*;
+pub mod placement;
+pub use placement::*;
+pub mod turn;
+pub use turn::*;
+pub mod game;
+pub use game::*;
+pub mod heatmap;
+pub use heatmap::*;
+pub mod tournament;
+pub use tournament::*;
@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{BOARD_SIZE, SHIP_LENGTHS},
+    Ship, Shot, ShotResult,
+};
+
+// These rules must stay in lockstep with circuits/create.circom, otherwise a deployment could
+// pass validation here but be unprovable (or vice versa).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum PlacementError {
+    WrongShipCount {
+        expected: usize,
+        actual: usize,
+    },
+    OutOfBounds {
+        ship_index: usize,
+    },
+    Collision {
+        ship_index: usize,
+        x: usize,
+        y: usize,
+    },
+}
+
+impl core::fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PlacementError::WrongShipCount { expected, .. } => {
+                write!(f, "Deployment must have {} ships", expected)
+            }
+            PlacementError::OutOfBounds { ship_index } => {
+                write!(f, "Ship {} is out of bounds", ship_index)
+            }
+            PlacementError::Collision { x, y, .. } => {
+                write!(f, "Ship deployment collision at ({}, {})", x, y)
+            }
+        }
+    }
+}
+
+/// Squares covered by a ship of the given length, or `None` if any part of it is off the board.
+pub fn ship_squares(ship: &Ship, ship_length: usize) -> Option<Vec<(usize, usize)>> {
+    if ship.x >= BOARD_SIZE || ship.y >= BOARD_SIZE {
+        return None;
+    }
+    let start = if ship.horizontal { ship.x } else { ship.y };
+    if start + ship_length > BOARD_SIZE {
+        return None;
+    }
+    Some(
+        (0..ship_length)
+            .map(|i| {
+                if ship.horizontal {
+                    (ship.x + i, ship.y)
+                } else {
+                    (ship.x, ship.y + i)
+                }
+            })
+            .collect(),
+    )
+}
+
+pub fn validate_placement(ships: &[Ship]) -> Result<(), PlacementError> {
+    if ships.len() != SHIP_LENGTHS.len() {
+        return Err(PlacementError::WrongShipCount {
+            expected: SHIP_LENGTHS.len(),
+            actual: ships.len(),
+        });
+    }
+    let mut occupied_squares = [[false; BOARD_SIZE]; BOARD_SIZE];
+    for (ship_index, (ship, ship_length)) in ships.iter().zip(SHIP_LENGTHS).enumerate() {
+        let squares =
+            ship_squares(ship, ship_length).ok_or(PlacementError::OutOfBounds { ship_index })?;
+        for (x, y) in squares {
+            if occupied_squares[x][y] {
+                return Err(PlacementError::Collision { ship_index, x, y });
+            }
+            occupied_squares[x][y] = true;
+        }
+    }
+    Ok(())
+}
+
+/// Mirrors `isMatch` in circuits/move.circom
+pub fn fleet_covers(ships: &[Ship], shot: &Shot) -> bool {
+    ships.iter().zip(SHIP_LENGTHS).any(|(ship, ship_length)| {
+        if ship.horizontal {
+            shot.y == ship.y && shot.x >= ship.x && shot.x < ship.x + ship_length
+        } else {
+            shot.x == ship.x && shot.y >= ship.y && shot.y < ship.y + ship_length
+        }
+    })
+}
+
+/// What a shot does to a fleet, given the hits already made on it. Mirrors move_sunk.circom,
+/// which reports a ship as sunk only on the shot that covers its last square.
+pub fn shot_result(ships: &[Ship], prior_hits: &[Shot], shot: &Shot) -> ShotResult {
+    let sunk_ship_length = ships
+        .iter()
+        .zip(SHIP_LENGTHS)
+        .find_map(|(ship, ship_length)| {
+            let squares = ship_squares(ship, ship_length)?;
+            if !squares.contains(&(shot.x, shot.y)) {
+                return None;
+            }
+            let sunk = squares
+                .iter()
+                .all(|&(x, y)| (x, y) == (shot.x, shot.y) || prior_hits.contains(&Shot { x, y }));
+            sunk.then_some(ship_length)
+        });
+    ShotResult {
+        hit: fleet_covers(ships, shot),
+        sunk_ship_length,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Direct transliteration of the constraints in circuits/create.circom
+    fn circuit_accepts(ships: &[Ship]) -> bool {
+        let mut pts = [[0u8; BOARD_SIZE]; BOARD_SIZE];
+        for (ship, len) in ships.iter().zip(SHIP_LENGTHS) {
+            if !(ship.x < BOARD_SIZE && ship.y < BOARD_SIZE) {
+                return false;
+            }
+            if !ship.horizontal {
+                if ship.y + len > BOARD_SIZE {
+                    return false;
+                }
+            } else if ship.x + len > BOARD_SIZE {
+                return false;
+            }
+            for l in 0..len {
+                let x = ship.x + if ship.horizontal { l } else { 0 };
+                let y = ship.y + if ship.horizontal { 0 } else { l };
+                if pts[x][y] != 0 {
+                    return false;
+                }
+                pts[x][y] = 1;
+            }
+        }
+        true
+    }
+
+    fn ship(x: usize, y: usize, horizontal: bool) -> Ship {
+        Ship { x, y, horizontal }
+    }
+
+    fn stacked_fleet() -> Vec<Ship> {
+        // Horizontal ships on rows 0, 2, 4, 6, 8
+        (0..SHIP_LENGTHS.len())
+            .map(|i| ship(0, i * 2, true))
+            .collect()
+    }
+
+    #[test]
+    fn stacked_fleet_is_valid() {
+        assert_eq!(validate_placement(&stacked_fleet()), Ok(()));
+        assert!(circuit_accepts(&stacked_fleet()));
+    }
+
+    #[test]
+    fn ship_may_touch_far_edge() {
+        for (ship_length, horizontal) in [(5, true), (5, false), (2, true), (2, false)] {
+            let start = BOARD_SIZE - ship_length;
+            let edge_ship = if horizontal {
+                ship(start, 9, true)
+            } else {
+                ship(9, start, false)
+            };
+            let squares = ship_squares(&edge_ship, ship_length).unwrap();
+            assert_eq!(squares.len(), ship_length);
+            assert!(squares
+                .iter()
+                .all(|&(x, y)| x < BOARD_SIZE && y < BOARD_SIZE));
+        }
+    }
+
+    #[test]
+    fn ship_overhanging_edge_is_out_of_bounds() {
+        assert_eq!(ship_squares(&ship(6, 0, true), 5), None);
+        assert_eq!(ship_squares(&ship(0, 9, false), 2), None);
+        assert_eq!(ship_squares(&ship(BOARD_SIZE, 0, false), 2), None);
+        assert_eq!(ship_squares(&ship(0, usize::MAX, true), 2), None);
+    }
+
+    #[test]
+    fn out_of_bounds_deployment_is_rejected_not_panicking() {
+        let mut fleet = stacked_fleet();
+        fleet[4] = ship(9, 9, true);
+        assert_eq!(
+            validate_placement(&fleet),
+            Err(PlacementError::OutOfBounds { ship_index: 4 })
+        );
+        fleet[4] = ship(usize::MAX, usize::MAX, false);
+        assert_eq!(
+            validate_placement(&fleet),
+            Err(PlacementError::OutOfBounds { ship_index: 4 })
+        );
+    }
+
+    #[test]
+    fn collisions_are_rejected() {
+        let mut fleet = stacked_fleet();
+        fleet[1] = ship(2, 0, false);
+        assert_eq!(
+            validate_placement(&fleet),
+            Err(PlacementError::Collision {
+                ship_index: 1,
+                x: 2,
+                y: 0
+            })
+        );
+    }
+
+    #[test]
+    fn wrong_ship_count_is_rejected() {
+        let mut fleet = stacked_fleet();
+        fleet.pop();
+        assert_eq!(
+            validate_placement(&fleet),
+            Err(PlacementError::WrongShipCount {
+                expected: 5,
+                actual: 4
+            })
+        );
+    }
+
+    #[test]
+    fn rules_agree_with_circuit_for_every_position() {
+        // Move each ship in turn through every position on (and just off) the board, in both
+        // orientations, keeping the rest of the fleet fixed. Covers all edge and overlap cases.
+        for ship_index in 0..SHIP_LENGTHS.len() {
+            for horizontal in [true, false] {
+                for x in 0..BOARD_SIZE + 2 {
+                    for y in 0..BOARD_SIZE + 2 {
+                        let mut fleet = stacked_fleet();
+                        fleet[ship_index] = ship(x, y, horizontal);
+                        assert_eq!(
+                            validate_placement(&fleet).is_ok(),
+                            circuit_accepts(&fleet),
+                            "disagreement for ship {} at ({}, {}) horizontal={}",
+                            ship_index,
+                            x,
+                            y,
+                            horizontal
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fleet_covers_matches_ship_squares() {
+        let fleet = stacked_fleet();
+        for x in 0..BOARD_SIZE {
+            for y in 0..BOARD_SIZE {
+                let occupied = fleet
+                    .iter()
+                    .zip(SHIP_LENGTHS)
+                    .any(|(s, l)| ship_squares(s, l).unwrap().contains(&(x, y)));
+                assert_eq!(fleet_covers(&fleet, &Shot { x, y }), occupied);
+            }
+        }
+    }
+
+    #[test]
+    fn shot_result_reports_sunk_ship_on_last_square() {
+        let fleet = stacked_fleet();
+        // The destroyer is the last ship, horizontal on row 8
+        let destroyer = [Shot { x: 0, y: 8 }, Shot { x: 1, y: 8 }];
+        assert_eq!(
+            shot_result(&fleet, &[], &destroyer[0]),
+            ShotResult {
+                hit: true,
+                sunk_ship_length: None
+            }
+        );
+        assert_eq!(
+            shot_result(&fleet, &destroyer[..1], &destroyer[1]),
+            ShotResult {
+                hit: true,
+                sunk_ship_length: Some(2)
+            }
+        );
+        assert_eq!(
+            shot_result(&fleet, &destroyer, &Shot { x: 5, y: 9 }),
+            ShotResult {
+                hit: false,
+                sunk_ship_length: None
+            }
+        );
+    }
+}
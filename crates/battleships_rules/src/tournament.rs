@@ -0,0 +1,261 @@
+//! Pairings and standings for tournaments, generic over how players are identified so the zomes
+//! can use agent keys and tests plain numbers.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum TournamentFormat {
+    // Losers drop out, winners are paired again until one is left
+    SingleElimination,
+    // Everyone plays everyone else once
+    RoundRobin,
+}
+
+/// Two players due to play each other, `home` inviting `away`. A pairing without an away player
+/// is a bye, which `home` wins without playing.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Pairing<P> {
+    pub home: P,
+    pub away: Option<P>,
+}
+
+impl<P: PartialEq> Pairing<P> {
+    pub fn is_bye(&self) -> bool {
+        self.away.is_none()
+    }
+
+    pub fn involves(&self, player: &P) -> bool {
+        &self.home == player || self.away.as_ref() == Some(player)
+    }
+}
+
+/// Number of rounds a round robin between this many entrants takes
+pub fn round_robin_rounds(entrants: usize) -> usize {
+    if entrants < 2 {
+        0
+    } else {
+        entrants + entrants % 2 - 1
+    }
+}
+
+/// The circle method: the first seat stays put while everyone else moves round one seat per
+/// round. With an odd number of entrants the empty seat is a bye.
+pub fn round_robin_pairings<P: Clone>(entrants: &[P], round: usize) -> Vec<Pairing<P>> {
+    let mut seats: Vec<Option<P>> = entrants.iter().cloned().map(Some).collect();
+    if seats.len() % 2 == 1 {
+        seats.push(None);
+    }
+    let seat_count = seats.len();
+    if seat_count < 2 {
+        return Vec::new();
+    }
+    seats[1..].rotate_right(round % (seat_count - 1));
+    (0..seat_count / 2)
+        .filter_map(|i| {
+            match (seats[i].clone(), seats[seat_count - 1 - i].clone()) {
+                // Swap sides every other round so nobody is always home
+                (Some(a), Some(b)) if (round + i) % 2 == 1 => Some(Pairing {
+                    home: b,
+                    away: Some(a),
+                }),
+                (Some(a), Some(b)) => Some(Pairing {
+                    home: a,
+                    away: Some(b),
+                }),
+                (Some(player), None) | (None, Some(player)) => Some(Pairing {
+                    home: player,
+                    away: None,
+                }),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+/// Pairs players off in order, the last one getting a bye if there is an odd number of them
+pub fn knockout_pairings<P: Clone>(players: &[P]) -> Vec<Pairing<P>> {
+    if players.len() < 2 {
+        return Vec::new();
+    }
+    players
+        .chunks(2)
+        .map(|pair| Pairing {
+            home: pair[0].clone(),
+            away: pair.get(1).cloned(),
+        })
+        .collect()
+}
+
+/// Pairings for the given round, counting from zero. `previous_winners` are the winners of the
+/// previous round's pairings in order, byes included, and are only needed for single
+/// elimination. No pairings means the tournament is over.
+pub fn tournament_pairings<P: Clone>(
+    format: &TournamentFormat,
+    entrants: &[P],
+    round: usize,
+    previous_winners: &[P],
+) -> Vec<Pairing<P>> {
+    match format {
+        TournamentFormat::RoundRobin => {
+            if round < round_robin_rounds(entrants.len()) {
+                round_robin_pairings(entrants, round)
+            } else {
+                Vec::new()
+            }
+        }
+        TournamentFormat::SingleElimination => {
+            if round == 0 {
+                knockout_pairings(entrants)
+            } else {
+                knockout_pairings(previous_winners)
+            }
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Standing<P> {
+    pub player: P,
+    pub wins: usize,
+    pub losses: usize,
+}
+
+/// Wins and losses of every entrant from `(winner, loser)` results, most wins first. Ties keep
+/// the entrants' order. Byes are not games, so they count for neither.
+pub fn tournament_standings<P: Clone + PartialEq>(
+    entrants: &[P],
+    results: &[(P, P)],
+) -> Vec<Standing<P>> {
+    let mut standings: Vec<Standing<P>> = entrants
+        .iter()
+        .map(|player| Standing {
+            player: player.clone(),
+            wins: results
+                .iter()
+                .filter(|(winner, _)| winner == player)
+                .count(),
+            losses: results.iter().filter(|(_, loser)| loser == player).count(),
+        })
+        .collect();
+    standings.sort_by(|a, b| b.wins.cmp(&a.wins).then(a.losses.cmp(&b.losses)));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays every round out with the lower numbered player winning, returning the pairings
+    fn play_out(format: &TournamentFormat, entrants: &[usize]) -> Vec<Vec<Pairing<usize>>> {
+        let mut rounds = Vec::new();
+        let mut winners = Vec::new();
+        loop {
+            let pairings = tournament_pairings(format, entrants, rounds.len(), &winners);
+            if pairings.is_empty() {
+                return rounds;
+            }
+            winners = pairings
+                .iter()
+                .map(|pairing| {
+                    pairing
+                        .away
+                        .map_or(pairing.home, |away| away.min(pairing.home))
+                })
+                .collect();
+            rounds.push(pairings);
+        }
+    }
+
+    #[test]
+    fn round_robin_pairs_everyone_exactly_once() {
+        for entrant_count in 2..10 {
+            let entrants: Vec<usize> = (0..entrant_count).collect();
+            let rounds = play_out(&TournamentFormat::RoundRobin, &entrants);
+            assert_eq!(rounds.len(), round_robin_rounds(entrant_count));
+            for a in 0..entrant_count {
+                for b in a + 1..entrant_count {
+                    let meetings = rounds
+                        .iter()
+                        .flatten()
+                        .filter(|pairing| pairing.involves(&a) && pairing.involves(&b))
+                        .count();
+                    assert_eq!(meetings, 1, "{} and {} of {}", a, b, entrant_count);
+                }
+            }
+            for pairings in &rounds {
+                for player in &entrants {
+                    let games = pairings.iter().filter(|p| p.involves(player)).count();
+                    assert_eq!(games, 1, "Player {} plays once a round", player);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn round_robin_gives_one_bye_a_round_with_odd_entrants() {
+        let rounds = play_out(&TournamentFormat::RoundRobin, &[0, 1, 2, 3, 4]);
+        for pairings in &rounds {
+            assert_eq!(pairings.iter().filter(|p| p.is_bye()).count(), 1);
+        }
+        let mut byes: Vec<usize> = rounds
+            .iter()
+            .flatten()
+            .filter(|pairing| pairing.is_bye())
+            .map(|pairing| pairing.home)
+            .collect();
+        byes.sort();
+        assert_eq!(byes, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn single_elimination_halves_the_field_each_round() {
+        let entrants: Vec<usize> = (0..8).collect();
+        let rounds = play_out(&TournamentFormat::SingleElimination, &entrants);
+        let sizes: Vec<usize> = rounds.iter().map(Vec::len).collect();
+        assert_eq!(sizes, vec![4, 2, 1]);
+        assert_eq!(
+            rounds[2],
+            vec![Pairing {
+                home: 0,
+                away: Some(4)
+            }]
+        );
+    }
+
+    #[test]
+    fn single_elimination_advances_byes() {
+        let rounds = play_out(&TournamentFormat::SingleElimination, &[0, 1, 2, 3, 4]);
+        assert_eq!(
+            rounds[0].last(),
+            Some(&Pairing {
+                home: 4,
+                away: None
+            })
+        );
+        // 5 -> 3 -> 2 -> 1
+        assert_eq!(rounds.len(), 3);
+        assert!(rounds[1].iter().any(|pairing| pairing.involves(&4)));
+    }
+
+    #[test]
+    fn too_few_entrants_have_no_rounds() {
+        for format in [
+            TournamentFormat::SingleElimination,
+            TournamentFormat::RoundRobin,
+        ] {
+            assert!(tournament_pairings(&format, &[7], 0, &[]).is_empty());
+            assert!(tournament_pairings::<usize>(&format, &[], 0, &[]).is_empty());
+        }
+    }
+
+    #[test]
+    fn standings_rank_by_wins_then_losses() {
+        let standings = tournament_standings(&[0, 1, 2, 3], &[(2, 0), (2, 1), (1, 0)]);
+        let order: Vec<(usize, usize, usize)> = standings
+            .iter()
+            .map(|standing| (standing.player, standing.wins, standing.losses))
+            .collect();
+        assert_eq!(order, vec![(2, 2, 0), (1, 1, 1), (3, 0, 0), (0, 0, 2)]);
+    }
+}
@@ -0,0 +1,469 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{BOARD_SIZE, FLEET_SQUARES, SHIP_LENGTHS},
+    first_invalid_shot, Shot,
+};
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, Default)]
+#[serde(tag = "type")]
+pub enum GameRules {
+    // One shot per turn
+    #[default]
+    Classic,
+    // One shot per surviving ship per turn
+    Salvo,
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum Player {
+    Home,
+    Away,
+}
+
+impl Player {
+    pub fn opponent(&self) -> Player {
+        match self {
+            Player::Home => Player::Away,
+            Player::Away => Player::Home,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum GameTurn {
+    AwayShot,
+    HomeProof,
+    HomeShot,
+    AwayProof,
+    HomeWon,
+    AwayWon,
+    Corrupt,
+}
+
+impl GameTurn {
+    /// The player who has to act next, if the game is still going
+    pub fn player(&self) -> Option<Player> {
+        match self {
+            GameTurn::HomeShot | GameTurn::HomeProof => Some(Player::Home),
+            GameTurn::AwayShot | GameTurn::AwayProof => Some(Player::Away),
+            _ => None,
+        }
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        match self {
+            GameTurn::HomeWon => Some(Player::Home),
+            GameTurn::AwayWon => Some(Player::Away),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.winner().is_some()
+    }
+}
+
+/// The proven result of a single shot
+pub trait Outcome {
+    fn hit(&self) -> bool;
+    fn sunk_ship_length(&self) -> Option<usize>;
+}
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct ShotResult {
+    pub hit: bool,
+    pub sunk_ship_length: Option<usize>,
+}
+
+impl Outcome for ShotResult {
+    fn hit(&self) -> bool {
+        self.hit
+    }
+
+    fn sunk_ship_length(&self) -> Option<usize> {
+        self.sunk_ship_length
+    }
+}
+
+/// Shots and outcomes of a game, batched per turn. Outcomes are the ones a player proves about
+/// their own fleet, so hits on the home fleet are proven by the home player and vice versa.
+pub trait Transcript {
+    type Outcome: Outcome;
+
+    fn first_player(&self) -> &Player;
+    fn shots(&self, player: &Player) -> &[Vec<Shot>];
+    fn outcomes(&self, player: &Player) -> &[Vec<Self::Outcome>];
+}
+
+pub fn count_hits<O: Outcome>(shot_outcomes: &[Vec<O>]) -> usize {
+    shot_outcomes
+        .iter()
+        .flatten()
+        .filter(|shot_outcome| shot_outcome.hit())
+        .count()
+}
+
+/// Lengths of the ships proven sunk by the given outcomes, in the order they were sunk
+pub fn sunk_ship_lengths<O: Outcome>(shot_outcomes: &[Vec<O>]) -> Vec<usize> {
+    shot_outcomes
+        .iter()
+        .flatten()
+        .filter_map(|shot_outcome| shot_outcome.sunk_ship_length())
+        .collect()
+}
+
+/// Number of shots the shooter must fire in their next salvo. own_shot_outcomes are the
+/// shooter's proofs about their own fleet, existing_salvos the shooter's previous salvos.
+pub fn allowed_salvo_size<O: Outcome>(
+    rules: &GameRules,
+    own_shot_outcomes: &[Vec<O>],
+    existing_salvos: &[Vec<Shot>],
+) -> usize {
+    match rules {
+        GameRules::Classic => 1,
+        GameRules::Salvo => {
+            let surviving_ships = SHIP_LENGTHS
+                .len()
+                .saturating_sub(sunk_ship_lengths(own_shot_outcomes).len());
+            let unshot_squares = (BOARD_SIZE * BOARD_SIZE)
+                .saturating_sub(existing_salvos.iter().map(Vec::len).sum::<usize>());
+            std::cmp::min(surviving_ships, unshot_squares)
+        }
+    }
+}
+
+pub fn game_turn<T: Transcript>(transcript: &T) -> GameTurn {
+    // Infer who's turn it should be
+    // Sequence of permitted steps is:
+    // 1. first player fires shot (included in create too)
+    // 2. second player adds hit/miss proof
+    // 3. second player fires shot
+    // 4. first player adds hit/miss proof
+    // 5. repeat steps 1-4 until game ends (17 hits made by one player)
+    // Therefore the step (1-4) can be inferred from shot & proof vec lengths
+    if count_hits(transcript.outcomes(&Player::Home)) >= FLEET_SQUARES {
+        return GameTurn::AwayWon;
+    }
+    if count_hits(transcript.outcomes(&Player::Away)) >= FLEET_SQUARES {
+        return GameTurn::HomeWon;
+    }
+    let home_shot_len = transcript.shots(&Player::Home).len();
+    let away_shot_len = transcript.shots(&Player::Away).len();
+    let home_proof_len = transcript.outcomes(&Player::Home).len();
+    let away_proof_len = transcript.outcomes(&Player::Away).len();
+    let steps = match transcript.first_player() {
+        Player::Away => [
+            (away_shot_len, GameTurn::AwayShot),
+            (home_proof_len, GameTurn::HomeProof),
+            (home_shot_len, GameTurn::HomeShot),
+            (away_proof_len, GameTurn::AwayProof),
+        ],
+        Player::Home => [
+            (home_shot_len, GameTurn::HomeShot),
+            (away_proof_len, GameTurn::AwayProof),
+            (away_shot_len, GameTurn::AwayShot),
+            (home_proof_len, GameTurn::HomeProof),
+        ],
+    };
+    let baseline = std::cmp::min(
+        std::cmp::min(home_shot_len, away_shot_len),
+        std::cmp::min(home_proof_len, away_proof_len),
+    );
+    match (
+        steps[0].0 - baseline,
+        steps[1].0 - baseline,
+        steps[2].0 - baseline,
+        steps[3].0 - baseline,
+    ) {
+        // All lists are the same length therefore we're at the beginning of the cycle.
+        (0, 0, 0, 0) => steps[0].1.clone(),
+        (1, 0, 0, 0) => steps[1].1.clone(),
+        (1, 1, 0, 0) => steps[2].1.clone(),
+        (1, 1, 1, 0) => steps[3].1.clone(),
+        // This should never happen
+        _ => GameTurn::Corrupt,
+    }
+}
+
+/// Why a move breaks the rules
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum MoveError {
+    NotYourTurn { turn: GameTurn },
+    GameFinished,
+    CorruptTranscript,
+    WrongSalvoSize { expected: usize, actual: usize },
+    InvalidShot { shot: Shot },
+    WrongOutcomeCount { expected: usize, actual: usize },
+}
+
+impl core::fmt::Display for MoveError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MoveError::NotYourTurn { turn } => write!(f, "Not your turn, it's {:?}", turn),
+            MoveError::GameFinished => write!(f, "Game has already finished"),
+            MoveError::CorruptTranscript => write!(f, "Transcript is corrupt"),
+            MoveError::WrongSalvoSize { expected, actual } => {
+                write!(f, "Salvo must have {} shots but has {}", expected, actual)
+            }
+            MoveError::InvalidShot { shot } => write!(
+                f,
+                "Shot at ({}, {}) is off the board or already taken",
+                shot.x, shot.y
+            ),
+            MoveError::WrongOutcomeCount { expected, actual } => {
+                write!(f, "Expected {} outcomes but found {}", expected, actual)
+            }
+        }
+    }
+}
+
+/// Checks a salvo the shooter is about to add after their existing_salvos
+pub fn check_salvo<O: Outcome>(
+    rules: &GameRules,
+    own_shot_outcomes: &[Vec<O>],
+    existing_salvos: &[Vec<Shot>],
+    salvo: &[Shot],
+) -> Result<(), MoveError> {
+    let allowed_shots = allowed_salvo_size(rules, own_shot_outcomes, existing_salvos);
+    if salvo.len() != allowed_shots {
+        return Err(MoveError::WrongSalvoSize {
+            expected: allowed_shots,
+            actual: salvo.len(),
+        });
+    }
+    if let Some(shot) = first_invalid_shot(existing_salvos, salvo) {
+        return Err(MoveError::InvalidShot { shot: shot.clone() });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counts {
+        first_player: Player,
+        home_player_shots: Vec<Vec<Shot>>,
+        away_player_shots: Vec<Vec<Shot>>,
+        home_player_outcomes: Vec<Vec<ShotResult>>,
+        away_player_outcomes: Vec<Vec<ShotResult>>,
+    }
+
+    impl Transcript for Counts {
+        type Outcome = ShotResult;
+
+        fn first_player(&self) -> &Player {
+            &self.first_player
+        }
+
+        fn shots(&self, player: &Player) -> &[Vec<Shot>] {
+            match player {
+                Player::Home => &self.home_player_shots,
+                Player::Away => &self.away_player_shots,
+            }
+        }
+
+        fn outcomes(&self, player: &Player) -> &[Vec<ShotResult>] {
+            match player {
+                Player::Home => &self.home_player_outcomes,
+                Player::Away => &self.away_player_outcomes,
+            }
+        }
+    }
+
+    fn miss() -> ShotResult {
+        ShotResult {
+            hit: false,
+            sunk_ship_length: None,
+        }
+    }
+
+    fn hit(sunk_ship_length: Option<usize>) -> ShotResult {
+        ShotResult {
+            hit: true,
+            sunk_ship_length,
+        }
+    }
+
+    // Transcript with the given number of salvos and outcomes per player, each a single miss
+    fn counts(first_player: Player, lengths: [usize; 4]) -> Counts {
+        let salvos = |n| vec![vec![Shot { x: 0, y: 0 }]; n];
+        let outcomes = |n| vec![vec![miss()]; n];
+        Counts {
+            first_player,
+            home_player_shots: salvos(lengths[0]),
+            away_player_shots: salvos(lengths[1]),
+            home_player_outcomes: outcomes(lengths[2]),
+            away_player_outcomes: outcomes(lengths[3]),
+        }
+    }
+
+    #[test]
+    fn turns_cycle_from_home_player() {
+        let cycle = [
+            ([0, 0, 0, 0], GameTurn::HomeShot),
+            ([1, 0, 0, 0], GameTurn::AwayProof),
+            ([1, 0, 0, 1], GameTurn::AwayShot),
+            ([1, 1, 0, 1], GameTurn::HomeProof),
+            ([1, 1, 1, 1], GameTurn::HomeShot),
+            ([2, 1, 1, 1], GameTurn::AwayProof),
+        ];
+        for (lengths, turn) in cycle {
+            assert_eq!(game_turn(&counts(Player::Home, lengths)), turn);
+        }
+    }
+
+    #[test]
+    fn turns_cycle_from_away_player() {
+        let cycle = [
+            ([0, 0, 0, 0], GameTurn::AwayShot),
+            ([0, 1, 0, 0], GameTurn::HomeProof),
+            ([0, 1, 1, 0], GameTurn::HomeShot),
+            ([1, 1, 1, 0], GameTurn::AwayProof),
+            ([1, 1, 1, 1], GameTurn::AwayShot),
+        ];
+        for (lengths, turn) in cycle {
+            assert_eq!(game_turn(&counts(Player::Away, lengths)), turn);
+        }
+    }
+
+    #[test]
+    fn out_of_sequence_transcripts_are_corrupt() {
+        for lengths in [[0, 1, 0, 0], [1, 1, 0, 0], [2, 0, 0, 0], [1, 0, 1, 1]] {
+            assert_eq!(
+                game_turn(&counts(Player::Home, lengths)),
+                GameTurn::Corrupt,
+                "{:?}",
+                lengths
+            );
+        }
+    }
+
+    #[test]
+    fn sinking_the_whole_fleet_wins() {
+        let mut transcript = counts(Player::Home, [1, 1, 1, 1]);
+        transcript.away_player_outcomes = vec![vec![hit(None)]; FLEET_SQUARES];
+        assert_eq!(game_turn(&transcript), GameTurn::HomeWon);
+        assert_eq!(GameTurn::HomeWon.winner(), Some(Player::Home));
+
+        transcript.away_player_outcomes.pop();
+        assert_ne!(game_turn(&transcript), GameTurn::HomeWon);
+
+        transcript.home_player_outcomes = vec![vec![hit(None)]; FLEET_SQUARES];
+        assert_eq!(game_turn(&transcript), GameTurn::AwayWon);
+    }
+
+    #[test]
+    fn only_finished_turns_have_a_winner() {
+        for turn in [
+            GameTurn::HomeShot,
+            GameTurn::AwayShot,
+            GameTurn::HomeProof,
+            GameTurn::AwayProof,
+            GameTurn::Corrupt,
+        ] {
+            assert_eq!(turn.winner(), None);
+            assert!(!turn.is_finished());
+        }
+        assert_eq!(GameTurn::HomeProof.player(), Some(Player::Home));
+        assert_eq!(GameTurn::AwayShot.player(), Some(Player::Away));
+        assert_eq!(GameTurn::AwayWon.player(), None);
+    }
+
+    #[test]
+    fn classic_salvos_are_single_shots() {
+        let sunk = vec![vec![hit(Some(5)), hit(Some(4))]];
+        assert_eq!(allowed_salvo_size(&GameRules::Classic, &sunk, &[]), 1);
+    }
+
+    #[test]
+    fn salvo_size_shrinks_with_sunk_ships() {
+        let no_outcomes: Vec<Vec<ShotResult>> = vec![];
+        assert_eq!(
+            allowed_salvo_size(&GameRules::Salvo, &no_outcomes, &[]),
+            SHIP_LENGTHS.len()
+        );
+        let outcomes = vec![vec![hit(None), hit(Some(2))], vec![miss(), hit(Some(3))]];
+        assert_eq!(sunk_ship_lengths(&outcomes), vec![2, 3]);
+        assert_eq!(
+            allowed_salvo_size(&GameRules::Salvo, &outcomes, &[]),
+            SHIP_LENGTHS.len() - 2
+        );
+    }
+
+    #[test]
+    fn salvo_size_is_capped_by_unshot_squares() {
+        let no_outcomes: Vec<Vec<ShotResult>> = vec![];
+        let taken: Vec<Vec<Shot>> = (0..BOARD_SIZE * BOARD_SIZE - 2)
+            .map(|i| {
+                vec![Shot {
+                    x: i % BOARD_SIZE,
+                    y: i / BOARD_SIZE,
+                }]
+            })
+            .collect();
+        assert_eq!(
+            allowed_salvo_size(&GameRules::Salvo, &no_outcomes, &taken),
+            2
+        );
+    }
+
+    #[test]
+    fn salvos_are_checked_for_size_and_repeats() {
+        let no_outcomes: Vec<Vec<ShotResult>> = vec![];
+        let existing = vec![vec![Shot { x: 1, y: 1 }]];
+        assert_eq!(
+            check_salvo(
+                &GameRules::Classic,
+                &no_outcomes,
+                &existing,
+                &[Shot { x: 2, y: 2 }]
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            check_salvo(&GameRules::Classic, &no_outcomes, &existing, &[]),
+            Err(MoveError::WrongSalvoSize {
+                expected: 1,
+                actual: 0
+            })
+        );
+        assert_eq!(
+            check_salvo(
+                &GameRules::Classic,
+                &no_outcomes,
+                &existing,
+                &[Shot { x: 1, y: 1 }]
+            ),
+            Err(MoveError::InvalidShot {
+                shot: Shot { x: 1, y: 1 }
+            })
+        );
+        let off_board = Shot {
+            x: BOARD_SIZE,
+            y: 0,
+        };
+        let salvo = [
+            Shot { x: 3, y: 3 },
+            Shot { x: 4, y: 4 },
+            off_board.clone(),
+            Shot { x: 0, y: 0 },
+            Shot { x: 0, y: 0 },
+        ];
+        assert_eq!(
+            check_salvo(&GameRules::Salvo, &no_outcomes, &existing, &salvo),
+            Err(MoveError::InvalidShot { shot: off_board })
+        );
+        assert_eq!(
+            check_salvo(&GameRules::Salvo, &no_outcomes, &existing, &salvo[3..]),
+            Err(MoveError::WrongSalvoSize {
+                expected: SHIP_LENGTHS.len(),
+                actual: 2
+            })
+        );
+    }
+}
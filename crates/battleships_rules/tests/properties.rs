@@ -0,0 +1,203 @@
+use battleships_rules::{
+    constants::{BOARD_SIZE, FLEET_SQUARES, SHIP_LENGTHS},
+    first_invalid_shot, hits_bitmask, ship_squares, shot_result, sunk_ship_lengths,
+    validate_placement, Game, GameRules, GameTurn, Player, Ship, Shot, Transcript,
+};
+use proptest::prelude::*;
+
+// Places each ship at the first free position at or after a random starting point, so every
+// generated fleet is legal without having to reject any
+fn any_fleet() -> impl Strategy<Value = Vec<Ship>> {
+    let positions = 2 * BOARD_SIZE * BOARD_SIZE;
+    prop::collection::vec(0..positions, SHIP_LENGTHS.len()).prop_map(move |starts| {
+        let mut fleet: Vec<Ship> = Vec::new();
+        for (start, ship_length) in starts.into_iter().zip(SHIP_LENGTHS) {
+            let ship = (0..positions)
+                .map(|offset| {
+                    let position = (start + offset) % positions;
+                    Ship {
+                        x: position % BOARD_SIZE,
+                        y: position / BOARD_SIZE % BOARD_SIZE,
+                        horizontal: position >= BOARD_SIZE * BOARD_SIZE,
+                    }
+                })
+                .find(|ship| {
+                    ship_squares(ship, ship_length).is_some_and(|squares| {
+                        squares.iter().all(|&(x, y)| {
+                            !fleet.iter().zip(SHIP_LENGTHS).any(|(placed, length)| {
+                                ship_squares(placed, length).unwrap().contains(&(x, y))
+                            })
+                        })
+                    })
+                })
+                .expect("a fleet always fits on the board");
+            fleet.push(ship);
+        }
+        fleet
+    })
+}
+
+// Every square of the board in a random order
+fn any_firing_order() -> impl Strategy<Value = Vec<Shot>> {
+    Just(
+        (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|i| Shot {
+                x: i % BOARD_SIZE,
+                y: i / BOARD_SIZE,
+            })
+            .collect::<Vec<Shot>>(),
+    )
+    .prop_shuffle()
+}
+
+fn any_player() -> impl Strategy<Value = Player> {
+    prop_oneof![Just(Player::Home), Just(Player::Away)]
+}
+
+fn any_rules() -> impl Strategy<Value = GameRules> {
+    prop_oneof![Just(GameRules::Classic), Just(GameRules::Salvo)]
+}
+
+proptest! {
+    #[test]
+    fn generated_fleets_are_legal(fleet in any_fleet()) {
+        prop_assert_eq!(validate_placement(&fleet), Ok(()));
+        let covered: usize = fleet
+            .iter()
+            .zip(SHIP_LENGTHS)
+            .map(|(ship, ship_length)| ship_squares(ship, ship_length).unwrap().len())
+            .sum();
+        prop_assert_eq!(covered, FLEET_SQUARES);
+    }
+
+    #[test]
+    fn moved_ships_stay_legal_only_without_overlap(
+        fleet in any_fleet(),
+        ship_index in 0..SHIP_LENGTHS.len(),
+        x in 0..BOARD_SIZE + 2,
+        y in 0..BOARD_SIZE + 2,
+        horizontal in any::<bool>(),
+    ) {
+        let mut moved = fleet.clone();
+        moved[ship_index] = Ship { x, y, horizontal };
+        let fits = ship_squares(&moved[ship_index], SHIP_LENGTHS[ship_index]).is_some_and(|squares| {
+            squares.iter().all(|&(x, y)| {
+                !moved
+                    .iter()
+                    .zip(SHIP_LENGTHS)
+                    .enumerate()
+                    .filter(|(i, _)| *i != ship_index)
+                    .any(|(_, (ship, length))| ship_squares(ship, length).unwrap().contains(&(x, y)))
+            })
+        });
+        prop_assert_eq!(validate_placement(&moved).is_ok(), fits);
+    }
+
+    #[test]
+    fn shooting_every_square_sinks_every_ship_once(
+        fleet in any_fleet(),
+        firing_order in any_firing_order(),
+    ) {
+        let mut hits = Vec::new();
+        let mut sunk = Vec::new();
+        for shot in &firing_order {
+            let result = shot_result(&fleet, &hits, shot);
+            prop_assert!(result.hit || result.sunk_ship_length.is_none());
+            if let Some(sunk_ship_length) = result.sunk_ship_length {
+                sunk.push(sunk_ship_length);
+            }
+            if result.hit {
+                hits.push(shot.clone());
+            }
+        }
+        prop_assert_eq!(hits.len(), FLEET_SQUARES);
+        sunk.sort_unstable();
+        let mut ship_lengths = SHIP_LENGTHS.to_vec();
+        ship_lengths.sort_unstable();
+        prop_assert_eq!(sunk, ship_lengths);
+    }
+
+    #[test]
+    fn distinct_shots_on_the_board_are_valid(
+        firing_order in any_firing_order(),
+        split in 0..BOARD_SIZE * BOARD_SIZE,
+        salvo_size in 1..=SHIP_LENGTHS.len(),
+    ) {
+        let (existing, rest) = firing_order.split_at(split);
+        let existing: Vec<Vec<Shot>> = existing.iter().map(|shot| vec![shot.clone()]).collect();
+        let salvo = &rest[..salvo_size.min(rest.len())];
+        prop_assert_eq!(first_invalid_shot(&existing, salvo), None);
+        prop_assert_eq!(
+            hits_bitmask(salvo).count_ones() as usize,
+            salvo.len()
+        );
+        if let Some(repeat) = existing.first() {
+            let mut salvo = salvo.to_vec();
+            salvo.push(repeat[0].clone());
+            prop_assert_eq!(first_invalid_shot(&existing, &salvo), Some(&repeat[0]));
+        }
+    }
+
+    // Plays a whole game between two fleets with shots in a random order, checking the turn
+    // state machine at every step
+    #[test]
+    fn games_always_finish_with_the_right_winner(
+        rules in any_rules(),
+        first_player in any_player(),
+        home_fleet in any_fleet(),
+        away_fleet in any_fleet(),
+        home_firing_order in any_firing_order(),
+        away_firing_order in any_firing_order(),
+    ) {
+        let mut game = Game::new(rules.clone(), first_player.clone());
+        let mut firing_orders = [home_firing_order.into_iter(), away_firing_order.into_iter()];
+        let mut hits: [Vec<Shot>; 2] = [Vec::new(), Vec::new()];
+        let index = |player: &Player| match player {
+            Player::Home => 0,
+            Player::Away => 1,
+        };
+        let fleet = |player: &Player| match player {
+            Player::Home => &home_fleet,
+            Player::Away => &away_fleet,
+        };
+        let mut shooter = first_player.clone();
+        let mut moves = 0;
+        while !game.turn().is_finished() {
+            moves += 1;
+            prop_assert!(moves <= 4 * BOARD_SIZE * BOARD_SIZE, "game never finished");
+            let defender = shooter.opponent();
+
+            prop_assert_eq!(game.turn().player(), Some(shooter.clone()));
+            let salvo_size = game.salvo_size(&shooter);
+            if rules == GameRules::Classic {
+                prop_assert_eq!(salvo_size, 1);
+            } else {
+                let surviving = SHIP_LENGTHS.len() - sunk_ship_lengths(game.outcomes(&shooter)).len();
+                let fired: usize = game.shots(&shooter).iter().map(Vec::len).sum();
+                prop_assert_eq!(salvo_size, surviving.min(BOARD_SIZE * BOARD_SIZE - fired));
+            }
+            prop_assert!(game.fire(&defender, vec![]).is_err());
+            let salvo: Vec<Shot> = firing_orders[index(&shooter)].by_ref().take(salvo_size).collect();
+            game.fire(&shooter, salvo.clone()).unwrap();
+
+            prop_assert_eq!(game.turn().player(), Some(defender.clone()));
+            let outcomes = salvo
+                .iter()
+                .map(|shot| {
+                    let result = shot_result(fleet(&defender), &hits[index(&defender)], shot);
+                    if result.hit {
+                        hits[index(&defender)].push(shot.clone());
+                    }
+                    result
+                })
+                .collect();
+            game.record_outcomes(&defender, outcomes).unwrap();
+            shooter = defender;
+        }
+
+        let winner = game.turn().winner().unwrap();
+        prop_assert_eq!(hits[index(&winner.opponent())].len(), FLEET_SQUARES);
+        prop_assert!(hits[index(&winner)].len() < FLEET_SQUARES);
+        prop_assert_ne!(game.turn(), GameTurn::Corrupt);
+    }
+}
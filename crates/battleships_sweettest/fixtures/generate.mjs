@@ -0,0 +1,129 @@
+// Generates the proofs the sweettest suite plays its games with, against the circuits in
+// circuits/build. Re-run it whenever the circuits are rebuilt, since every build has its own
+// trusted setup and the zomes embed the verification keys of the build they were compiled with.
+//
+//   node crates/battleships_sweettest/fixtures/generate.mjs
+
+import { writeFileSync } from "fs";
+import { dirname, join } from "path";
+import { fileURLToPath } from "url";
+import * as snarkjs from "snarkjs";
+
+const here = dirname(fileURLToPath(import.meta.url));
+const build = join(here, "../../../circuits/build");
+const circuit = (name) => ({
+  wasm: join(build, name, `${name}_js`, `${name}.wasm`),
+  zkey: join(build, name, `${name}_0001.zkey`),
+});
+
+const SHIP_LENGTHS = [5, 4, 3, 3, 2];
+const BOARD_SIZE = 10;
+
+// Home stacks horizontal ships on rows 0, 2, 4, 6, 8 and away stands vertical ships on
+// columns 0, 2, 4, 6, 8, so the two fleets overlap on some squares and not others
+const FLEETS = {
+  home: {
+    salt: "1234567",
+    ships: SHIP_LENGTHS.map((_, i) => ({ x: 0, y: i * 2, horizontal: true })),
+  },
+  away: {
+    salt: "7654321",
+    ships: SHIP_LENGTHS.map((_, i) => ({ x: i * 2, y: 0, horizontal: false })),
+  },
+};
+
+function proofToCommaSeparated(proof) {
+  return [
+    proof.pi_a[0],
+    proof.pi_a[1],
+    proof.pi_b[0][0],
+    proof.pi_b[0][1],
+    proof.pi_b[1][0],
+    proof.pi_b[1][1],
+    proof.pi_c[0],
+    proof.pi_c[1],
+  ].join(",");
+}
+
+function shipInputs(ships) {
+  return ships.map((ship) => [
+    ship.x.toString(),
+    ship.y.toString(),
+    ship.horizontal ? "1" : "0",
+  ]);
+}
+
+async function fleetFixture({ salt, ships }) {
+  const create = circuit("create");
+  const deployment = await snarkjs.groth16.fullProve(
+    { nonce: salt, ships: shipInputs(ships) },
+    create.wasm,
+    create.zkey
+  );
+  const commitment = deployment.publicSignals[0];
+
+  const move = circuit("move");
+  const shots = [];
+  for (let y = 0; y < BOARD_SIZE; y += 1) {
+    for (let x = 0; x < BOARD_SIZE; x += 1) {
+      const { proof, publicSignals } = await snarkjs.groth16.fullProve(
+        {
+          nonce: salt,
+          ships: shipInputs(ships),
+          boardHash: commitment,
+          guess: [x.toString(), y.toString()],
+        },
+        move.wasm,
+        move.zkey
+      );
+      shots.push({
+        shot: { x, y },
+        hit: publicSignals[0] === "1",
+        proof: proofToCommaSeparated(proof),
+      });
+    }
+  }
+
+  // Hits have to announce whether they sink a ship, which depends on the hits before them, so
+  // these assume the fleet is hit in board order
+  const moveSunk = circuit("move_sunk");
+  const sunkShots = [];
+  let priorHits = 0n;
+  for (const { shot, hit } of shots.filter(({ hit }) => hit)) {
+    const { proof, publicSignals } = await snarkjs.groth16.fullProve(
+      {
+        nonce: salt,
+        ships: shipInputs(ships),
+        boardHash: commitment,
+        guess: [shot.x.toString(), shot.y.toString()],
+        priorHits: priorHits.toString(),
+      },
+      moveSunk.wasm,
+      moveSunk.zkey
+    );
+    const sunkLength = Number(publicSignals[1]);
+    sunkShots.push({
+      shot,
+      sunk_ship_length: sunkLength === 0 ? null : sunkLength,
+      proof: proofToCommaSeparated(proof),
+    });
+    priorHits |= 1n << BigInt(shot.y * BOARD_SIZE + shot.x);
+  }
+
+  return {
+    ships,
+    salt,
+    commitment,
+    proof: proofToCommaSeparated(deployment.proof),
+    shots,
+    sunk_shots: sunkShots,
+  };
+}
+
+const fixtures = {
+  home: await fleetFixture(FLEETS.home),
+  away: await fleetFixture(FLEETS.away),
+};
+writeFileSync(join(here, "proofs.json"), JSON.stringify(fixtures, null, 2));
+console.log(`Wrote ${join(here, "proofs.json")}`);
+process.exit(0);
@@ -0,0 +1,8 @@
+//! Integration tests that install the battleships DNA into local conductors and play whole
+//! games through its zome functions. Everything lives under `tests/`; run them with
+//!
+//! ```text
+//! npm run build:happ
+//! node crates/battleships_sweettest/fixtures/generate.mjs
+//! cargo test -p battleships_sweettest
+//! ```
@@ -0,0 +1,445 @@
+use std::path::PathBuf;
+
+use battleships::game_state::GameState;
+use battleships_integrity::{
+    BattleshipsError, GameInvite, GameRules, HitOrMissProof, HitOrMissProofKind, Player, Ship,
+    ShipDeployment, ShipDeploymentProof, Shot,
+};
+use hdk::prelude::*;
+use holochain::{
+    conductor::api::error::ConductorApiResult,
+    sweettest::{consistency_10s, SweetCell, SweetConductorBatch, SweetDnaFile},
+};
+
+#[derive(Deserialize, Debug)]
+pub struct ShotFixture {
+    pub shot: Shot,
+    pub hit: bool,
+    pub proof: String,
+}
+
+/// A move_sunk proof of a hit, given every hit on the fleet before it in board order
+#[derive(Deserialize, Debug)]
+pub struct SunkShotFixture {
+    pub shot: Shot,
+    pub sunk_ship_length: Option<usize>,
+    pub proof: String,
+}
+
+/// A fleet with its deployment proof, a move proof for every square of the board and a
+/// move_sunk proof for every square it occupies
+#[derive(Deserialize, Debug)]
+pub struct FleetFixture {
+    pub ships: Vec<Ship>,
+    pub salt: String,
+    pub commitment: String,
+    pub proof: String,
+    pub shots: Vec<ShotFixture>,
+    pub sunk_shots: Vec<SunkShotFixture>,
+}
+
+impl FleetFixture {
+    pub fn shot(&self, shot: &Shot) -> &ShotFixture {
+        self.shots
+            .iter()
+            .find(|fixture| &fixture.shot == shot)
+            .expect("fixtures cover every square of the board")
+    }
+
+    /// Every square this fleet occupies, in board order
+    pub fn hits(&self) -> Vec<Shot> {
+        self.shots
+            .iter()
+            .filter(|fixture| fixture.hit)
+            .map(|fixture| fixture.shot.clone())
+            .collect()
+    }
+
+    /// Every square this fleet leaves empty, in board order
+    pub fn misses(&self) -> Vec<Shot> {
+        self.shots
+            .iter()
+            .filter(|fixture| !fixture.hit)
+            .map(|fixture| fixture.shot.clone())
+            .collect()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ProofFixtures {
+    pub home: FleetFixture,
+    pub away: FleetFixture,
+}
+
+fn crate_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[cfg(not(feature = "mock-verifier"))]
+const DNA_BUNDLE: &str = "../../dnas/battleships/workdir/battleships.dna";
+#[cfg(feature = "mock-verifier")]
+const DNA_BUNDLE: &str = "../../dnas/battleships/workdir/mock/battleships.dna";
+
+#[cfg(not(feature = "mock-verifier"))]
+pub fn load_fixtures() -> ProofFixtures {
+    let path = crate_dir().join("fixtures/proofs.json");
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "Could not read {}: {}. Build the circuits and run \
+             `node crates/battleships_sweettest/fixtures/generate.mjs` first",
+            path.display(),
+            e
+        )
+    });
+    serde_json::from_str(&json).expect("proofs.json is not a valid fixture file")
+}
+
+#[cfg(feature = "mock-verifier")]
+fn mock_fleet(ships: Vec<Ship>, salt: &str, commitment: &str) -> FleetFixture {
+    use battleships_integrity::{
+        constants::{BOARD_SIZE, SHIP_LENGTHS},
+        hits_bitmask, mock_proof, ship_squares, shot_result,
+    };
+
+    let squares: Vec<(usize, usize)> = ships
+        .iter()
+        .zip(SHIP_LENGTHS)
+        .flat_map(|(ship, ship_length)| ship_squares(ship, ship_length).unwrap())
+        .collect();
+    let shots = (0..BOARD_SIZE * BOARD_SIZE)
+        .map(|i| {
+            let shot = Shot {
+                x: i % BOARD_SIZE,
+                y: i / BOARD_SIZE,
+            };
+            let hit = squares.contains(&(shot.x, shot.y));
+            let proof = mock_proof(
+                "move",
+                &[
+                    if hit { "1" } else { "0" },
+                    commitment,
+                    shot.x.to_string().as_str(),
+                    shot.y.to_string().as_str(),
+                ],
+            );
+            ShotFixture { shot, hit, proof }
+        })
+        .collect::<Vec<_>>();
+    let hits: Vec<Shot> = shots
+        .iter()
+        .filter(|fixture| fixture.hit)
+        .map(|fixture| fixture.shot.clone())
+        .collect();
+    let sunk_shots = hits
+        .iter()
+        .enumerate()
+        .map(|(i, shot)| {
+            let sunk_ship_length = shot_result(&ships, &hits[..i], shot).sunk_ship_length;
+            let proof = mock_proof(
+                "move_sunk",
+                &[
+                    "1",
+                    sunk_ship_length.unwrap_or(0).to_string().as_str(),
+                    commitment,
+                    shot.x.to_string().as_str(),
+                    shot.y.to_string().as_str(),
+                    hits_bitmask(&hits[..i]).to_string().as_str(),
+                ],
+            );
+            SunkShotFixture {
+                shot: shot.clone(),
+                sunk_ship_length,
+                proof,
+            }
+        })
+        .collect();
+    FleetFixture {
+        ships,
+        salt: salt.into(),
+        commitment: commitment.into(),
+        proof: mock_proof("create", &[commitment]),
+        shots,
+        sunk_shots,
+    }
+}
+
+/// The same fleets as `fixtures/generate.mjs`, with forged proofs the mock verifier accepts
+#[cfg(feature = "mock-verifier")]
+pub fn load_fixtures() -> ProofFixtures {
+    ProofFixtures {
+        home: mock_fleet(
+            (0..5)
+                .map(|i| Ship {
+                    x: 0,
+                    y: i * 2,
+                    horizontal: true,
+                })
+                .collect(),
+            "1234567",
+            "1111111",
+        ),
+        away: mock_fleet(
+            (0..5)
+                .map(|i| Ship {
+                    x: i * 2,
+                    y: 0,
+                    horizontal: false,
+                })
+                .collect(),
+            "7654321",
+            "2222222",
+        ),
+    }
+}
+
+/// Recovers the structured error raised by a failed zome call, whether the coordinator returned
+/// it or validation rejected one of the records it tried to commit
+pub fn battleships_error<E: std::fmt::Debug>(error: E) -> BattleshipsError {
+    // The error JSON is nested inside escaped strings, and none of our errors contain backslashes
+    let message = format!("{:?}", error).replace('\\', "");
+    let start = message
+        .find("{\"code\"")
+        .unwrap_or_else(|| panic!("Not a battleships error: {}", message));
+    serde_json::Deserializer::from_str(&message[start..])
+        .into_iter::<BattleshipsError>()
+        .next()
+        .and_then(Result::ok)
+        .unwrap_or_else(|| panic!("Not a battleships error: {}", message))
+}
+
+#[derive(Serialize, Debug)]
+struct FireShotInput {
+    game_invite_hash: ActionHash,
+    shot: Shot,
+}
+
+#[derive(Serialize, Debug)]
+struct ProveHitOrMissInput {
+    game_invite_hash: ActionHash,
+    hit_or_miss_proof: HitOrMissProof,
+}
+
+/// Two conductors with the DNA installed, one per player, and a game between them with both
+/// fleets deployed and the coin flip revealed
+pub struct TestGame {
+    pub conductors: SweetConductorBatch,
+    pub home: SweetCell,
+    pub away: SweetCell,
+    pub fixtures: ProofFixtures,
+    pub game_invite_hash: ActionHash,
+    // Home's then away's
+    deployment_proofs: Vec<ActionHash>,
+}
+
+impl TestGame {
+    pub async fn start(rules: GameRules, first_player: Player) -> Self {
+        let fixtures = load_fixtures();
+        let dna = SweetDnaFile::from_bundle(&crate_dir().join(DNA_BUNDLE))
+            .await
+            .expect("Run `npm run build:happ`, or `npm run build:dna:mock` for the mock verifier");
+
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let apps = conductors.setup_app("battleships", &[dna]).await.unwrap();
+        conductors.exchange_peer_info().await;
+        let ((home,), (away,)) = apps.into_tuples();
+
+        let game_invite: Record = conductors[0]
+            .call(
+                &home.zome("battleships"),
+                "create_game_invite",
+                GameInvite {
+                    home_player: home.agent_pubkey().clone(),
+                    away_player: away.agent_pubkey().clone(),
+                    rules,
+                },
+            )
+            .await;
+        let game_invite_hash = game_invite.action_address().clone();
+        consistency_10s([&home, &away]).await;
+
+        let mut game = TestGame {
+            conductors,
+            home,
+            away,
+            fixtures,
+            game_invite_hash: game_invite_hash.clone(),
+            deployment_proofs: Vec::new(),
+        };
+        game.switch_to_game(game_invite_hash, first_player).await;
+        game
+    }
+
+    /// Deploys both fleets for another invite between the same players, with the home player
+    /// of the invite deploying the home fleet, and plays that game from then on
+    pub async fn switch_to_game(&mut self, game_invite_hash: ActionHash, first_player: Player) {
+        self.game_invite_hash = game_invite_hash;
+        // The opener is the parity of both coin secrets
+        let home_coin_secret = match first_player {
+            Player::Home => [vec![1], vec![0; 31]].concat(),
+            Player::Away => vec![0; 32],
+        };
+        let home_deployment_proof = self.deploy(&Player::Home, home_coin_secret).await;
+        let away_deployment_proof = self.deploy(&Player::Away, vec![0; 32]).await;
+        self.deployment_proofs = vec![home_deployment_proof, away_deployment_proof];
+        // Deploying second reveals straight away, the first deployer has to be told to
+        let _: Record = self
+            .call(
+                &Player::Home,
+                "reveal_coin_flip",
+                self.game_invite_hash.clone(),
+            )
+            .await;
+        self.sync().await;
+    }
+
+    fn cell(&self, player: &Player) -> (usize, &SweetCell) {
+        match player {
+            Player::Home => (0, &self.home),
+            Player::Away => (1, &self.away),
+        }
+    }
+
+    pub fn fleet(&self, player: &Player) -> &FleetFixture {
+        match player {
+            Player::Home => &self.fixtures.home,
+            Player::Away => &self.fixtures.away,
+        }
+    }
+
+    pub async fn call<I, O>(&self, player: &Player, fn_name: &str, payload: I) -> O
+    where
+        I: Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let (index, cell) = self.cell(player);
+        self.conductors[index]
+            .call(&cell.zome("battleships"), fn_name, payload)
+            .await
+    }
+
+    pub async fn call_fallible<I, O>(
+        &self,
+        player: &Player,
+        fn_name: &str,
+        payload: I,
+    ) -> ConductorApiResult<O>
+    where
+        I: Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let (index, cell) = self.cell(player);
+        self.conductors[index]
+            .call_fallible(&cell.zome("battleships"), fn_name, payload)
+            .await
+    }
+
+    pub async fn sync(&self) {
+        consistency_10s([&self.home, &self.away]).await;
+    }
+
+    async fn deploy(&self, player: &Player, coin_secret: Vec<u8>) -> ActionHash {
+        let fleet = self.fleet(player);
+        let ship_deployment: Record = self
+            .call(
+                player,
+                "create_ship_deployment",
+                ShipDeployment {
+                    invite: self.game_invite_hash.clone(),
+                    ships: fleet.ships.clone(),
+                    salt: fleet.salt.clone(),
+                    coin_secret,
+                },
+            )
+            .await;
+        let ship_deployment_proof: Record = self
+            .call(
+                player,
+                "create_ship_deployment_proof",
+                ShipDeploymentProof {
+                    invite: self.game_invite_hash.clone(),
+                    private_entry: ship_deployment.action_address().clone(),
+                    commitment: fleet.commitment.clone(),
+                    proof: fleet.proof.clone(),
+                    coin_commitment: vec![],
+                },
+            )
+            .await;
+        self.sync().await;
+        ship_deployment_proof.action_address().clone()
+    }
+
+    pub async fn game_state(&self, player: &Player) -> GameState {
+        self.call(player, "get_game_state", self.game_invite_hash.clone())
+            .await
+    }
+
+    pub async fn fire(&self, player: &Player, shot: Shot) -> ConductorApiResult<Record> {
+        let result = self
+            .call_fallible(
+                player,
+                "fire_shot",
+                FireShotInput {
+                    game_invite_hash: self.game_invite_hash.clone(),
+                    shot,
+                },
+            )
+            .await;
+        self.sync().await;
+        result
+    }
+
+    /// The honest proof of the outcome of a shot at the given player's fleet. Hits announce
+    /// whether they sink a ship, so the fleet has to be hit in board order.
+    pub fn hit_or_miss_proof(&self, player: &Player, shot: &Shot) -> HitOrMissProof {
+        let fleet = self.fleet(player);
+        let fixture = fleet.shot(shot);
+        let mut hit_or_miss_proof = self.plain_hit_or_miss_proof(player, shot);
+        if fixture.hit {
+            let i = fleet
+                .sunk_shots
+                .iter()
+                .position(|sunk_shot| &sunk_shot.shot == shot)
+                .expect("fixtures cover every square of the fleet");
+            hit_or_miss_proof.proof = fleet.sunk_shots[i].proof.clone();
+            hit_or_miss_proof.kind = HitOrMissProofKind::WithSunk {
+                prior_hits: fleet.hits()[..i].to_vec(),
+                sunk_ship_length: fleet.sunk_shots[i].sunk_ship_length,
+            };
+        }
+        hit_or_miss_proof
+    }
+
+    /// A genuine move proof of the outcome of a shot, which says nothing about sinking ships
+    pub fn plain_hit_or_miss_proof(&self, player: &Player, shot: &Shot) -> HitOrMissProof {
+        let fleet = self.fleet(player);
+        let fixture = fleet.shot(shot);
+        let (index, _) = self.cell(player);
+        HitOrMissProof {
+            deployment_proof: self.deployment_proofs[index].clone(),
+            deployment_commitment: fleet.commitment.clone(),
+            shot: fixture.shot.clone(),
+            hit: fixture.hit,
+            proof: fixture.proof.clone(),
+            kind: HitOrMissProofKind::HitOrMiss,
+        }
+    }
+
+    pub async fn prove(
+        &self,
+        player: &Player,
+        hit_or_miss_proof: HitOrMissProof,
+    ) -> ConductorApiResult<Record> {
+        let result = self
+            .call_fallible(
+                player,
+                "prove_hit_or_miss",
+                ProveHitOrMissInput {
+                    game_invite_hash: self.game_invite_hash.clone(),
+                    hit_or_miss_proof,
+                },
+            )
+            .await;
+        self.sync().await;
+        result
+    }
+}
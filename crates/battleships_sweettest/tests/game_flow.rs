@@ -0,0 +1,716 @@
+mod common;
+
+use std::time::Duration;
+
+use battleships::{
+    game_match::{CreateMatchInput, MatchState},
+    game_message::SendGameMessageInput,
+    game_state::GameState,
+    replay::{GameSnapshot, GetGameSnapshotInput, ReplayMove},
+    spectators::CellView,
+    tournament::{BracketRound, CreateTournamentInput},
+    Signal,
+};
+use battleships_integrity::{
+    constants::BOARD_SIZE, BattleshipsError, GameInvite, GameMessage, GameMessageContent,
+    GameRules, GameTurn, Heatmap, Pairing, Player, ProofKind, ShipDeployment, ShipDeploymentProof,
+    Shot, ShotResult, TournamentFormat, TournamentRound, MESSAGE_RATE_LIMIT,
+    MESSAGE_RATE_WINDOW_MICROS,
+};
+use common::{battleships_error, TestGame};
+use hdk::prelude::{Action, ActionHashed, Entry, EntryHash, ExternIO, Record, SignedActionHashed};
+use tokio_stream::StreamExt;
+
+fn turn(game_state: &GameState) -> GameTurn {
+    match game_state {
+        GameState::GameStarted { turn, .. } => turn.clone(),
+        other => panic!("Game has not started: {:?}", other),
+    }
+}
+
+async fn assert_turn(game: &TestGame, expected: GameTurn) {
+    for player in [Player::Home, Player::Away] {
+        assert_eq!(turn(&game.game_state(&player).await), expected);
+    }
+}
+
+/// Fires a shot and has the defender prove its outcome, checking the turn after each step
+async fn play_move(game: &TestGame, shooter: &Player, shot: Shot) {
+    let defender = shooter.opponent();
+    game.fire(shooter, shot.clone()).await.unwrap();
+    let proving = match defender {
+        Player::Home => GameTurn::HomeProof,
+        Player::Away => GameTurn::AwayProof,
+    };
+    assert_turn(game, proving).await;
+    game.prove(&defender, game.hit_or_miss_proof(&defender, &shot))
+        .await
+        .unwrap();
+}
+
+/// Plays a game home opened to the end, with home firing only at away's ships and away only at
+/// empty water, and returns the squares away has not fired at yet
+async fn play_until_home_wins(game: &TestGame) -> std::vec::IntoIter<Shot> {
+    let home_shots = game.fleet(&Player::Away).hits();
+    let mut away_shots = game.fleet(&Player::Home).misses().into_iter();
+    for (i, shot) in home_shots.iter().enumerate() {
+        play_move(game, &Player::Home, shot.clone()).await;
+        if i + 1 == home_shots.len() {
+            break;
+        }
+        assert_turn(game, GameTurn::AwayShot).await;
+        play_move(game, &Player::Away, away_shots.next().unwrap()).await;
+        assert_turn(game, GameTurn::HomeShot).await;
+    }
+    assert_turn(game, GameTurn::HomeWon).await;
+    away_shots
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn full_game_ends_when_a_fleet_is_sunk() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    assert_turn(&game, GameTurn::HomeShot).await;
+
+    let mut away_shots = play_until_home_wins(&game).await;
+    let error = game
+        .fire(&Player::Away, away_shots.next().unwrap())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::NotYourTurn {
+            turn: GameTurn::HomeWon
+        }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn fleet_can_only_be_revealed_once() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    play_until_home_wins(&game).await;
+
+    let _: Record = game
+        .call(&Player::Away, "reveal_fleet", game.game_invite_hash.clone())
+        .await;
+    let error = game
+        .call_fallible::<_, Record>(&Player::Away, "reveal_fleet", game.game_invite_hash.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::FleetAlreadyRevealed
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn coin_flip_decides_who_opens() {
+    let game = TestGame::start(GameRules::Classic, Player::Away).await;
+    assert_turn(&game, GameTurn::AwayShot).await;
+
+    play_move(&game, &Player::Away, Shot { x: 0, y: 0 }).await;
+    assert_turn(&game, GameTurn::HomeShot).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn second_deployment_proof_cannot_redo_the_coin_flip() {
+    let game = TestGame::start(GameRules::Classic, Player::Away).await;
+
+    // Having seen away's coin, home deploys again with a secret that would win the flip
+    let fleet = game.fleet(&Player::Home);
+    let ship_deployment: Record = game
+        .call(
+            &Player::Home,
+            "create_ship_deployment",
+            ShipDeployment {
+                invite: game.game_invite_hash.clone(),
+                ships: fleet.ships.clone(),
+                salt: fleet.salt.clone(),
+                coin_secret: [vec![1], vec![0; 31]].concat(),
+            },
+        )
+        .await;
+    let error = game
+        .call_fallible::<_, Record>(
+            &Player::Home,
+            "create_ship_deployment_proof",
+            ShipDeploymentProof {
+                invite: game.game_invite_hash.clone(),
+                private_entry: ship_deployment.action_address().clone(),
+                commitment: fleet.commitment.clone(),
+                proof: fleet.proof.clone(),
+                coin_commitment: vec![],
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::MultipleDeployments
+    );
+    game.sync().await;
+    assert_turn(&game, GameTurn::AwayShot).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn copied_coin_commitment_cannot_be_opened() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let game_invite: Record = game
+        .call(
+            &Player::Home,
+            "create_game_invite",
+            GameInvite {
+                home_player: game.home.agent_pubkey().clone(),
+                away_player: game.away.agent_pubkey().clone(),
+                rules: GameRules::Classic,
+            },
+        )
+        .await;
+    let game_invite_hash = game_invite.action_address().clone();
+    game.sync().await;
+
+    let home_coin_secret = [vec![1], vec![0; 31]].concat();
+    let mut coin_commitment = Vec::new();
+    for player in [Player::Home, Player::Away] {
+        let fleet = game.fleet(&player);
+        let ship_deployment: Record = game
+            .call(
+                &player,
+                "create_ship_deployment",
+                ShipDeployment {
+                    invite: game_invite_hash.clone(),
+                    ships: fleet.ships.clone(),
+                    salt: fleet.salt.clone(),
+                    coin_secret: home_coin_secret.clone(),
+                },
+            )
+            .await;
+        let ship_deployment_proof = ShipDeploymentProof {
+            invite: game_invite_hash.clone(),
+            private_entry: ship_deployment.action_address().clone(),
+            commitment: fleet.commitment.clone(),
+            proof: fleet.proof.clone(),
+            coin_commitment: coin_commitment.clone(),
+        };
+        if player == Player::Home {
+            let record: Record = game
+                .call(
+                    &player,
+                    "create_ship_deployment_proof",
+                    ship_deployment_proof,
+                )
+                .await;
+            let ship_deployment_proof: ShipDeploymentProof =
+                record.entry().to_app_option().unwrap().unwrap();
+            coin_commitment = ship_deployment_proof.coin_commitment;
+            game.sync().await;
+            continue;
+        }
+        // Away copies home's commitment and, deploying second, reveals straight away with home's
+        // secret as if it had already been revealed
+        let error = game
+            .call_fallible::<_, Record>(
+                &player,
+                "create_ship_deployment_proof",
+                ship_deployment_proof,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            battleships_error(&error),
+            BattleshipsError::CoinCommitmentMismatch
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn firing_out_of_turn_is_rejected() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+
+    let error = game
+        .fire(&Player::Away, Shot { x: 0, y: 0 })
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::NotYourTurn {
+            turn: GameTurn::HomeShot
+        }
+    );
+
+    game.fire(&Player::Home, Shot { x: 0, y: 0 }).await.unwrap();
+    let error = game
+        .fire(&Player::Home, Shot { x: 1, y: 0 })
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::NotYourTurn {
+            turn: GameTurn::AwayProof
+        }
+    );
+    assert_turn(&game, GameTurn::AwayProof).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proof_for_the_wrong_shot_is_rejected() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let shot = Shot { x: 0, y: 0 };
+    game.fire(&Player::Home, shot.clone()).await.unwrap();
+
+    // A genuine proof, but for a square that wasn't fired at
+    let other_shot = Shot { x: 1, y: 0 };
+    let error = game
+        .prove(
+            &Player::Away,
+            game.hit_or_miss_proof(&Player::Away, &other_shot),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::ProofForWrongShot {
+            expected: shot.clone(),
+            actual: other_shot,
+        }
+    );
+    assert_turn(&game, GameTurn::AwayProof).await;
+
+    game.prove(&Player::Away, game.hit_or_miss_proof(&Player::Away, &shot))
+        .await
+        .unwrap();
+    assert_turn(&game, GameTurn::AwayShot).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn lying_about_the_outcome_is_rejected() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let shot = game.fleet(&Player::Away).hits()[0].clone();
+    game.fire(&Player::Home, shot.clone()).await.unwrap();
+
+    let mut hit_or_miss_proof = game.hit_or_miss_proof(&Player::Away, &shot);
+    hit_or_miss_proof.hit = false;
+    let error = game
+        .prove(&Player::Away, hit_or_miss_proof)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::InvalidProof {
+            kind: ProofKind::HitOrMiss
+        }
+    );
+    assert_turn(&game, GameTurn::AwayProof).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn hits_must_announce_sunk_ships_in_classic_games() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let shot = game.fleet(&Player::Away).hits()[0].clone();
+    game.fire(&Player::Home, shot.clone()).await.unwrap();
+
+    // A genuine move proof, which can't say whether the hit sank a ship
+    let error = game
+        .prove(
+            &Player::Away,
+            game.plain_hit_or_miss_proof(&Player::Away, &shot),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::SunkProofRequired
+    );
+    assert_turn(&game, GameTurn::AwayProof).await;
+
+    game.prove(&Player::Away, game.hit_or_miss_proof(&Player::Away, &shot))
+        .await
+        .unwrap();
+
+    // Misses sink nothing, so the plain proof is enough for them
+    let miss = game.fleet(&Player::Home).misses()[0].clone();
+    game.fire(&Player::Away, miss.clone()).await.unwrap();
+    game.prove(
+        &Player::Home,
+        game.plain_hit_or_miss_proof(&Player::Home, &miss),
+    )
+    .await
+    .unwrap();
+    assert_turn(&game, GameTurn::HomeShot).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signals_are_checked_against_their_signed_action() {
+    let mut game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let mut signals = game.conductors[0].signals().await;
+    let game_message = GameMessage {
+        invite: game.game_invite_hash.clone(),
+        content: GameMessageContent::Text {
+            text: "Good luck".into(),
+        },
+    };
+    // Away's post_commit signals the message straight away, before it can have been published
+    let record: Record = game
+        .call(
+            &Player::Away,
+            "send_game_message",
+            SendGameMessageInput {
+                game_invite_hash: game.game_invite_hash.clone(),
+                content: game_message.content.clone(),
+            },
+        )
+        .await;
+
+    // Away's signature on the message, claimed for another one
+    let forged_message = GameMessage {
+        content: GameMessageContent::Text {
+            text: "I resign".into(),
+        },
+        ..game_message.clone()
+    };
+    let mut create = match record.action().clone() {
+        Action::Create(create) => create,
+        other => panic!("Not a create: {:?}", other),
+    };
+    create.entry_hash = EntryHash::with_data_sync(&Entry::try_from(&forged_message).unwrap());
+    let forged_action = SignedActionHashed::with_presigned(
+        ActionHashed::from_content_sync(Action::Create(create)),
+        record.signature().clone(),
+    );
+    let signal = Signal::GameMessageReceived {
+        game_invite_hash: game.game_invite_hash.clone(),
+        action: forged_action.clone(),
+        game_message: forged_message,
+    };
+    let _: () = game.conductors[0]
+        .call_from(
+            game.away.agent_pubkey(),
+            None,
+            &game.home.zome("battleships"),
+            "recv_remote_signal",
+            ExternIO::encode(signal).unwrap(),
+        )
+        .await;
+
+    let mut received = Vec::new();
+    while let Ok(Some(signal)) = tokio::time::timeout(Duration::from_secs(2), signals.next()).await
+    {
+        if let holochain::prelude::Signal::App { signal, .. } = signal {
+            if let Ok(Signal::GameMessageReceived { action, .. }) = signal.into_inner().decode() {
+                received.push(action.hashed.hash);
+            }
+        }
+    }
+    assert!(received.contains(record.action_address()));
+    assert!(!received.contains(&forged_action.hashed.hash));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn message_rate_limit_counts_messages_behind_other_actions() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let send_message = |text: &str| {
+        game.call_fallible::<_, Record>(
+            &Player::Home,
+            "send_game_message",
+            SendGameMessageInput {
+                game_invite_hash: game.game_invite_hash.clone(),
+                content: GameMessageContent::Text { text: text.into() },
+            },
+        )
+    };
+    for _ in 0..MESSAGE_RATE_LIMIT {
+        send_message("Fire!").await.unwrap();
+    }
+    // Bury the messages under more Spectators links than one batch of the chain walk holds
+    for _ in 0..60 {
+        let _: () = game
+            .call(
+                &Player::Home,
+                "subscribe_to_game",
+                game.game_invite_hash.clone(),
+            )
+            .await;
+    }
+
+    let error = send_message("Fire!").await.unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::MessageRateLimited {
+            limit: MESSAGE_RATE_LIMIT,
+            window_micros: MESSAGE_RATE_WINDOW_MICROS,
+        }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shot_heatmap_counts_fleets_that_explain_the_hits() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    // Away hasn't fired, so their heatmap is the empty board's
+    let empty: Heatmap = game
+        .call(
+            &Player::Away,
+            "get_shot_heatmap",
+            game.game_invite_hash.clone(),
+        )
+        .await;
+    assert!(empty.iter().flatten().all(|score| *score > 0));
+
+    let shot = game.fleet(&Player::Away).hits()[0].clone();
+    play_move(&game, &Player::Home, shot.clone()).await;
+    let heatmap: Heatmap = game
+        .call(
+            &Player::Home,
+            "get_shot_heatmap",
+            game.game_invite_hash.clone(),
+        )
+        .await;
+    assert_eq!(heatmap[shot.x][shot.y], 0);
+    // Every fleet counted has a ship through the hit, so the hottest square is next to it
+    let hottest = (0..BOARD_SIZE * BOARD_SIZE)
+        .map(|i| (i % BOARD_SIZE, i / BOARD_SIZE))
+        .max_by_key(|&(x, y)| heatmap[x][y])
+        .unwrap();
+    assert_eq!(hottest.0.abs_diff(shot.x) + hottest.1.abs_diff(shot.y), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replay_lists_moves_in_order_and_snapshots_boards() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let home_shot = game.fleet(&Player::Away).hits()[0].clone();
+    let away_shot = game.fleet(&Player::Home).misses()[0].clone();
+    play_move(&game, &Player::Home, home_shot.clone()).await;
+    game.fire(&Player::Away, away_shot.clone()).await.unwrap();
+    game.sync().await;
+
+    let replay: Vec<ReplayMove> = game
+        .call(
+            &Player::Home,
+            "get_game_replay",
+            game.game_invite_hash.clone(),
+        )
+        .await;
+    assert_eq!(replay.len(), 2);
+    assert_eq!(replay[0].player, Player::Home);
+    assert_eq!(replay[0].shot, home_shot);
+    assert_eq!(
+        replay[0].outcome,
+        Some(ShotResult {
+            hit: true,
+            sunk_ship_length: None
+        })
+    );
+    assert!(replay[0].proof_hash.is_some());
+    assert!(replay[0].fired_at <= replay[0].proven_at.unwrap());
+    assert_eq!(replay[1].player, Player::Away);
+    assert_eq!(replay[1].shot, away_shot);
+    assert_eq!(replay[1].outcome, None);
+
+    let snapshot = |move_index| GetGameSnapshotInput {
+        game_invite_hash: game.game_invite_hash.clone(),
+        move_index,
+    };
+    let first: GameSnapshot = game
+        .call(&Player::Away, "get_game_snapshot", snapshot(0))
+        .await;
+    assert_eq!(first.away_board[home_shot.x][home_shot.y], CellView::Hit);
+    assert_eq!(
+        first.home_board[away_shot.x][away_shot.y],
+        CellView::NotShot
+    );
+    let second: GameSnapshot = game
+        .call(&Player::Away, "get_game_snapshot", snapshot(1))
+        .await;
+    assert_eq!(
+        second.home_board[away_shot.x][away_shot.y],
+        CellView::Pending
+    );
+
+    let error = game
+        .call_fallible::<_, GameSnapshot>(&Player::Away, "get_game_snapshot", snapshot(2))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::MoveIndexOutOfRange {
+            move_index: 2,
+            moves: 2
+        }
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_organiser_advances_a_tournament_once_its_games_finish() {
+    let game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let tournament: Record = game
+        .call(
+            &Player::Home,
+            "create_tournament",
+            CreateTournamentInput {
+                name: "Harbour cup".into(),
+                format: TournamentFormat::RoundRobin,
+                rules: GameRules::Classic,
+            },
+        )
+        .await;
+    let tournament_hash = tournament.action_address().clone();
+    game.sync().await;
+    for player in [Player::Home, Player::Away] {
+        let _: () = game
+            .call(&player, "register_for_tournament", tournament_hash.clone())
+            .await;
+        game.sync().await;
+    }
+
+    let error = game
+        .call_fallible::<_, Record>(&Player::Away, "advance_tournament", tournament_hash.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(battleships_error(&error), BattleshipsError::NotOrganiser);
+
+    let first_round: Record = game
+        .call(&Player::Home, "advance_tournament", tournament_hash.clone())
+        .await;
+    let first_round: TournamentRound = first_round.entry().to_app_option().unwrap().unwrap();
+    // The first round points at both players' own registrations
+    assert_eq!(first_round.registrations.len(), 2);
+    game.sync().await;
+    let error = game
+        .call_fallible::<_, ()>(
+            &Player::Away,
+            "register_for_tournament",
+            tournament_hash.clone(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::RegistrationClosed
+    );
+
+    // Only the home player of a pairing starts its game
+    let started: Vec<Record> = game
+        .call(
+            &Player::Away,
+            "start_tournament_games",
+            tournament_hash.clone(),
+        )
+        .await;
+    assert!(started.is_empty());
+    let started: Vec<Record> = game
+        .call(
+            &Player::Home,
+            "start_tournament_games",
+            tournament_hash.clone(),
+        )
+        .await;
+    assert_eq!(started.len(), 1);
+    game.sync().await;
+
+    let bracket: Vec<BracketRound> = game
+        .call(
+            &Player::Away,
+            "get_tournament_bracket",
+            tournament_hash.clone(),
+        )
+        .await;
+    assert_eq!(bracket.len(), 1);
+    assert_eq!(
+        bracket[0].games[0].pairing,
+        Pairing {
+            home: game.home.agent_pubkey().clone(),
+            away: Some(game.away.agent_pubkey().clone()),
+        }
+    );
+    assert_eq!(
+        bracket[0].games[0].game_invite_hash,
+        Some(started[0].action_address().clone())
+    );
+    assert_eq!(bracket[0].games[0].winner, None);
+
+    let error = game
+        .call_fallible::<_, Record>(&Player::Home, "advance_tournament", tournament_hash)
+        .await
+        .unwrap_err();
+    assert_eq!(battleships_error(&error), BattleshipsError::GameNotFinished);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn match_swaps_home_and_away_between_games() {
+    let mut game = TestGame::start(GameRules::Classic, Player::Home).await;
+    let error = game
+        .call_fallible::<_, Record>(
+            &Player::Home,
+            "create_match",
+            CreateMatchInput {
+                opponent: game.away.agent_pubkey().clone(),
+                best_of: 2,
+                rules: GameRules::Classic,
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(
+        battleships_error(&error),
+        BattleshipsError::InvalidMatchLength { max: 9, actual: 2 }
+    );
+
+    let game_match: Record = game
+        .call(
+            &Player::Home,
+            "create_match",
+            CreateMatchInput {
+                opponent: game.away.agent_pubkey().clone(),
+                best_of: 3,
+                rules: GameRules::Classic,
+            },
+        )
+        .await;
+    let match_hash = game_match.action_address().clone();
+    game.sync().await;
+
+    let my_matches: Vec<Record> = game.call(&Player::Away, "get_my_matches", ()).await;
+    assert_eq!(my_matches.len(), 1);
+    let match_state: MatchState = game
+        .call(&Player::Away, "get_match_state", match_hash.clone())
+        .await;
+    assert_eq!(match_state.games.len(), 1);
+    assert_eq!(&match_state.games[0].home_player, game.home.agent_pubkey());
+    assert_eq!(match_state.games[0].winner, None);
+    assert_eq!(
+        (match_state.home_player_wins, match_state.away_player_wins),
+        (0, 0)
+    );
+    assert_eq!(match_state.winner, None);
+    // The first game is still being played
+    assert_eq!(match_state.next_game, None);
+
+    let error = game
+        .call_fallible::<_, Record>(&Player::Away, "start_next_match_game", match_hash.clone())
+        .await
+        .unwrap_err();
+    assert_eq!(battleships_error(&error), BattleshipsError::GameNotFinished);
+
+    game.switch_to_game(match_state.games[0].game_invite_hash.clone(), Player::Home)
+        .await;
+    play_until_home_wins(&game).await;
+    game.sync().await;
+    // The second game's link references the finished first one
+    let _: Record = game
+        .call(&Player::Away, "start_next_match_game", match_hash.clone())
+        .await;
+    game.sync().await;
+    let match_state: MatchState = game
+        .call(&Player::Home, "get_match_state", match_hash.clone())
+        .await;
+    assert_eq!(match_state.games.len(), 2);
+    assert_eq!(&match_state.games[1].home_player, game.away.agent_pubkey());
+    assert_eq!(
+        (match_state.home_player_wins, match_state.away_player_wins),
+        (1, 0)
+    );
+
+    let error = game
+        .call_fallible::<_, Record>(&Player::Away, "start_next_match_game", match_hash)
+        .await
+        .unwrap_err();
+    assert_eq!(battleships_error(&error), BattleshipsError::GameNotFinished);
+}
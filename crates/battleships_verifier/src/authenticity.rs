@@ -0,0 +1,38 @@
+use battleships_integrity::SignedExport;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use hdi::prelude::*;
+
+/// Checks that an exported record is exactly what its author signed. Returns the reason it
+/// isn't, if any.
+pub fn check_signed_export<T: Serialize>(signed_export: &SignedExport<T>) -> Option<String> {
+    let action = signed_export.action.action();
+    if ActionHash::with_data_sync(action) != signed_export.action.hashed.hash {
+        return Some("action hash doesn't match the action".into());
+    }
+    // Holochain signs the serialized action
+    let action_bytes = match ExternIO::encode(action) {
+        Ok(action_bytes) => action_bytes,
+        Err(e) => return Some(format!("action could not be serialized: {}", e)),
+    };
+    let author_key: [u8; 32] = match action.author().get_raw_32().try_into() {
+        Ok(author_key) => author_key,
+        Err(_) => return Some("author is not an ed25519 key".into()),
+    };
+    let signature = Signature::from_bytes(&signed_export.action.signature.0);
+    let verified = VerifyingKey::from_bytes(&author_key)
+        .and_then(|author_key| author_key.verify(&action_bytes.0, &signature));
+    if verified.is_err() {
+        return Some("signature doesn't match the author".into());
+    }
+    let entry_bytes = match ExternIO::encode(&signed_export.entry) {
+        Ok(entry_bytes) => entry_bytes,
+        Err(e) => return Some(format!("entry could not be serialized: {}", e)),
+    };
+    let entry = Entry::App(AppEntryBytes(SerializedBytes::from(UnsafeBytes::from(
+        entry_bytes.0,
+    ))));
+    if Some(&EntryHash::with_data_sync(&entry)) != action.entry_hash() {
+        return Some("entry hash doesn't match the action".into());
+    }
+    None
+}
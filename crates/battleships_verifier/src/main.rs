@@ -0,0 +1,160 @@
+//! Checks a game exported with the `export_game` zome function without joining the network.
+//!
+//! ```text
+//! battleships_verifier game.json
+//! ```
+//!
+//! Every record's hash and signature are checked against its author, then the zero knowledge
+//! proofs, coin flip and transcript rules are re-run exactly as the integrity zome runs them. Prints a
+//! verdict per deployment and per move and exits non-zero if any of them fail.
+
+mod authenticity;
+
+use std::{collections::HashMap, fs, io::Read, process::ExitCode};
+
+use authenticity::check_signed_export;
+use battleships_integrity::{
+    audit_game_export, AuditStep, GameExport, COIN_SECRET_LENGTH, GAME_EXPORT_VERSION,
+};
+use hdi::prelude::*;
+
+fn read_export(path: &str) -> Result<GameExport, String> {
+    let json = if path == "-" {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .map_err(|e| e.to_string())?;
+        json
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?
+    };
+    let game_export: GameExport = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    if game_export.version != GAME_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported export version {}, expected {}",
+            game_export.version, GAME_EXPORT_VERSION
+        ));
+    }
+    Ok(game_export)
+}
+
+/// Action hashes of the records that fail their authenticity check, with the reason
+fn inauthentic_records(game_export: &GameExport) -> Vec<(ActionHash, String)> {
+    let mut failures = Vec::new();
+    let mut check = |hash: &ActionHash, failure: Option<String>| {
+        if let Some(failure) = failure {
+            failures.push((hash.clone(), failure));
+        }
+    };
+    check(
+        game_export.game_invite.hash(),
+        check_signed_export(&game_export.game_invite),
+    );
+    for deployment_proof in &game_export.deployment_proofs {
+        check(
+            deployment_proof.hash(),
+            check_signed_export(deployment_proof),
+        );
+    }
+    for coin_flip_reveal in &game_export.coin_flip_reveals {
+        check(
+            coin_flip_reveal.hash(),
+            check_signed_export(coin_flip_reveal),
+        );
+    }
+    for game_transcript in &game_export.transcript_revisions {
+        check(game_transcript.hash(), check_signed_export(game_transcript));
+    }
+    for hit_or_miss_proof in &game_export.hit_or_miss_proofs {
+        check(
+            hit_or_miss_proof.hash(),
+            check_signed_export(hit_or_miss_proof),
+        );
+    }
+    failures
+}
+
+/// What the hash_blake2b host function gives `coin_commitment` inside the zome
+fn blake2b(bytes: &[u8]) -> ExternResult<Vec<u8>> {
+    Ok(blake2b_simd::Params::new()
+        .hash_length(COIN_SECRET_LENGTH)
+        .hash(bytes)
+        .as_bytes()
+        .to_vec())
+}
+
+fn describe_step(step: &AuditStep) -> String {
+    match step {
+        AuditStep::Deployment {
+            player: Some(player),
+        } => format!("{:?} deployment", player),
+        AuditStep::Deployment { player: None } => "Deployment".into(),
+        AuditStep::Move { revision, turn } => format!("Move {} ({:?})", revision + 1, turn),
+    }
+}
+
+fn main() -> ExitCode {
+    let path = match std::env::args().nth(1) {
+        Some(path) => path,
+        None => {
+            eprintln!("Usage: battleships_verifier <game.json | ->");
+            return ExitCode::from(2);
+        }
+    };
+    let game_export = match read_export(&path) {
+        Ok(game_export) => game_export,
+        Err(e) => {
+            eprintln!("Could not read game export: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let verdicts = match audit_game_export(&game_export, blake2b) {
+        Ok(verdicts) => verdicts,
+        Err(e) => {
+            eprintln!("Could not audit game: {:?}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut failed = false;
+    let inauthentic = inauthentic_records(&game_export);
+    // The invite, coin flip reveals and hit or miss proofs have no verdict of their own
+    for (hash, failure) in &inauthentic {
+        if !verdicts.iter().any(|verdict| &verdict.action_hash == hash) {
+            failed = true;
+            println!(
+                "FAIL  Record {}: {}",
+                ActionHashB64::from(hash.clone()),
+                failure
+            );
+        }
+    }
+    let inauthentic: HashMap<ActionHash, String> = inauthentic.into_iter().collect();
+    for verdict in &verdicts {
+        let failure = match (&verdict.error, inauthentic.get(&verdict.action_hash)) {
+            (_, Some(failure)) => Some(failure.clone()),
+            (Some(error), None) => Some(error.to_string()),
+            (None, None) => None,
+        };
+        let author = AgentPubKeyB64::from(verdict.author.clone());
+        match failure {
+            None => println!("PASS  {} by {}", describe_step(&verdict.step), author),
+            Some(failure) => {
+                failed = true;
+                println!(
+                    "FAIL  {} by {}: {}",
+                    describe_step(&verdict.step),
+                    author,
+                    failure
+                );
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,103 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    ship_deployment::get_your_ship_deployment_for_invite,
+    ship_deployment_proof::{get_deployers_for_invite, get_your_ship_deployment_proof_for_invite},
+};
+
+/// Reveals our coin flip secret for a game once both players have committed to theirs
+#[hdk_extern]
+pub fn reveal_coin_flip(game_invite_hash: ActionHash) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if let Some((coin_flip_reveal_hash, _)) = get_coin_flip_reveal_links(game_invite_hash.clone())?
+        .into_iter()
+        .find(|(_, tag)| tag.revealer == my_pub_key)
+    {
+        return get(coin_flip_reveal_hash, GetOptions::default())?.ok_or(
+            BattleshipsError::NotFound {
+                kind: RecordKind::CoinFlipReveal,
+            }
+            .into(),
+        );
+    }
+    // Revealing before the opponent commits would let them choose the outcome
+    let deployers = get_deployers_for_invite(game_invite_hash.clone())?;
+    if deployers.len() != 2 {
+        return Err(BattleshipsError::WrongDeploymentCount {
+            expected: 2,
+            actual: deployers.len(),
+        }
+        .into());
+    }
+    let ship_deployment: ShipDeployment =
+        get_your_ship_deployment_for_invite(game_invite_hash.clone())?
+            .and_then(|record| record.entry().to_app_option().ok().flatten())
+            .ok_or(BattleshipsError::NotFound {
+                kind: RecordKind::ShipDeployment,
+            })?;
+    let deployment_proof_record = get_your_ship_deployment_proof_for_invite(
+        game_invite_hash.clone(),
+    )?
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::ShipDeploymentProof,
+    })?;
+    let coin_flip_reveal = CoinFlipReveal {
+        invite: game_invite_hash.clone(),
+        deployment_proof: deployment_proof_record.action_hashed().hash.clone(),
+        secret: ship_deployment.coin_secret,
+    };
+    let coin_flip_reveal_hash =
+        create_entry(&EntryTypes::CoinFlipReveal(coin_flip_reveal.clone()))?;
+    let record = get(coin_flip_reveal_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::CoinFlipReveal,
+        },
+    )?;
+    create_link(
+        game_invite_hash,
+        coin_flip_reveal_hash,
+        LinkTypes::CoinFlipReveals,
+        encode_link_tag(&CoinFlipRevealLinkTag {
+            revealer: my_pub_key,
+            secret: coin_flip_reveal.secret,
+        })?,
+    )?;
+    Ok(record)
+}
+
+pub fn get_coin_flip_reveal_links(
+    game_invite_hash: ActionHash,
+) -> ExternResult<Vec<(ActionHash, CoinFlipRevealLinkTag)>> {
+    get_links(game_invite_hash, LinkTypes::CoinFlipReveals, None)?
+        .into_iter()
+        .map(|link| Ok((ActionHash::from(link.target), decode_link_tag(&link.tag)?)))
+        .collect()
+}
+
+#[hdk_extern]
+pub fn get_coin_flip_reveals_for_invite(
+    game_invite_hash: ActionHash,
+) -> ExternResult<Vec<CoinFlipRevealLinkTag>> {
+    Ok(get_coin_flip_reveal_links(game_invite_hash)?
+        .into_iter()
+        .map(|(_, tag)| tag)
+        .collect())
+}
+
+/// Each player's revealed secret, if they have revealed
+pub fn player_secrets<'a>(
+    game_invite: &GameInvite,
+    reveals: &'a [CoinFlipRevealLinkTag],
+) -> (Option<&'a [u8]>, Option<&'a [u8]>) {
+    let secret_for = |player: &AgentPubKey| {
+        reveals
+            .iter()
+            .find(|reveal| &reveal.revealer == player)
+            .map(|reveal| reveal.secret.as_slice())
+    };
+    (
+        secret_for(&game_invite.home_player),
+        secret_for(&game_invite.away_player),
+    )
+}
@@ -0,0 +1,67 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_latest_game_transcript_for_game_invite, get_entry_for_record,
+    ship_deployment::get_your_ship_deployment_for_invite,
+    ship_deployment_proof::get_your_ship_deployment_proof_for_invite,
+};
+
+#[hdk_extern]
+pub fn reveal_fleet(game_invite_hash: ActionHash) -> ExternResult<Record> {
+    let ship_deployment = match get_your_ship_deployment_for_invite(game_invite_hash.clone())?
+        .map(|record| get_entry_for_record(&record))
+    {
+        Some(Ok(Some(EntryTypes::ShipDeployment(ship_deployment)))) => ship_deployment,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::ShipDeployment,
+            }
+            .into())
+        }
+    };
+    let deployment_proof_record = get_your_ship_deployment_proof_for_invite(
+        game_invite_hash.clone(),
+    )?
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::ShipDeploymentProof,
+    })?;
+    let game_transcript_record = get_latest_game_transcript_for_game_invite(
+        game_invite_hash.clone(),
+    )?
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::GameTranscript,
+    })?;
+    let fleet_reveal = FleetReveal {
+        invite: game_invite_hash,
+        game_transcript: game_transcript_record.action_hashed().hash.clone(),
+        deployment_proof: deployment_proof_record.action_hashed().hash.clone(),
+        ships: ship_deployment.ships,
+        salt: ship_deployment.salt,
+    };
+    let fleet_reveal_hash = create_entry(&EntryTypes::FleetReveal(fleet_reveal.clone()))?;
+    let record = get(fleet_reveal_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::FleetReveal,
+        },
+    )?;
+    create_link(
+        fleet_reveal.invite,
+        fleet_reveal_hash,
+        LinkTypes::FleetReveals,
+        (),
+    )?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn get_fleet_reveals_for_invite(game_invite_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(game_invite_hash, LinkTypes::FleetReveals, None)?;
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    let records: Vec<Record> = records.into_iter().flatten().collect();
+    Ok(records)
+}
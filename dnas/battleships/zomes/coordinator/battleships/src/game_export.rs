@@ -0,0 +1,136 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_game_transcript_revisions, get_entry_for_record,
+    ship_deployment_proof::get_ship_deployment_proofs_for_invite,
+};
+
+fn signed_export<T, F>(record: Record, kind: RecordKind, unwrap: F) -> ExternResult<SignedExport<T>>
+where
+    F: Fn(EntryTypes) -> Option<T>,
+{
+    match get_entry_for_record(&record)?.and_then(unwrap) {
+        Some(entry) => Ok(SignedExport {
+            action: record.signed_action,
+            entry,
+        }),
+        None => Err(BattleshipsError::WrongEntryType {
+            field: "entry".into(),
+            expected: kind,
+        }
+        .into()),
+    }
+}
+
+fn get_records(hashes: Vec<ActionHash>, kind: RecordKind) -> ExternResult<Vec<Record>> {
+    let get_input: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    HDK.with(|hdk| hdk.borrow().get(get_input))?
+        .into_iter()
+        .map(|record| record.ok_or(BattleshipsError::NotFound { kind }.into()))
+        .collect()
+}
+
+/// Every signed record of a game as a JSON document that the battleships_verifier CLI can check
+/// offline
+#[hdk_extern]
+pub fn export_game(game_invite_hash: ActionHash) -> ExternResult<String> {
+    let game_invite_record = get(game_invite_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameInvite,
+        },
+    )?;
+    let game_invite = signed_export(
+        game_invite_record,
+        RecordKind::GameInvite,
+        |entry| match entry {
+            EntryTypes::GameInvite(game_invite) => Some(game_invite),
+            _ => None,
+        },
+    )?;
+    let deployment_proofs = get_ship_deployment_proofs_for_invite(game_invite_hash.clone())?
+        .into_iter()
+        .map(|record| {
+            signed_export(
+                record,
+                RecordKind::ShipDeploymentProof,
+                |entry| match entry {
+                    EntryTypes::ShipDeploymentProof(deployment_proof) => Some(deployment_proof),
+                    _ => None,
+                },
+            )
+        })
+        .collect::<ExternResult<Vec<_>>>()?;
+
+    let transcript_revisions = get_game_transcript_revisions(game_invite_hash)?
+        .into_iter()
+        .map(|record| {
+            signed_export(record, RecordKind::GameTranscript, |entry| match entry {
+                EntryTypes::GameTranscript(game_transcript) => Some(game_transcript),
+                _ => None,
+            })
+        })
+        .collect::<ExternResult<Vec<SignedExport<GameTranscript>>>>()?;
+
+    // The original transcript settles who opens, so its reveals are the ones that count
+    let coin_flip_reveal_hashes: Vec<ActionHash> = transcript_revisions
+        .first()
+        .map(|original| {
+            vec![
+                original.entry.home_player_coin_flip_reveal.clone(),
+                original.entry.away_player_coin_flip_reveal.clone(),
+            ]
+        })
+        .unwrap_or_default();
+    let coin_flip_reveals = get_records(coin_flip_reveal_hashes, RecordKind::CoinFlipReveal)?
+        .into_iter()
+        .map(|record| {
+            signed_export(record, RecordKind::CoinFlipReveal, |entry| match entry {
+                EntryTypes::CoinFlipReveal(coin_flip_reveal) => Some(coin_flip_reveal),
+                _ => None,
+            })
+        })
+        .collect::<ExternResult<Vec<_>>>()?;
+
+    // Outcomes are only ever appended, so the latest revision references every proof
+    let proof_hashes: Vec<ActionHash> = transcript_revisions
+        .last()
+        .map(|latest| {
+            latest
+                .entry
+                .home_player_hit_or_miss_proofs
+                .iter()
+                .chain(latest.entry.away_player_hit_or_miss_proofs.iter())
+                .flatten()
+                .map(|shot_outcome| shot_outcome.proof_hash.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let hit_or_miss_proofs = get_records(proof_hashes, RecordKind::HitOrMissProof)?
+        .into_iter()
+        .map(|record| {
+            signed_export(record, RecordKind::HitOrMissProof, |entry| match entry {
+                EntryTypes::HitOrMissProof(hit_or_miss_proof) => Some(hit_or_miss_proof),
+                _ => None,
+            })
+        })
+        .collect::<ExternResult<Vec<_>>>()?;
+
+    serde_json::to_string_pretty(&GameExport {
+        version: GAME_EXPORT_VERSION,
+        game_invite,
+        deployment_proofs,
+        coin_flip_reveals,
+        transcript_revisions,
+        hit_or_miss_proofs,
+    })
+    .map_err(|e| {
+        BattleshipsError::ExportNotSerializable {
+            reason: e.to_string(),
+        }
+        .into()
+    })
+}
@@ -4,20 +4,33 @@ use hdk::prelude::*;
 #[hdk_extern]
 pub fn create_game_invite(game_invite: GameInvite) -> ExternResult<Record> {
     let game_invite_hash = create_entry(&EntryTypes::GameInvite(game_invite.clone()))?;
-    let record = get(game_invite_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest(String::from("Could not find the newly created GameInvite"))
-    ))?;
+    let record = get(game_invite_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameInvite,
+        },
+    )?;
+    let created_at = record.action().timestamp();
     create_link(
-        game_invite.home_player,
+        game_invite.home_player.clone(),
         game_invite_hash.clone(),
         LinkTypes::Invites,
-        (),
+        encode_link_tag(&InviteLinkTag {
+            opponent: game_invite.away_player.clone(),
+            is_home_player: true,
+            rules: game_invite.rules.clone(),
+            created_at,
+        })?,
     )?;
     create_link(
         game_invite.away_player,
         game_invite_hash,
         LinkTypes::Invites,
-        (),
+        encode_link_tag(&InviteLinkTag {
+            opponent: game_invite.home_player,
+            is_home_player: false,
+            rules: game_invite.rules,
+            created_at,
+        })?,
     )?;
     Ok(record)
 }
@@ -0,0 +1,269 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_invite::create_game_invite, game_transcript::get_latest_game_transcript_for_game_invite,
+    get_entry_for_action, get_entry_for_record,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateMatchInput {
+    pub opponent: AgentPubKey,
+    pub best_of: usize,
+    #[serde(default)]
+    pub rules: GameRules,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchGame {
+    // Counting from zero
+    pub game_number: usize,
+    pub game_invite_hash: ActionHash,
+    pub home_player: AgentPubKey,
+    pub away_player: AgentPubKey,
+    // None while the game is being played
+    pub winner: Option<AgentPubKey>,
+}
+
+/// The game due to be started next, by its home player
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NextMatchGame {
+    pub game_number: usize,
+    pub home_player: AgentPubKey,
+    pub away_player: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MatchState {
+    pub game_match: Match,
+    // Wins of the match's home and away players, whichever side they played each game on
+    pub home_player_wins: usize,
+    pub away_player_wins: usize,
+    pub games: Vec<MatchGame>,
+    pub winner: Option<AgentPubKey>,
+    // None while a game is being played and once the match is over
+    pub next_game: Option<NextMatchGame>,
+}
+
+/// Creates a match against the opponent and invites them to its first game, with the caller
+/// at home
+#[hdk_extern]
+pub fn create_match(input: CreateMatchInput) -> ExternResult<Record> {
+    let game_match = Match {
+        home_player: agent_info()?.agent_latest_pubkey,
+        away_player: input.opponent,
+        best_of: input.best_of,
+        rules: input.rules,
+    };
+    let match_hash = create_entry(&EntryTypes::Match(game_match.clone()))?;
+    let record =
+        get(match_hash.clone(), GetOptions::default())?.ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::Match,
+        })?;
+    for player in [&game_match.home_player, &game_match.away_player] {
+        create_link(
+            player.clone(),
+            match_hash.clone(),
+            LinkTypes::PlayerMatches,
+            (),
+        )?;
+    }
+    start_match_game(
+        match_hash,
+        &game_match,
+        MatchGameLinkTag {
+            game_number: 0,
+            previous_game: None,
+            home_player_wins: 0,
+            away_player_wins: 0,
+        },
+    )?;
+    Ok(record)
+}
+
+fn start_match_game(
+    match_hash: ActionHash,
+    game_match: &Match,
+    match_game_link_tag: MatchGameLinkTag,
+) -> ExternResult<Record> {
+    let (home_player, away_player) = game_match.game_players(match_game_link_tag.game_number);
+    let record = create_game_invite(GameInvite {
+        home_player,
+        away_player,
+        rules: game_match.rules.clone(),
+    })?;
+    create_link(
+        match_hash,
+        record.action_address().clone(),
+        LinkTypes::MatchGames,
+        encode_link_tag(&match_game_link_tag)?,
+    )?;
+    Ok(record)
+}
+
+/// Every match the caller plays in, oldest first
+#[hdk_extern]
+pub fn get_my_matches(_: ()) -> ExternResult<Vec<Record>> {
+    let mut links = get_links(
+        agent_info()?.agent_latest_pubkey,
+        LinkTypes::PlayerMatches,
+        None,
+    )?;
+    links.sort_by_key(|link| link.timestamp);
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    Ok(records.into_iter().flatten().collect())
+}
+
+fn get_game_winner(
+    game_invite_hash: ActionHash,
+    game_invite: &GameInvite,
+) -> ExternResult<Option<AgentPubKey>> {
+    let game_transcript = match get_latest_game_transcript_for_game_invite(game_invite_hash)? {
+        Some(record) => match get_entry_for_record(&record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => game_transcript,
+            _ => return Ok(None),
+        },
+        None => return Ok(None),
+    };
+    Ok(match get_game_turn(&game_transcript) {
+        GameTurn::HomeWon => Some(game_invite.home_player.clone()),
+        GameTurn::AwayWon => Some(game_invite.away_player.clone()),
+        _ => None,
+    })
+}
+
+/// The MatchGames link of each game of the match, by game number. Each game can only be linked
+/// once.
+fn get_match_game_links(match_hash: ActionHash, best_of: usize) -> ExternResult<Vec<Option<Link>>> {
+    let mut links = get_links(match_hash, LinkTypes::MatchGames, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let mut game_links: Vec<Option<Link>> = vec![None; best_of];
+    for link in links {
+        let match_game_link_tag: MatchGameLinkTag = decode_link_tag(&link.tag)?;
+        if let Some(slot) = game_links.get_mut(match_game_link_tag.game_number) {
+            slot.get_or_insert(link);
+        }
+    }
+    Ok(game_links)
+}
+
+/// The score of a match, its games so far and the game to be started next
+#[hdk_extern]
+pub fn get_match_state(match_hash: ActionHash) -> ExternResult<MatchState> {
+    let game_match = match get_entry_for_action(&match_hash)? {
+        Some(EntryTypes::Match(game_match)) => game_match,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::Match,
+            }
+            .into())
+        }
+    };
+    let mut games = Vec::new();
+    let (mut home_player_wins, mut away_player_wins) = (0, 0);
+    // Each game is only started once the one before it is finished
+    for (game_number, link) in get_match_game_links(match_hash, game_match.best_of)?
+        .into_iter()
+        .enumerate()
+    {
+        let game_invite_hash = match link {
+            Some(link) => ActionHash::from(link.target),
+            None => break,
+        };
+        let game_invite = match get_entry_for_action(&game_invite_hash)? {
+            Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+            _ => break,
+        };
+        let winner = get_game_winner(game_invite_hash.clone(), &game_invite)?;
+        if winner.as_ref() == Some(&game_match.home_player) {
+            home_player_wins += 1;
+        } else if winner.is_some() {
+            away_player_wins += 1;
+        }
+        games.push(MatchGame {
+            game_number,
+            game_invite_hash,
+            home_player: game_invite.home_player,
+            away_player: game_invite.away_player,
+            winner,
+        });
+    }
+
+    let winner = if home_player_wins >= game_match.wins_needed() {
+        Some(game_match.home_player.clone())
+    } else if away_player_wins >= game_match.wins_needed() {
+        Some(game_match.away_player.clone())
+    } else {
+        None
+    };
+    let in_play = games.last().is_some_and(|game| game.winner.is_none());
+    let next_game = if winner.is_some() || in_play {
+        None
+    } else {
+        let (home_player, away_player) = game_match.game_players(games.len());
+        Some(NextMatchGame {
+            game_number: games.len(),
+            home_player,
+            away_player,
+        })
+    };
+    Ok(MatchState {
+        game_match,
+        home_player_wins,
+        away_player_wins,
+        games,
+        winner,
+        next_game,
+    })
+}
+
+/// Invites the opponent to the next game of the match once the last one is finished. Home and
+/// away swap every game, so only the next game's home player can start it.
+#[hdk_extern]
+pub fn start_next_match_game(match_hash: ActionHash) -> ExternResult<Record> {
+    let match_state = get_match_state(match_hash.clone())?;
+    if match_state.winner.is_some() {
+        return Err(BattleshipsError::MatchFinished.into());
+    }
+    let next_game = match_state
+        .next_game
+        .ok_or(BattleshipsError::GameNotFinished)?;
+    if next_game.home_player != agent_info()?.agent_latest_pubkey {
+        return Err(BattleshipsError::HomePlayerMustBeAuthor.into());
+    }
+    // The first game is started along with the match, so the next one always has a game before
+    // it unless that link hasn't reached us yet
+    let game_links = get_match_game_links(match_hash.clone(), match_state.game_match.best_of)?;
+    let previous_link = next_game
+        .game_number
+        .checked_sub(1)
+        .and_then(|previous_game_number| game_links.into_iter().nth(previous_game_number))
+        .flatten()
+        .ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::GameInvite,
+        })?;
+    let game_transcript_record = get_latest_game_transcript_for_game_invite(ActionHash::from(
+        previous_link.target,
+    ))?
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::GameTranscript,
+    })?;
+    let previous_game = PreviousMatchGame {
+        link: previous_link.create_link_hash,
+        game_transcript: game_transcript_record.action_address().clone(),
+    };
+    start_match_game(
+        match_hash,
+        &match_state.game_match,
+        MatchGameLinkTag {
+            game_number: next_game.game_number,
+            previous_game: Some(previous_game),
+            home_player_wins: match_state.home_player_wins,
+            away_player_wins: match_state.away_player_wins,
+        },
+    )
+}
@@ -0,0 +1,84 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{get_entry_for_action, Signal};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SendGameMessageInput {
+    pub game_invite_hash: ActionHash,
+    pub content: GameMessageContent,
+}
+
+#[hdk_extern]
+pub fn send_game_message(input: SendGameMessageInput) -> ExternResult<Record> {
+    let game_message = GameMessage {
+        invite: input.game_invite_hash,
+        content: input.content,
+    };
+    let game_message_hash = create_entry(&EntryTypes::GameMessage(game_message.clone()))?;
+    let record = get(game_message_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameMessage,
+        },
+    )?;
+    create_link(
+        game_message.invite,
+        game_message_hash,
+        LinkTypes::GameMessages,
+        (),
+    )?;
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetGameMessagesInput {
+    pub game_invite_hash: ActionHash,
+    // Only messages sent after this time are returned
+    pub since: Option<Timestamp>,
+}
+
+/// Messages for a game in the order they were sent
+#[hdk_extern]
+pub fn get_game_messages(input: GetGameMessagesInput) -> ExternResult<Vec<Record>> {
+    let mut links = get_links(input.game_invite_hash, LinkTypes::GameMessages, None)?;
+    if let Some(since) = input.since {
+        links.retain(|link| link.timestamp > since);
+    }
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    let mut records: Vec<Record> = records.into_iter().flatten().collect();
+    records.sort_by_key(|record| record.action().timestamp());
+    Ok(records)
+}
+
+/// Delivers a message we just sent to our opponent
+pub fn notify_game_message(
+    game_message: &GameMessage,
+    action: SignedActionHashed,
+) -> ExternResult<()> {
+    let game_invite = match get_entry_for_action(&game_message.invite)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
+        }
+    };
+    let opponent = if action.action().author() == &game_invite.home_player {
+        game_invite.away_player
+    } else {
+        game_invite.home_player
+    };
+    remote_signal(
+        Signal::GameMessageReceived {
+            game_invite_hash: game_message.invite.clone(),
+            action,
+            game_message: game_message.clone(),
+        },
+        vec![opponent],
+    )
+}
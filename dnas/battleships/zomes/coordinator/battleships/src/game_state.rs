@@ -1,12 +1,16 @@
-use battleships_integrity::{get_game_turn, EntryTypes, GameTurn};
+use battleships_integrity::{
+    first_player, get_game_turn, sunk_ship_lengths, BattleshipsError, CoinFlipRevealLinkTag,
+    EntryTypes, GameInvite, GameTranscript, GameTurn, Player, RecordKind,
+};
 use hdk::prelude::*;
 
 use crate::{
+    coin_flip_reveal::{get_coin_flip_reveals_for_invite, player_secrets},
     game_transcript::{
         get_latest_game_transcript_revision, get_original_game_transcript_hash_for_game_invite,
     },
     get_entry_for_action, get_entry_for_record,
-    ship_deployment_proof::get_ship_deployment_proofs_for_invite,
+    ship_deployment_proof::get_deployers_for_invite,
 };
 
 #[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
@@ -15,64 +19,118 @@ pub enum GameState {
     AwaitingHomeDeployment,
     AwaitingAwayDeployment,
     AwaitingBothDeployments,
-    GameStarted { turn: GameTurn },
+    // Both fleets deployed, waiting for the coin flip deciding who opens
+    AwaitingCoinFlip {
+        home_player_revealed: bool,
+        away_player_revealed: bool,
+    },
+    GameStarted {
+        turn: GameTurn,
+        // Lengths of each player's own ships that have been proven sunk
+        home_player_sunk_ships: Vec<usize>,
+        away_player_sunk_ships: Vec<usize>,
+    },
 }
 
-#[hdk_extern]
-pub fn get_game_state(game_invite_hash: ActionHash) -> ExternResult<GameState> {
-    let game_invite = match get_entry_for_action(&game_invite_hash)? {
-        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                "Game invite data not found"
-            ))))
-        }
-    };
-    let deployments = get_ship_deployment_proofs_for_invite(game_invite_hash.clone())?;
-    match deployments.len() {
-        0 => return Ok(GameState::AwaitingBothDeployments),
-        1 => {
-            let deployer = deployments[0].action().author();
+/// Works out the state of a game from the authors of its deployment proofs, the coin flip
+/// reveals and its latest transcript, if one has been started
+pub fn compute_game_state(
+    game_invite: &GameInvite,
+    deployers: &[AgentPubKey],
+    reveals: &[CoinFlipRevealLinkTag],
+    game_transcript: Option<&GameTranscript>,
+) -> Result<GameState, BattleshipsError> {
+    match deployers {
+        [] => return Ok(GameState::AwaitingBothDeployments),
+        [deployer] => {
             if deployer == &game_invite.home_player {
                 return Ok(GameState::AwaitingAwayDeployment);
             } else if deployer == &game_invite.away_player {
                 return Ok(GameState::AwaitingHomeDeployment);
             } else {
-                return Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                    "Non player has deployed"
-                ))));
+                return Err(BattleshipsError::NotAPlayer);
             }
         }
-        2 => {
+        [_, _] => {
             // Game can be or has been started
         }
         _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(String::from(
-                "Game invite has too many deployments"
-            ))))
+            return Err(BattleshipsError::WrongDeploymentCount {
+                expected: 2,
+                actual: deployers.len(),
+            })
         }
     }
-    let original_game_transcript_hash =
-        match get_original_game_transcript_hash_for_game_invite(game_invite_hash)? {
-            Some(hash) => hash,
-            // Transcript not started - away player opens game
-            None => {
-                return Ok(GameState::GameStarted {
-                    turn: GameTurn::AwayShot,
-                })
-            }
-        };
-    let game_transcript = match get_latest_game_transcript_revision(original_game_transcript_hash)?
-        .map(|record| get_entry_for_record(&record))
-    {
-        Some(Ok(Some(EntryTypes::GameTranscript(game_transcript)))) => game_transcript,
+    Ok(match game_transcript {
+        Some(game_transcript) => GameState::GameStarted {
+            turn: get_game_turn(game_transcript),
+            home_player_sunk_ships: sunk_ship_lengths(
+                &game_transcript.home_player_hit_or_miss_proofs,
+            ),
+            away_player_sunk_ships: sunk_ship_lengths(
+                &game_transcript.away_player_hit_or_miss_proofs,
+            ),
+        },
+        // Transcript not started - the coin flip decides who opens
+        None => match player_secrets(game_invite, reveals) {
+            (Some(home_player_secret), Some(away_player_secret)) => GameState::GameStarted {
+                turn: match first_player(home_player_secret, away_player_secret) {
+                    Player::Home => GameTurn::HomeShot,
+                    Player::Away => GameTurn::AwayShot,
+                },
+                home_player_sunk_ships: Vec::new(),
+                away_player_sunk_ships: Vec::new(),
+            },
+            (home_player_secret, away_player_secret) => GameState::AwaitingCoinFlip {
+                home_player_revealed: home_player_secret.is_some(),
+                away_player_revealed: away_player_secret.is_some(),
+            },
+        },
+    })
+}
+
+#[hdk_extern]
+pub fn get_game_state(game_invite_hash: ActionHash) -> ExternResult<GameState> {
+    let game_invite = match get_entry_for_action(&game_invite_hash)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
         _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Game Transcript not found".into()
-            )))
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
         }
     };
-    Ok(GameState::GameStarted {
-        turn: get_game_turn(&game_transcript),
-    })
+    let deployers: Vec<AgentPubKey> = get_deployers_for_invite(game_invite_hash.clone())?
+        .into_iter()
+        .map(|deployment_proof_link_tag| deployment_proof_link_tag.deployer)
+        .collect();
+    if deployers.len() < 2 {
+        return compute_game_state(&game_invite, &deployers, &[], None).map_err(WasmError::from);
+    }
+    let game_transcript =
+        match get_original_game_transcript_hash_for_game_invite(game_invite_hash.clone())? {
+            Some(original_game_transcript_hash) => {
+                match get_latest_game_transcript_revision(original_game_transcript_hash)?
+                    .map(|record| get_entry_for_record(&record))
+                {
+                    Some(Ok(Some(EntryTypes::GameTranscript(game_transcript)))) => {
+                        Some(game_transcript)
+                    }
+                    _ => {
+                        return Err(BattleshipsError::NotFound {
+                            kind: RecordKind::GameTranscript,
+                        }
+                        .into())
+                    }
+                }
+            }
+            None => None,
+        };
+    let reveals = match game_transcript {
+        // Only needed to decide who opens
+        Some(_) => Vec::new(),
+        None => get_coin_flip_reveals_for_invite(game_invite_hash)?,
+    };
+    compute_game_state(&game_invite, &deployers, &reveals, game_transcript.as_ref())
+        .map_err(WasmError::from)
 }
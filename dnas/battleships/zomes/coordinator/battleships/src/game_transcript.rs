@@ -2,18 +2,18 @@ use battleships_integrity::*;
 use hdk::prelude::*;
 
 use crate::{
-    get_entry_for_action, get_entry_for_record,
+    coin_flip_reveal::get_coin_flip_reveal_links, get_entry_for_action, get_entry_for_record,
     ship_deployment_proof::get_ship_deployment_proofs_for_invite,
 };
 
 #[hdk_extern]
 pub fn create_game_transcript(game_transcript: GameTranscript) -> ExternResult<Record> {
     let game_transcript_hash = create_entry(&EntryTypes::GameTranscript(game_transcript.clone()))?;
-    let record = get(game_transcript_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest(String::from(
-            "Could not find the newly created GameTranscript"
-        ))
-    ))?;
+    let record = get(game_transcript_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameTranscript,
+        },
+    )?;
     create_link(
         game_transcript.invite,
         game_transcript_hash,
@@ -36,11 +36,11 @@ fn update_game_transcript(
         LinkTypes::GameTranscriptUpdates,
         (),
     )?;
-    let record = get(updated_game_transcript_hash, GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest(String::from(
-            "Could not find the newly updated GameTranscript"
-        ))
-    ))?;
+    let record = get(updated_game_transcript_hash, GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameTranscript,
+        },
+    )?;
     Ok(record)
 }
 
@@ -71,6 +71,39 @@ pub fn get_latest_game_transcript_revision(
     get(latest_game_transcript_hash, GetOptions::default())
 }
 
+/// Every revision of a game's transcript, oldest first. Empty until the first salvo is fired.
+pub fn get_game_transcript_revisions(game_invite_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let original_hash = match get_original_game_transcript_hash_for_game_invite(game_invite_hash)? {
+        Some(original_hash) => original_hash,
+        None => return Ok(Vec::new()),
+    };
+    let mut update_links = get_links(
+        original_hash.clone(),
+        LinkTypes::GameTranscriptUpdates,
+        None,
+    )?;
+    update_links.sort_by_key(|link| link.timestamp);
+    let get_input: Vec<GetInput> = std::iter::once(original_hash)
+        .chain(
+            update_links
+                .into_iter()
+                .map(|link| ActionHash::from(link.target)),
+        )
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    HDK.with(|hdk| hdk.borrow().get(get_input))?
+        .into_iter()
+        .map(|record| {
+            record.ok_or(
+                BattleshipsError::NotFound {
+                    kind: RecordKind::GameTranscript,
+                }
+                .into(),
+            )
+        })
+        .collect()
+}
+
 #[hdk_extern]
 pub fn get_latest_game_transcript_for_game_invite(
     game_invite_hash: ActionHash,
@@ -89,13 +122,29 @@ pub struct FireShotInput {
 
 #[hdk_extern]
 pub fn fire_shot(input: FireShotInput) -> ExternResult<Record> {
+    fire_salvo(FireSalvoInput {
+        game_invite_hash: input.game_invite_hash,
+        shots: vec![input.shot],
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FireSalvoInput {
+    game_invite_hash: ActionHash,
+    shots: Vec<Shot>,
+}
+
+#[hdk_extern]
+pub fn fire_salvo(input: FireSalvoInput) -> ExternResult<Record> {
     match get_original_game_transcript_hash_for_game_invite(input.game_invite_hash.clone())? {
-        None => fire_first_shot(input),
-        Some(original_game_transcript_hash) => fire_next_shot(input, original_game_transcript_hash),
+        None => fire_first_salvo(input),
+        Some(original_game_transcript_hash) => {
+            fire_next_salvo(input, original_game_transcript_hash)
+        }
     }
 }
 
-fn fire_first_shot(input: FireShotInput) -> ExternResult<Record> {
+fn fire_first_salvo(input: FireSalvoInput) -> ExternResult<Record> {
     let GameInvite {
         home_player,
         away_player,
@@ -103,16 +152,19 @@ fn fire_first_shot(input: FireShotInput) -> ExternResult<Record> {
     } = match get_entry_for_action(&input.game_invite_hash)? {
         Some(EntryTypes::GameInvite(game_invite)) => game_invite,
         _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Game invite not found".into()
-            )))
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
         }
     };
     let deployment_proofs = get_ship_deployment_proofs_for_invite(input.game_invite_hash.clone())?;
     if deployment_proofs.len() != 2 {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Expect 2 exisiting deployment proofs".into()
-        )));
+        return Err(BattleshipsError::WrongDeploymentCount {
+            expected: 2,
+            actual: deployment_proofs.len(),
+        }
+        .into());
     }
     let author0 = deployment_proofs[0].action().author();
     let author1 = deployment_proofs[1].action().author();
@@ -128,51 +180,63 @@ fn fire_first_shot(input: FireShotInput) -> ExternResult<Record> {
                 deployment_proofs[0].action_hashed().hash.clone(),
             )
         } else {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Linked deployment proofs don't match players".into()
-            )));
+            return Err(BattleshipsError::NotAPlayer.into());
         };
+    let reveals = get_coin_flip_reveal_links(input.game_invite_hash.clone())?;
+    let reveal_for = |player: &AgentPubKey| {
+        reveals
+            .iter()
+            .find(|(_, tag)| &tag.revealer == player)
+            .ok_or(BattleshipsError::CoinFlipNotRevealed)
+    };
+    let (home_player_coin_flip_reveal, home_player_reveal) = reveal_for(&home_player)?;
+    let (away_player_coin_flip_reveal, away_player_reveal) = reveal_for(&away_player)?;
+    let first_player = first_player(&home_player_reveal.secret, &away_player_reveal.secret);
+    let (home_player_shots, away_player_shots) = match first_player {
+        Player::Home => (vec![input.shots], Vec::new()),
+        Player::Away => (Vec::new(), vec![input.shots]),
+    };
     let game_transcript = GameTranscript {
         invite: input.game_invite_hash.clone(),
         home_player_deployment_proof,
         away_player_deployment_proof,
-        home_player_shots: Vec::new(),
-        away_player_shots: vec![input.shot],
+        home_player_coin_flip_reveal: home_player_coin_flip_reveal.clone(),
+        away_player_coin_flip_reveal: away_player_coin_flip_reveal.clone(),
+        first_player,
+        home_player_shots,
+        away_player_shots,
         home_player_hit_or_miss_proofs: Vec::new(),
         away_player_hit_or_miss_proofs: Vec::new(),
     };
     create_game_transcript(game_transcript)
 }
 
-fn fire_next_shot(
-    input: FireShotInput,
+fn fire_next_salvo(
+    input: FireSalvoInput,
     original_game_transcript_hash: ActionHash,
 ) -> ExternResult<Record> {
     let game_transcript_record = get_latest_game_transcript_revision(
         original_game_transcript_hash.clone(),
     )?
-    .ok_or(wasm_error!(WasmErrorInner::Guest(
-        "GameTranscript Record not found".into()
-    )))?;
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::GameTranscript,
+    })?;
 
     let mut game_transcript = match get_entry_for_record(&game_transcript_record)? {
         Some(EntryTypes::GameTranscript(transcript)) => transcript,
         _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Transcript not found".into()
-            )))
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameTranscript,
+            }
+            .into())
         }
     };
     let game_turn = get_game_turn(&game_transcript);
     debug!("{:?}", &game_turn);
     match game_turn {
-        GameTurn::HomeShot => game_transcript.home_player_shots.push(input.shot),
-        GameTurn::AwayShot => game_transcript.away_player_shots.push(input.shot),
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Wrong time to fire shot".into()
-            )))
-        }
+        GameTurn::HomeShot => game_transcript.home_player_shots.push(input.shots),
+        GameTurn::AwayShot => game_transcript.away_player_shots.push(input.shots),
+        _ => return Err(BattleshipsError::NotYourTurn { turn: game_turn }.into()),
     }
 
     update_game_transcript(
@@ -190,47 +254,64 @@ pub struct ProveHitOrMissInput {
 
 #[hdk_extern]
 pub fn prove_hit_or_miss(input: ProveHitOrMissInput) -> ExternResult<Record> {
+    prove_salvo(ProveSalvoInput {
+        game_invite_hash: input.game_invite_hash,
+        hit_or_miss_proofs: vec![input.hit_or_miss_proof],
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProveSalvoInput {
+    game_invite_hash: ActionHash,
+    // One proof per shot of the opponent's latest salvo, in the same order
+    hit_or_miss_proofs: Vec<HitOrMissProof>,
+}
+
+#[hdk_extern]
+pub fn prove_salvo(input: ProveSalvoInput) -> ExternResult<Record> {
     let original_game_transcript_hash =
         match get_original_game_transcript_hash_for_game_invite(input.game_invite_hash.clone())? {
             None => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    "Transcript not found".into()
-                )))
+                return Err(BattleshipsError::NotFound {
+                    kind: RecordKind::GameTranscript,
+                }
+                .into())
             }
             Some(original_game_transcript_hash) => original_game_transcript_hash,
         };
     let game_transcript_record = get_latest_game_transcript_revision(
         original_game_transcript_hash.clone(),
     )?
-    .ok_or(wasm_error!(WasmErrorInner::Guest(
-        "GameTranscript Record not found".into()
-    )))?;
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::GameTranscript,
+    })?;
 
     let mut game_transcript = match get_entry_for_record(&game_transcript_record)? {
         Some(EntryTypes::GameTranscript(transcript)) => transcript,
         _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Latest Game Transcript not found".into()
-            )))
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameTranscript,
+            }
+            .into())
         }
     };
     let game_turn = get_game_turn(&game_transcript);
     let outcomes_to_grow = match game_turn {
         GameTurn::AwayProof => &mut game_transcript.away_player_hit_or_miss_proofs,
         GameTurn::HomeProof => &mut game_transcript.home_player_hit_or_miss_proofs,
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Wrong time to prove".into()
-            )))
-        }
+        _ => return Err(BattleshipsError::NotYourTurn { turn: game_turn }.into()),
     };
-    let hit_or_miss_proof_hash =
-        create_entry(&EntryTypes::HitOrMissProof(input.hit_or_miss_proof.clone()))?;
-    let shot_outcome = ShotOutcome {
-        hit: input.hit_or_miss_proof.hit,
-        proof_hash: hit_or_miss_proof_hash,
-    };
-    outcomes_to_grow.push(shot_outcome);
+    let mut shot_outcomes = Vec::new();
+    for hit_or_miss_proof in input.hit_or_miss_proofs {
+        let hit_or_miss_proof_hash =
+            create_entry(&EntryTypes::HitOrMissProof(hit_or_miss_proof.clone()))?;
+        shot_outcomes.push(ShotOutcome {
+            hit: hit_or_miss_proof.hit,
+            proof_hash: hit_or_miss_proof_hash,
+            sunk_ship_length: hit_or_miss_proof.sunk_ship_length(),
+        });
+    }
+    outcomes_to_grow.push(shot_outcomes);
 
     update_game_transcript(
         original_game_transcript_hash,
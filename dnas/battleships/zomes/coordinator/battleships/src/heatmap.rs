@@ -0,0 +1,49 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_latest_game_transcript_for_game_invite, get_entry_for_action,
+    get_entry_for_record,
+};
+
+/// Scores each square of the opponent's board by how many legal placements of their surviving
+/// fleet cover it, from the public shots and outcomes of the game. Placements have to explain
+/// every hit on a ship not yet sunk and never cross a miss or a sunk ship, so the hottest
+/// squares are next to the hits. Squares already fired at score zero.
+#[hdk_extern]
+pub fn get_shot_heatmap(game_invite_hash: ActionHash) -> ExternResult<Heatmap> {
+    let game_invite = match get_entry_for_action(&game_invite_hash)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
+        }
+    };
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let me = if my_pub_key == game_invite.home_player {
+        Player::Home
+    } else if my_pub_key == game_invite.away_player {
+        Player::Away
+    } else {
+        return Err(BattleshipsError::NotAPlayer.into());
+    };
+    let game_transcript = match get_latest_game_transcript_for_game_invite(game_invite_hash)? {
+        Some(record) => match get_entry_for_record(&record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => game_transcript,
+            _ => {
+                return Err(BattleshipsError::NotFound {
+                    kind: RecordKind::GameTranscript,
+                }
+                .into())
+            }
+        },
+        // Nobody has fired yet
+        None => return Ok(shot_heatmap::<ShotOutcome>(&[], &[])),
+    };
+    Ok(shot_heatmap(
+        game_transcript.shots(&me),
+        game_transcript.outcomes(&me.opponent()),
+    ))
+}
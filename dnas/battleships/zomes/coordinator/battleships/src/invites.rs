@@ -1,5 +1,7 @@
 use battleships_integrity::*;
 use hdk::prelude::*;
+
+use crate::profile::get_latest_profiles;
 #[hdk_extern]
 pub fn get_invites(participant: AgentPubKey) -> ExternResult<Vec<Record>> {
     let links = get_links(participant, LinkTypes::Invites, None)?;
@@ -11,3 +13,61 @@ pub fn get_invites(participant: AgentPubKey) -> ExternResult<Vec<Record>> {
     let records: Vec<Record> = records.into_iter().flatten().collect();
     Ok(records)
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InviteListing {
+    pub game_invite_hash: ActionHash,
+    pub opponent: AgentPubKey,
+    pub is_home_player: bool,
+    pub rules: GameRules,
+    pub created_at: Timestamp,
+    pub opponent_profile: Option<Profile>,
+}
+
+impl InviteListing {
+    pub fn game_invite(&self, participant: &AgentPubKey) -> GameInvite {
+        let (home_player, away_player) = if self.is_home_player {
+            (participant.clone(), self.opponent.clone())
+        } else {
+            (self.opponent.clone(), participant.clone())
+        };
+        GameInvite {
+            home_player,
+            away_player,
+            rules: self.rules.clone(),
+        }
+    }
+}
+
+pub fn invite_listing_from_link(link: &Link) -> ExternResult<InviteListing> {
+    let invite_link_tag: InviteLinkTag = decode_link_tag(&link.tag)?;
+    Ok(InviteListing {
+        game_invite_hash: ActionHash::from(link.target.clone()),
+        opponent: invite_link_tag.opponent,
+        is_home_player: invite_link_tag.is_home_player,
+        rules: invite_link_tag.rules,
+        created_at: invite_link_tag.created_at,
+        opponent_profile: None,
+    })
+}
+
+/// Invites for a participant, read from the link tags without fetching the invites, along
+/// with each opponent's profile
+#[hdk_extern]
+pub fn get_invite_listings(participant: AgentPubKey) -> ExternResult<Vec<InviteListing>> {
+    let mut invite_listings = get_links(participant, LinkTypes::Invites, None)?
+        .iter()
+        .map(invite_listing_from_link)
+        .collect::<ExternResult<Vec<InviteListing>>>()?;
+    let opponents = invite_listings
+        .iter()
+        .map(|invite_listing| invite_listing.opponent.clone())
+        .collect();
+    for (invite_listing, profile) in invite_listings
+        .iter_mut()
+        .zip(get_latest_profiles(opponents)?)
+    {
+        invite_listing.opponent_profile = profile;
+    }
+    Ok(invite_listings)
+}
@@ -0,0 +1,159 @@
+use std::collections::{BTreeMap, HashSet};
+
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_latest_game_transcript_for_game_invite, get_entry_for_action,
+    get_entry_for_record, profile::get_latest_profiles,
+};
+
+const INITIAL_RATING: f64 = 1200.0;
+const K_FACTOR: f64 = 32.0;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Rating {
+    pub agent: AgentPubKey,
+    pub rating: f64,
+    pub games: usize,
+    pub profile: Option<Profile>,
+}
+
+#[hdk_extern]
+pub fn record_game_result(game_invite_hash: ActionHash) -> ExternResult<Record> {
+    let game_invite = match get_entry_for_action(&game_invite_hash)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
+        }
+    };
+    let game_transcript_record = get_latest_game_transcript_for_game_invite(
+        game_invite_hash.clone(),
+    )?
+    .ok_or(BattleshipsError::NotFound {
+        kind: RecordKind::GameTranscript,
+    })?;
+    let game_transcript = match get_entry_for_record(&game_transcript_record)? {
+        Some(EntryTypes::GameTranscript(game_transcript)) => game_transcript,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameTranscript,
+            }
+            .into())
+        }
+    };
+    let (winner, loser) = match get_game_turn(&game_transcript) {
+        GameTurn::HomeWon => (game_invite.home_player, game_invite.away_player),
+        GameTurn::AwayWon => (game_invite.away_player, game_invite.home_player),
+        _ => return Err(BattleshipsError::GameNotFinished.into()),
+    };
+    let game_result = GameResult {
+        invite: game_invite_hash,
+        game_transcript: game_transcript_record.action_hashed().hash.clone(),
+        winner,
+        loser,
+    };
+    let game_result_hash = create_entry(&EntryTypes::GameResult(game_result))?;
+    let record = get(game_result_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::GameResult,
+        },
+    )?;
+    create_link(
+        ladder_anchor_hash()?,
+        game_result_hash,
+        LinkTypes::LadderResults,
+        (),
+    )?;
+    Ok(record)
+}
+
+fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+/// Replays every ladder result in the order it was recorded. Either player may record the
+/// result of a game, so only the first result for each invite counts.
+pub fn compute_ratings(results: &[GameResult]) -> Vec<Rating> {
+    let mut ratings: BTreeMap<AgentPubKey, (f64, usize)> = BTreeMap::new();
+    let mut counted_invites = HashSet::new();
+    for result in results {
+        if !counted_invites.insert(result.invite.clone()) {
+            continue;
+        }
+        let (winner_rating, _) = *ratings
+            .entry(result.winner.clone())
+            .or_insert((INITIAL_RATING, 0));
+        let (loser_rating, _) = *ratings
+            .entry(result.loser.clone())
+            .or_insert((INITIAL_RATING, 0));
+        let delta = K_FACTOR * (1.0 - expected_score(winner_rating, loser_rating));
+        if let Some((rating, games)) = ratings.get_mut(&result.winner) {
+            *rating += delta;
+            *games += 1;
+        }
+        if let Some((rating, games)) = ratings.get_mut(&result.loser) {
+            *rating -= delta;
+            *games += 1;
+        }
+    }
+    ratings
+        .into_iter()
+        .map(|(agent, (rating, games))| Rating {
+            agent,
+            rating,
+            games,
+            profile: None,
+        })
+        .collect()
+}
+
+fn get_ladder_results() -> ExternResult<Vec<GameResult>> {
+    let mut links = get_links(ladder_anchor_hash()?, LinkTypes::LadderResults, None)?;
+    links.sort_by(|link_a, link_b| link_a.timestamp.cmp(&link_b.timestamp));
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    let mut results = Vec::new();
+    for record in records.into_iter().flatten() {
+        if let Some(EntryTypes::GameResult(game_result)) = get_entry_for_record(&record)? {
+            results.push(game_result);
+        }
+    }
+    Ok(results)
+}
+
+#[hdk_extern]
+pub fn get_leaderboard(limit: usize) -> ExternResult<Vec<Rating>> {
+    let mut ratings = compute_ratings(&get_ladder_results()?);
+    ratings.sort_by(|a, b| b.rating.total_cmp(&a.rating));
+    ratings.truncate(limit);
+    with_profiles(ratings)
+}
+
+fn with_profiles(mut ratings: Vec<Rating>) -> ExternResult<Vec<Rating>> {
+    let agents = ratings.iter().map(|rating| rating.agent.clone()).collect();
+    for (rating, profile) in ratings.iter_mut().zip(get_latest_profiles(agents)?) {
+        rating.profile = profile;
+    }
+    Ok(ratings)
+}
+
+#[hdk_extern]
+pub fn get_rating(agent: AgentPubKey) -> ExternResult<Rating> {
+    let rating = compute_ratings(&get_ladder_results()?)
+        .into_iter()
+        .find(|rating| rating.agent == agent)
+        .unwrap_or(Rating {
+            agent,
+            rating: INITIAL_RATING,
+            games: 0,
+            profile: None,
+        });
+    Ok(with_profiles(vec![rating])?.remove(0))
+}
@@ -1,9 +1,24 @@
+pub mod coin_flip_reveal;
+pub mod fleet_reveal;
+pub mod game_export;
 pub mod game_invite;
+pub mod game_match;
+pub mod game_message;
 pub mod game_state;
 pub mod game_transcript;
+pub mod heatmap;
 pub mod invites;
+pub mod ladder;
+pub mod membrane_proof;
+pub mod my_games;
+pub mod profile;
+pub mod remote_signal;
+pub mod replay;
 pub mod ship_deployment;
 pub mod ship_deployment_proof;
+pub mod spectators;
+pub mod stats;
+pub mod tournament;
 use battleships_integrity::*;
 use hdk::prelude::*;
 #[hdk_extern]
@@ -42,6 +57,18 @@ pub enum Signal {
         action: SignedActionHashed,
         link_type: LinkTypes,
     },
+    GameMoved {
+        game_invite_hash: ActionHash,
+        game_transcript_hash: ActionHash,
+        turn: GameTurn,
+        action: SignedActionHashed,
+        game_transcript: GameTranscript,
+    },
+    GameMessageReceived {
+        game_invite_hash: ActionHash,
+        action: SignedActionHashed,
+        game_message: GameMessage,
+    },
 }
 #[hdk_extern(infallible)]
 pub fn post_commit(committed_actions: Vec<SignedActionHashed>) {
@@ -55,6 +82,19 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
     match action.hashed.content.clone() {
         Action::Create(_create) => {
             if let Ok(Some(app_entry)) = get_entry_for_action(&action.hashed.hash) {
+                if let EntryTypes::GameTranscript(game_transcript) = &app_entry {
+                    if let Err(err) = spectators::notify_game_move(game_transcript, action.clone())
+                    {
+                        error!("Error notifying game move: {:?}", err);
+                    }
+                }
+                if let EntryTypes::GameMessage(game_message) = &app_entry {
+                    if let Err(err) =
+                        game_message::notify_game_message(game_message, action.clone())
+                    {
+                        error!("Error delivering game message: {:?}", err);
+                    }
+                }
                 let signal = Signal::EntryCreated {
                     action,
                     app_entry: app_entry.clone(),
@@ -70,6 +110,12 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
         }
         Action::Update(update) => {
             if let Ok(Some(app_entry)) = get_entry_for_action(&action.hashed.hash) {
+                if let EntryTypes::GameTranscript(game_transcript) = &app_entry {
+                    if let Err(err) = spectators::notify_game_move(game_transcript, action.clone())
+                    {
+                        error!("Error notifying game move: {:?}", err);
+                    }
+                }
                 if let Ok(Some(original_app_entry)) =
                     get_entry_for_action(&update.original_action_address)
                 {
@@ -101,9 +147,9 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
         }
         Action::DeleteLink(delete_link) => {
             let record = get(delete_link.link_add_address.clone(), GetOptions::default())?.ok_or(
-                wasm_error!(WasmErrorInner::Guest(
-                    "Failed to fetch CreateLink action".to_string()
-                )),
+                BattleshipsError::NotFound {
+                    kind: RecordKind::Link,
+                },
             )?;
             match record.action() {
                 Action::CreateLink(create_link) => {
@@ -115,9 +161,10 @@ fn signal_action(action: SignedActionHashed) -> ExternResult<()> {
                     Ok(())
                 }
                 _ => {
-                    return Err(wasm_error!(WasmErrorInner::Guest(
-                        "Create Link should exist".to_string()
-                    )));
+                    return Err(BattleshipsError::NotFound {
+                        kind: RecordKind::Link,
+                    }
+                    .into());
                 }
             }
         }
@@ -163,6 +210,15 @@ fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
     let sig: Signal = signal
         .decode()
         .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+    // Anyone can call this function, so only pass on signals the caller could have sent
+    let provenance = call_info()?.provenance;
+    if !remote_signal::is_authentic(&sig, &provenance)? {
+        warn!(
+            "Dropping unauthenticated remote signal from {:?}",
+            provenance
+        );
+        return Ok(());
+    }
     emit_signal(sig)?;
     Ok(())
 }
@@ -0,0 +1,26 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+pub fn get_dna_properties(_: ()) -> ExternResult<DnaProperties> {
+    dna_properties()
+}
+
+/// Signs a membrane proof letting `agent` join the network. Only the progenitor can mint
+/// proofs; it can do so from its own conductor without the new member being online, and
+/// hand the returned bytes over to be passed in when the member installs the app.
+#[hdk_extern]
+pub fn mint_membrane_proof(agent: AgentPubKey) -> ExternResult<SerializedBytes> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    match dna_properties()?.progenitor {
+        Some(progenitor) if AgentPubKey::from(progenitor) == my_pub_key => {}
+        _ => return Err(BattleshipsError::NotProgenitor.into()),
+    }
+    let payload = MembraneProofPayload {
+        agent,
+        dna_hash: dna_info()?.hash,
+    };
+    let signature = sign(my_pub_key, payload.clone())?;
+    SerializedBytes::try_from(SignedMembraneProof { payload, signature })
+        .map_err(|e| wasm_error!(e))
+}
@@ -0,0 +1,217 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_state::{compute_game_state, GameState},
+    get_entry_for_record,
+    invites::get_invite_listings,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum MyGamesFilter {
+    All,
+    MyTurn,
+    // Unfinished games where the opponent has to move
+    Waiting,
+    Finished,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MyGame {
+    pub game_invite_hash: ActionHash,
+    pub game_invite: GameInvite,
+    pub opponent: AgentPubKey,
+    pub opponent_profile: Option<Profile>,
+    pub state: GameState,
+    pub last_move_at: Timestamp,
+    pub my_turn: bool,
+}
+
+pub fn is_my_turn(state: &GameState, is_home_player: bool) -> bool {
+    match state {
+        GameState::AwaitingBothDeployments => true,
+        GameState::AwaitingHomeDeployment => is_home_player,
+        GameState::AwaitingAwayDeployment => !is_home_player,
+        GameState::AwaitingCoinFlip {
+            home_player_revealed,
+            away_player_revealed,
+        } => {
+            if is_home_player {
+                !home_player_revealed
+            } else {
+                !away_player_revealed
+            }
+        }
+        GameState::GameStarted { turn, .. } => matches!(
+            (turn, is_home_player),
+            (GameTurn::HomeProof | GameTurn::HomeShot, true)
+                | (GameTurn::AwayShot | GameTurn::AwayProof, false)
+        ),
+    }
+}
+
+pub fn is_finished(state: &GameState) -> bool {
+    matches!(
+        state,
+        GameState::GameStarted {
+            turn: GameTurn::HomeWon | GameTurn::AwayWon,
+            ..
+        }
+    )
+}
+
+impl MyGamesFilter {
+    pub fn matches(&self, game: &MyGame) -> bool {
+        match self {
+            MyGamesFilter::All => true,
+            MyGamesFilter::MyTurn => game.my_turn,
+            MyGamesFilter::Waiting => !game.my_turn && !is_finished(&game.state),
+            MyGamesFilter::Finished => is_finished(&game.state),
+        }
+    }
+}
+
+fn get_records(hashes: Vec<ActionHash>) -> ExternResult<Vec<Option<Record>>> {
+    let get_input: Vec<GetInput> = hashes
+        .into_iter()
+        .map(|hash| GetInput::new(hash.into(), GetOptions::default()))
+        .collect();
+    HDK.with(|hdk| hdk.borrow().get(get_input))
+}
+
+fn get_links_for_bases(bases: Vec<(ActionHash, LinkTypes)>) -> ExternResult<Vec<Vec<Link>>> {
+    let get_links_input = bases
+        .into_iter()
+        .map(|(base, link_type)| {
+            Ok(GetLinksInput::new(
+                base.into(),
+                link_type.try_into_filter()?,
+                None,
+            ))
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    HDK.with(|hdk| hdk.borrow().get_links(get_links_input))
+}
+
+/// All games the caller has been invited to. Invites and deployments are read from link
+/// tags and the remaining lookups are batched, so the number of host calls doesn't grow
+/// with the number of games
+#[hdk_extern]
+pub fn get_my_games(filter: MyGamesFilter) -> ExternResult<Vec<MyGame>> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let invites = get_invite_listings(my_pub_key.clone())?;
+
+    // Deployment proof, transcript and coin flip links for every invite in one call
+    let mut link_bases = Vec::new();
+    for invite in invites.iter() {
+        link_bases.push((invite.game_invite_hash.clone(), LinkTypes::DeploymentProofs));
+        link_bases.push((invite.game_invite_hash.clone(), LinkTypes::GameTranscript));
+        link_bases.push((invite.game_invite_hash.clone(), LinkTypes::CoinFlipReveals));
+    }
+    let mut invite_links = get_links_for_bases(link_bases)?.into_iter();
+    let mut deployments = Vec::new();
+    let mut original_game_transcript_hashes = Vec::new();
+    let mut coin_flip_reveals = Vec::new();
+    for _ in invites.iter() {
+        deployments.push(
+            invite_links
+                .next()
+                .unwrap_or_default()
+                .iter()
+                .map(|link| decode_link_tag::<DeploymentProofLinkTag>(&link.tag))
+                .collect::<ExternResult<Vec<DeploymentProofLinkTag>>>()?,
+        );
+        original_game_transcript_hashes.push(
+            invite_links
+                .next()
+                .unwrap_or_default()
+                .pop()
+                .map(|link| ActionHash::from(link.target)),
+        );
+        coin_flip_reveals.push(
+            invite_links
+                .next()
+                .unwrap_or_default()
+                .iter()
+                .map(|link| decode_link_tag::<CoinFlipRevealLinkTag>(&link.tag))
+                .collect::<ExternResult<Vec<CoinFlipRevealLinkTag>>>()?,
+        );
+    }
+
+    let update_links = get_links_for_bases(
+        original_game_transcript_hashes
+            .iter()
+            .flatten()
+            .map(|hash| (hash.clone(), LinkTypes::GameTranscriptUpdates))
+            .collect(),
+    )?;
+    let latest_game_transcript_hashes: Vec<ActionHash> = original_game_transcript_hashes
+        .iter()
+        .flatten()
+        .zip(update_links)
+        .map(|(original_hash, links)| {
+            links
+                .into_iter()
+                .max_by(|link_a, link_b| link_a.timestamp.cmp(&link_b.timestamp))
+                .map(|link| ActionHash::from(link.target))
+                .unwrap_or_else(|| original_hash.clone())
+        })
+        .collect();
+    let mut game_transcript_records = get_records(latest_game_transcript_hashes)?.into_iter();
+
+    let mut games = Vec::new();
+    for (((invite, deployments), original_hash), reveals) in invites
+        .into_iter()
+        .zip(deployments)
+        .zip(original_game_transcript_hashes)
+        .zip(coin_flip_reveals)
+    {
+        let transcript_record = match original_hash {
+            Some(_) => game_transcript_records.next().flatten(),
+            None => None,
+        };
+        let game_transcript = match transcript_record.as_ref().map(get_entry_for_record) {
+            Some(Ok(Some(EntryTypes::GameTranscript(game_transcript)))) => Some(game_transcript),
+            _ => None,
+        };
+        let game_invite = invite.game_invite(&my_pub_key);
+        let deployers: Vec<AgentPubKey> = deployments
+            .iter()
+            .map(|deployment| deployment.deployer.clone())
+            .collect();
+        let state = match compute_game_state(
+            &game_invite,
+            &deployers,
+            &reveals,
+            game_transcript.as_ref(),
+        ) {
+            Ok(state) => state,
+            // Skip games with inconsistent data rather than failing the whole dashboard
+            Err(_) => continue,
+        };
+        let last_move_at = transcript_record
+            .map(|record| record.action().timestamp())
+            .or_else(|| {
+                deployments
+                    .iter()
+                    .map(|deployment| deployment.deployed_at)
+                    .max()
+            })
+            .unwrap_or(invite.created_at);
+        let game = MyGame {
+            game_invite_hash: invite.game_invite_hash,
+            my_turn: is_my_turn(&state, invite.is_home_player),
+            opponent: invite.opponent,
+            opponent_profile: invite.opponent_profile,
+            game_invite,
+            state,
+            last_move_at,
+        };
+        if filter.matches(&game) {
+            games.push(game);
+        }
+    }
+    games.sort_by(|a, b| b.last_move_at.cmp(&a.last_move_at));
+    Ok(games)
+}
@@ -0,0 +1,190 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentProfile {
+    pub agent: AgentPubKey,
+    pub profile: Profile,
+}
+
+fn link_nickname(nickname: &str, profile_hash: &ActionHash) -> ExternResult<()> {
+    create_link(
+        nickname_prefix_path(nickname).path_entry_hash()?,
+        agent_info()?.agent_latest_pubkey,
+        LinkTypes::NicknamePrefix,
+        nickname_link_tag(nickname, profile_hash)?,
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn create_profile(profile: Profile) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let profile_hash = create_entry(&EntryTypes::Profile(profile.clone()))?;
+    let record =
+        get(profile_hash.clone(), GetOptions::default())?.ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::Profile,
+        })?;
+    create_link(
+        my_pub_key,
+        profile_hash.clone(),
+        LinkTypes::AgentToProfile,
+        (),
+    )?;
+    link_nickname(&profile.nickname, &profile_hash)?;
+    Ok(record)
+}
+
+#[hdk_extern]
+pub fn update_profile(profile: Profile) -> ExternResult<Record> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    // Every revision updates the original so that its details list them all
+    let original_profile_hash = get_links(my_pub_key.clone(), LinkTypes::AgentToProfile, None)?
+        .into_iter()
+        .min_by_key(|link| link.timestamp)
+        .map(|link| ActionHash::from(link.target))
+        .ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::Profile,
+        })?;
+    let previous_record = get_latest_profile_records(vec![my_pub_key])?
+        .pop()
+        .flatten()
+        .ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::Profile,
+        })?;
+    let previous_profile: Profile = previous_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    let updated_profile_hash =
+        update_entry(original_profile_hash, &EntryTypes::Profile(profile.clone()))?;
+    // Links for the old nickname are left in place and filtered out when searching
+    if previous_profile.nickname.to_lowercase() != profile.nickname.to_lowercase() {
+        link_nickname(&profile.nickname, &updated_profile_hash)?;
+    }
+    get(updated_profile_hash, GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::Profile,
+        }
+        .into(),
+    )
+}
+
+/// Latest revision of each agent's profile, in the order the agents were given. Takes three
+/// batched host calls however many agents are asked for.
+pub fn get_latest_profile_records(agents: Vec<AgentPubKey>) -> ExternResult<Vec<Option<Record>>> {
+    let get_links_input = agents
+        .into_iter()
+        .map(|agent| {
+            Ok(GetLinksInput::new(
+                agent.into(),
+                LinkTypes::AgentToProfile.try_into_filter()?,
+                None,
+            ))
+        })
+        .collect::<ExternResult<Vec<GetLinksInput>>>()?;
+    let links = HDK.with(|hdk| hdk.borrow().get_links(get_links_input))?;
+    let original_hashes: Vec<Option<ActionHash>> = links
+        .into_iter()
+        .map(|links| {
+            links
+                .into_iter()
+                .min_by_key(|link| link.timestamp)
+                .map(|link| ActionHash::from(link.target))
+        })
+        .collect();
+    let get_details_input: Vec<GetInput> = original_hashes
+        .iter()
+        .flatten()
+        .map(|hash| GetInput::new(hash.clone().into(), GetOptions::default()))
+        .collect();
+    let mut details = HDK
+        .with(|hdk| hdk.borrow().get_details(get_details_input))?
+        .into_iter();
+    let latest_hashes: Vec<Option<ActionHash>> = original_hashes
+        .into_iter()
+        .map(|original_hash| {
+            let original_hash = original_hash?;
+            match details.next().flatten() {
+                Some(Details::Record(record_details)) => Some(
+                    record_details
+                        .updates
+                        .into_iter()
+                        .max_by_key(|update| update.action().timestamp())
+                        .map(|update| update.hashed.hash)
+                        .unwrap_or(original_hash),
+                ),
+                _ => None,
+            }
+        })
+        .collect();
+    let get_input: Vec<GetInput> = latest_hashes
+        .iter()
+        .flatten()
+        .map(|hash| GetInput::new(hash.clone().into(), GetOptions::default()))
+        .collect();
+    let mut records = HDK.with(|hdk| hdk.borrow().get(get_input))?.into_iter();
+    Ok(latest_hashes
+        .into_iter()
+        .map(|hash| hash.and_then(|_| records.next().flatten()))
+        .collect())
+}
+
+/// Latest profile of each agent, in the order the agents were given
+pub fn get_latest_profiles(agents: Vec<AgentPubKey>) -> ExternResult<Vec<Option<Profile>>> {
+    Ok(get_latest_profile_records(agents)?
+        .into_iter()
+        .map(|record| record?.entry().to_app_option().ok().flatten())
+        .collect())
+}
+
+#[hdk_extern]
+pub fn get_agent_profile(agent: AgentPubKey) -> ExternResult<Option<Record>> {
+    Ok(get_latest_profile_records(vec![agent])?.pop().flatten())
+}
+
+#[hdk_extern]
+pub fn get_agent_profiles(agents: Vec<AgentPubKey>) -> ExternResult<Vec<AgentProfile>> {
+    let profiles = get_latest_profiles(agents.clone())?;
+    Ok(agents
+        .into_iter()
+        .zip(profiles)
+        .filter_map(|(agent, profile)| {
+            Some(AgentProfile {
+                agent,
+                profile: profile?,
+            })
+        })
+        .collect())
+}
+
+/// Agents whose current nickname starts with the given prefix, ignoring case
+#[hdk_extern]
+pub fn search_profiles(nickname_prefix: String) -> ExternResult<Vec<AgentProfile>> {
+    if nickname_prefix.chars().count() < NICKNAME_PREFIX_LENGTH {
+        return Ok(vec![]);
+    }
+    let links = get_links(
+        nickname_prefix_path(&nickname_prefix).path_entry_hash()?,
+        LinkTypes::NicknamePrefix,
+        Some(nickname_search_tag(&nickname_prefix)),
+    )?;
+    let mut agents: Vec<AgentPubKey> = links
+        .into_iter()
+        .map(|link| AgentPubKey::from(EntryHash::from(link.target)))
+        .collect();
+    agents.sort();
+    agents.dedup();
+    let nickname_prefix = nickname_prefix.to_lowercase();
+    Ok(get_agent_profiles(agents)?
+        .into_iter()
+        .filter(|agent_profile| {
+            agent_profile
+                .profile
+                .nickname
+                .to_lowercase()
+                .starts_with(&nickname_prefix)
+        })
+        .collect())
+}
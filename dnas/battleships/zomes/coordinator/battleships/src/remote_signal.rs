@@ -0,0 +1,65 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{get_entry_for_action, Signal};
+
+/// Whether the action really was authored and signed by `author`
+fn is_signed_by(action: &SignedActionHashed, author: &AgentPubKey) -> ExternResult<bool> {
+    if action.action().author() != author
+        || hash_action(action.action().clone())? != action.hashed.hash
+    {
+        return Ok(false);
+    }
+    verify_signature(
+        author.clone(),
+        action.signature().clone(),
+        action.action().clone(),
+    )
+}
+
+fn is_for_entry<T>(action: &SignedActionHashed, entry: T) -> ExternResult<bool>
+where
+    Entry: TryFrom<T, Error = WasmError>,
+{
+    Ok(action.action().entry_hash() == Some(&hash_entry(entry)?))
+}
+
+fn is_participant(game_invite_hash: &ActionHash, agent: &AgentPubKey) -> ExternResult<bool> {
+    Ok(match get_entry_for_action(game_invite_hash)? {
+        Some(EntryTypes::GameInvite(game_invite)) => {
+            &game_invite.home_player == agent || &game_invite.away_player == agent
+        }
+        _ => false,
+    })
+}
+
+/// Checks a remote signal against the agent that sent it. Signals must carry the signed action
+/// they announce, which must have been authored and signed by the sender, be for the entry in
+/// the signal and belong to a game the sender is playing. Signals are sent from post_commit,
+/// before the action can have reached the DHT, so the signature is all there is to go on. Any
+/// other kind of signal is only ever emitted locally.
+pub fn is_authentic(signal: &Signal, provenance: &AgentPubKey) -> ExternResult<bool> {
+    match signal {
+        Signal::GameMoved {
+            game_invite_hash,
+            game_transcript_hash,
+            turn,
+            action,
+            game_transcript,
+        } => Ok(&action.hashed.hash == game_transcript_hash
+            && &game_transcript.invite == game_invite_hash
+            && &get_game_turn(game_transcript) == turn
+            && is_signed_by(action, provenance)?
+            && is_for_entry(action, game_transcript)?
+            && is_participant(game_invite_hash, provenance)?),
+        Signal::GameMessageReceived {
+            game_invite_hash,
+            action,
+            game_message,
+        } => Ok(&game_message.invite == game_invite_hash
+            && is_signed_by(action, provenance)?
+            && is_for_entry(action, game_message)?
+            && is_participant(game_invite_hash, provenance)?),
+        _ => Ok(false),
+    }
+}
@@ -0,0 +1,163 @@
+use battleships_integrity::{constants::BOARD_SIZE, *};
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_game_transcript_revisions, get_entry_for_record, spectators::CellView,
+};
+
+/// A single shot and, once proven, its outcome
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReplayMove {
+    // Who fired
+    pub player: Player,
+    pub shot: Shot,
+    // None until the defender proves it
+    pub outcome: Option<ShotResult>,
+    pub proof_hash: Option<ActionHash>,
+    // Of the transcript revisions that recorded the shot and its outcome
+    pub fired_at: Timestamp,
+    pub proven_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameSnapshot {
+    pub move_index: usize,
+    // Shots fired at each player's fleet, indexed [x][y]
+    pub home_board: Vec<Vec<CellView>>,
+    pub away_board: Vec<Vec<CellView>>,
+    pub home_player_sunk_ships: Vec<usize>,
+    pub away_player_sunk_ships: Vec<usize>,
+}
+
+fn player_index(player: &Player) -> usize {
+    match player {
+        Player::Home => 0,
+        Player::Away => 1,
+    }
+}
+
+/// Every shot of the game in the order it was fired, from the transcript's revisions oldest
+/// first. Each revision only ever appends to the one before it.
+pub fn replay_moves(revisions: &[(Timestamp, GameTranscript)]) -> Vec<ReplayMove> {
+    let mut moves: Vec<ReplayMove> = Vec::new();
+    // Index into moves of each player's shots, in the order they fired them
+    let mut shot_moves: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+    let mut proven = [0; 2];
+    for (timestamp, game_transcript) in revisions {
+        for player in [Player::Home, Player::Away] {
+            let fired = &mut shot_moves[player_index(&player)];
+            for shot in game_transcript
+                .shots(&player)
+                .iter()
+                .flatten()
+                .skip(fired.len())
+            {
+                fired.push(moves.len());
+                moves.push(ReplayMove {
+                    player: player.clone(),
+                    shot: shot.clone(),
+                    outcome: None,
+                    proof_hash: None,
+                    fired_at: *timestamp,
+                    proven_at: None,
+                });
+            }
+        }
+        // Outcomes are proven by the defender about the shooter's shots
+        for player in [Player::Home, Player::Away] {
+            let shooter = player_index(&player.opponent());
+            for shot_outcome in game_transcript
+                .outcomes(&player)
+                .iter()
+                .flatten()
+                .skip(proven[shooter])
+            {
+                // Validation never lets outcomes outnumber the shots they prove
+                let i = match shot_moves[shooter].get(proven[shooter]) {
+                    Some(i) => *i,
+                    None => break,
+                };
+                moves[i].outcome = Some(ShotResult {
+                    hit: shot_outcome.hit,
+                    sunk_ship_length: shot_outcome.sunk_ship_length,
+                });
+                moves[i].proof_hash = Some(shot_outcome.proof_hash.clone());
+                moves[i].proven_at = Some(*timestamp);
+                proven[shooter] += 1;
+            }
+        }
+    }
+    moves
+}
+
+/// Both fog-of-war boards after the first `move_index + 1` moves
+pub fn game_snapshot(moves: &[ReplayMove], move_index: usize) -> GameSnapshot {
+    let mut boards = [
+        vec![vec![CellView::NotShot; BOARD_SIZE]; BOARD_SIZE],
+        vec![vec![CellView::NotShot; BOARD_SIZE]; BOARD_SIZE],
+    ];
+    let mut sunk_ships = [Vec::new(), Vec::new()];
+    for replay_move in moves.iter().take(move_index + 1) {
+        let defender = player_index(&replay_move.player.opponent());
+        boards[defender][replay_move.shot.x][replay_move.shot.y] = match &replay_move.outcome {
+            Some(outcome) if outcome.hit => CellView::Hit,
+            Some(_) => CellView::Miss,
+            None => CellView::Pending,
+        };
+        if let Some(sunk_ship_length) = replay_move
+            .outcome
+            .as_ref()
+            .and_then(|outcome| outcome.sunk_ship_length)
+        {
+            sunk_ships[defender].push(sunk_ship_length);
+        }
+    }
+    let [home_board, away_board] = boards;
+    let [home_player_sunk_ships, away_player_sunk_ships] = sunk_ships;
+    GameSnapshot {
+        move_index,
+        home_board,
+        away_board,
+        home_player_sunk_ships,
+        away_player_sunk_ships,
+    }
+}
+
+/// Every shot of a game in the order it was fired, with its outcome once proven
+#[hdk_extern]
+pub fn get_game_replay(game_invite_hash: ActionHash) -> ExternResult<Vec<ReplayMove>> {
+    let revisions = get_game_transcript_revisions(game_invite_hash)?
+        .into_iter()
+        .map(|record| match get_entry_for_record(&record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => {
+                Ok((record.action().timestamp(), game_transcript))
+            }
+            _ => Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameTranscript,
+            }
+            .into()),
+        })
+        .collect::<ExternResult<Vec<_>>>()?;
+    Ok(replay_moves(&revisions))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetGameSnapshotInput {
+    pub game_invite_hash: ActionHash,
+    // Into the moves returned by get_game_replay
+    pub move_index: usize,
+}
+
+/// Both fog-of-war boards as they stood right after the given move
+#[hdk_extern]
+pub fn get_game_snapshot(input: GetGameSnapshotInput) -> ExternResult<GameSnapshot> {
+    let moves = get_game_replay(input.game_invite_hash)?;
+    if input.move_index >= moves.len() {
+        return Err(BattleshipsError::MoveIndexOutOfRange {
+            move_index: input.move_index,
+            moves: moves.len(),
+        }
+        .into());
+    }
+    Ok(game_snapshot(&moves, input.move_index))
+}
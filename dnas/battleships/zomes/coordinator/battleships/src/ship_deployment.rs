@@ -1,13 +1,21 @@
 use battleships_integrity::*;
 use hdk::prelude::*;
 #[hdk_extern]
-pub fn create_ship_deployment(ship_deployment: ShipDeployment) -> ExternResult<Record> {
+pub fn check_ship_placement(ships: Vec<Ship>) -> ExternResult<Option<PlacementError>> {
+    Ok(validate_placement(&ships).err())
+}
+#[hdk_extern]
+pub fn create_ship_deployment(mut ship_deployment: ShipDeployment) -> ExternResult<Record> {
+    validate_placement(&ship_deployment.ships).map_err(BattleshipsError::from)?;
+    if ship_deployment.coin_secret.len() != COIN_SECRET_LENGTH {
+        ship_deployment.coin_secret = random_bytes(COIN_SECRET_LENGTH as u32)?.into_vec();
+    }
     let ship_deployment_hash = create_entry(&EntryTypes::ShipDeployment(ship_deployment.clone()))?;
-    let record = get(ship_deployment_hash.clone(), GetOptions::default())?.ok_or(wasm_error!(
-        WasmErrorInner::Guest(String::from(
-            "Could not find the newly created ShipDeployment"
-        ))
-    ))?;
+    let record = get(ship_deployment_hash.clone(), GetOptions::default())?.ok_or(
+        BattleshipsError::NotFound {
+            kind: RecordKind::ShipDeployment,
+        },
+    )?;
     create_link(
         ship_deployment.invite,
         ship_deployment_hash,
@@ -33,9 +41,7 @@ pub fn get_your_ship_deployment_for_invite(
         .filter(|record| record.action().author() == your_pub_key)
         .collect();
     if records.len() > 1 {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "You have multiple deployments".into()
-        )));
+        return Err(BattleshipsError::MultipleDeployments.into());
     }
 
     Ok(records.pop())
@@ -1,23 +1,54 @@
 use battleships_integrity::*;
 use hdk::prelude::*;
+
+use crate::coin_flip_reveal::reveal_coin_flip;
 #[hdk_extern]
 pub fn create_ship_deployment_proof(
-    ship_deployment_proof: ShipDeploymentProof,
+    mut ship_deployment_proof: ShipDeploymentProof,
 ) -> ExternResult<Record> {
+    // Commit to the coin flip secret held in our private deployment, unless the caller brings
+    // their own commitment
+    if ship_deployment_proof.coin_commitment.is_empty() {
+        let ship_deployment: ShipDeployment = get(
+            ship_deployment_proof.private_entry.clone(),
+            GetOptions::content(),
+        )?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::ShipDeployment,
+        })?;
+        ship_deployment_proof.coin_commitment = coin_commitment(
+            &agent_info()?.agent_latest_pubkey,
+            &ship_deployment_proof.invite,
+            &ship_deployment.coin_secret,
+        )?;
+    }
+    let game_invite_hash = ship_deployment_proof.invite.clone();
     let ship_deployment_proof_hash = create_entry(&EntryTypes::ShipDeploymentProof(
         ship_deployment_proof.clone(),
     ))?;
     let record = get(ship_deployment_proof_hash.clone(), GetOptions::default())?.ok_or(
-        wasm_error!(WasmErrorInner::Guest(String::from(
-            "Could not find the newly created ShipDeploymentProof"
-        ))),
+        BattleshipsError::NotFound {
+            kind: RecordKind::ShipDeploymentProof,
+        },
     )?;
     create_link(
         ship_deployment_proof.invite,
         ship_deployment_proof_hash,
         LinkTypes::DeploymentProofs,
-        (),
+        encode_link_tag(&DeploymentProofLinkTag {
+            deployer: record.action().author().clone(),
+            deployed_at: record.action().timestamp(),
+        })?,
     )?;
+    // If the opponent has already committed we can reveal straight away
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if get_deployers_for_invite(game_invite_hash.clone())?
+        .iter()
+        .any(|deployment| deployment.deployer != my_pub_key)
+    {
+        reveal_coin_flip(game_invite_hash)?;
+    }
     Ok(record)
 }
 #[hdk_extern]
@@ -41,27 +72,35 @@ pub fn get_ship_deployment_proofs_for_invite(
     Ok(records)
 }
 
+/// Deployers of a game's fleets, read from the link tags without fetching the proofs
+#[hdk_extern]
+pub fn get_deployers_for_invite(
+    game_invite_action_hash: ActionHash,
+) -> ExternResult<Vec<DeploymentProofLinkTag>> {
+    get_links(game_invite_action_hash, LinkTypes::DeploymentProofs, None)?
+        .iter()
+        .map(|link| decode_link_tag(&link.tag))
+        .collect()
+}
+
 #[hdk_extern]
 pub fn get_your_ship_deployment_proof_for_invite(
     game_invite_action_hash: ActionHash,
 ) -> ExternResult<Option<Record>> {
     let links = get_links(game_invite_action_hash, LinkTypes::DeploymentProofs, None)?;
-    let get_input: Vec<GetInput> = links
-        .into_iter()
-        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::content()))
-        .collect();
-    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
-    let your_pub_key = &agent_info()?.agent_latest_pubkey;
-    let mut records: Vec<Record> = records
-        .into_iter()
-        .flatten()
-        .filter(|record| record.action().author() == your_pub_key)
-        .collect();
-    if records.len() > 1 {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "You have multiple deployment proofs".into()
-        )));
+    let your_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut your_links = Vec::new();
+    for link in links {
+        let deployment_proof_link_tag: DeploymentProofLinkTag = decode_link_tag(&link.tag)?;
+        if deployment_proof_link_tag.deployer == your_pub_key {
+            your_links.push(link);
+        }
+    }
+    if your_links.len() > 1 {
+        return Err(BattleshipsError::MultipleDeployments.into());
+    }
+    match your_links.pop() {
+        Some(link) => get(ActionHash::from(link.target), GetOptions::content()),
+        None => Ok(None),
     }
-
-    Ok(records.pop())
 }
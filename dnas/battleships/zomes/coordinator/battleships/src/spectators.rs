@@ -0,0 +1,177 @@
+use battleships_integrity::{constants::BOARD_SIZE, *};
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_latest_game_transcript_for_game_invite, get_entry_for_action,
+    get_entry_for_record, Signal,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum CellView {
+    NotShot,
+    // Shot fired but outcome not yet proven
+    Pending,
+    Hit,
+    Miss,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicGameView {
+    pub home_player: AgentPubKey,
+    pub away_player: AgentPubKey,
+    pub rules: GameRules,
+    // None until the first shot is fired
+    pub turn: Option<GameTurn>,
+    pub move_number: usize,
+    // Shots fired at each player's fleet, indexed [x][y]
+    pub home_board: Vec<Vec<CellView>>,
+    pub away_board: Vec<Vec<CellView>>,
+    pub home_player_sunk_ships: Vec<usize>,
+    pub away_player_sunk_ships: Vec<usize>,
+}
+
+/// Fog-of-war board from the opponent's shots and the defender's proven outcomes
+pub fn fog_of_war_board(
+    opponent_shots: &[Vec<Shot>],
+    shot_outcomes: &[Vec<ShotOutcome>],
+) -> Vec<Vec<CellView>> {
+    let mut board = vec![vec![CellView::NotShot; BOARD_SIZE]; BOARD_SIZE];
+    let mut outcomes = shot_outcomes.iter().flatten();
+    for shot in opponent_shots.iter().flatten() {
+        // Shots are validated to be on the board
+        board[shot.x][shot.y] = match outcomes.next() {
+            Some(shot_outcome) if shot_outcome.hit => CellView::Hit,
+            Some(_) => CellView::Miss,
+            None => CellView::Pending,
+        };
+    }
+    board
+}
+
+pub fn public_game_view(
+    game_invite: GameInvite,
+    game_transcript: Option<GameTranscript>,
+) -> PublicGameView {
+    let empty_board = vec![vec![CellView::NotShot; BOARD_SIZE]; BOARD_SIZE];
+    let GameInvite {
+        home_player,
+        away_player,
+        rules,
+    } = game_invite;
+    match game_transcript {
+        Some(game_transcript) => PublicGameView {
+            home_player,
+            away_player,
+            rules,
+            turn: Some(get_game_turn(&game_transcript)),
+            move_number: game_transcript.home_player_shots.len()
+                + game_transcript.away_player_shots.len()
+                + game_transcript.home_player_hit_or_miss_proofs.len()
+                + game_transcript.away_player_hit_or_miss_proofs.len(),
+            home_board: fog_of_war_board(
+                &game_transcript.away_player_shots,
+                &game_transcript.home_player_hit_or_miss_proofs,
+            ),
+            away_board: fog_of_war_board(
+                &game_transcript.home_player_shots,
+                &game_transcript.away_player_hit_or_miss_proofs,
+            ),
+            home_player_sunk_ships: sunk_ship_lengths(
+                &game_transcript.home_player_hit_or_miss_proofs,
+            ),
+            away_player_sunk_ships: sunk_ship_lengths(
+                &game_transcript.away_player_hit_or_miss_proofs,
+            ),
+        },
+        None => PublicGameView {
+            home_player,
+            away_player,
+            rules,
+            turn: None,
+            move_number: 0,
+            home_board: empty_board.clone(),
+            away_board: empty_board,
+            home_player_sunk_ships: Vec::new(),
+            away_player_sunk_ships: Vec::new(),
+        },
+    }
+}
+
+#[hdk_extern]
+pub fn get_public_game_view(game_invite_hash: ActionHash) -> ExternResult<PublicGameView> {
+    let game_invite = match get_entry_for_action(&game_invite_hash)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
+        }
+    };
+    let game_transcript = match get_latest_game_transcript_for_game_invite(game_invite_hash)? {
+        Some(record) => match get_entry_for_record(&record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => Some(game_transcript),
+            _ => {
+                return Err(BattleshipsError::NotFound {
+                    kind: RecordKind::GameTranscript,
+                }
+                .into())
+            }
+        },
+        None => None,
+    };
+    Ok(public_game_view(game_invite, game_transcript))
+}
+
+#[hdk_extern]
+pub fn subscribe_to_game(game_invite_hash: ActionHash) -> ExternResult<()> {
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    create_link(game_invite_hash, my_pub_key, LinkTypes::Spectators, ())?;
+    Ok(())
+}
+
+#[hdk_extern]
+pub fn get_spectators(game_invite_hash: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    let links = get_links(game_invite_hash, LinkTypes::Spectators, None)?;
+    let mut spectators: Vec<AgentPubKey> = links
+        .into_iter()
+        .map(|link| AgentPubKey::from(EntryHash::from(link.target)))
+        .collect();
+    spectators.sort();
+    spectators.dedup();
+    Ok(spectators)
+}
+
+/// Lets the opponent and any spectators know a move has been made
+pub fn notify_game_move(
+    game_transcript: &GameTranscript,
+    action: SignedActionHashed,
+) -> ExternResult<()> {
+    let game_invite = match get_entry_for_action(&game_transcript.invite)? {
+        Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+        _ => {
+            return Err(BattleshipsError::NotFound {
+                kind: RecordKind::GameInvite,
+            }
+            .into())
+        }
+    };
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut recipients = get_spectators(game_transcript.invite.clone())?;
+    recipients.push(game_invite.home_player);
+    recipients.push(game_invite.away_player);
+    recipients.retain(|agent| agent != &my_pub_key);
+    recipients.sort();
+    recipients.dedup();
+    remote_signal(
+        Signal::GameMoved {
+            game_invite_hash: game_transcript.invite.clone(),
+            game_transcript_hash: action.hashed.hash.clone(),
+            turn: get_game_turn(game_transcript),
+            action,
+            game_transcript: game_transcript.clone(),
+        },
+        recipients,
+    )
+}
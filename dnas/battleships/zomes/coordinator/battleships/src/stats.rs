@@ -0,0 +1,185 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_transcript::get_latest_game_transcript_for_game_invite, get_entry_for_record,
+    invites::get_invites,
+};
+
+// Unfinished games with no moves for this long are considered abandoned
+const ABANDONED_AFTER_MICROS: i64 = 7 * 24 * 60 * 60 * 1_000_000;
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum GameOutcome {
+    Won,
+    Lost,
+    Abandoned,
+    InProgress,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameSummary {
+    pub game_invite_hash: ActionHash,
+    pub opponent: AgentPubKey,
+    pub rules: GameRules,
+    pub outcome: GameOutcome,
+    pub shots_fired: usize,
+    pub hits: usize,
+    pub last_move_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PlayerStats {
+    pub games_played: usize,
+    pub games_won: usize,
+    pub games_lost: usize,
+    pub games_abandoned: usize,
+    pub average_shots_to_win: Option<f64>,
+    pub hit_ratio: Option<f64>,
+}
+
+pub fn summarise_game(
+    agent: &AgentPubKey,
+    game_invite_hash: ActionHash,
+    game_invite: &GameInvite,
+    game_transcript: &GameTranscript,
+    last_move_at: Timestamp,
+    now: Timestamp,
+) -> GameSummary {
+    let is_home_player = &game_invite.home_player == agent;
+    let (opponent, own_shots, own_shot_outcomes) = if is_home_player {
+        (
+            &game_invite.away_player,
+            &game_transcript.home_player_shots,
+            &game_transcript.away_player_hit_or_miss_proofs,
+        )
+    } else {
+        (
+            &game_invite.home_player,
+            &game_transcript.away_player_shots,
+            &game_transcript.home_player_hit_or_miss_proofs,
+        )
+    };
+    let outcome = match (get_game_turn(game_transcript), is_home_player) {
+        (GameTurn::HomeWon, true) | (GameTurn::AwayWon, false) => GameOutcome::Won,
+        (GameTurn::HomeWon, false) | (GameTurn::AwayWon, true) => GameOutcome::Lost,
+        _ if now.as_micros() - last_move_at.as_micros() > ABANDONED_AFTER_MICROS => {
+            GameOutcome::Abandoned
+        }
+        _ => GameOutcome::InProgress,
+    };
+    GameSummary {
+        game_invite_hash,
+        opponent: opponent.clone(),
+        rules: game_invite.rules.clone(),
+        outcome,
+        // Only count shots whose outcome has been proven
+        shots_fired: own_shot_outcomes.iter().map(Vec::len).sum(),
+        hits: own_shot_outcomes
+            .iter()
+            .flatten()
+            .filter(|shot_outcome| shot_outcome.hit)
+            .count(),
+        last_move_at,
+    }
+}
+
+/// Summaries of every started game the agent has taken part in
+fn get_game_summaries(agent: &AgentPubKey) -> ExternResult<Vec<GameSummary>> {
+    let now = sys_time()?;
+    let mut summaries = Vec::new();
+    for invite_record in get_invites(agent.clone())? {
+        let game_invite = match get_entry_for_record(&invite_record)? {
+            Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+            _ => continue,
+        };
+        let game_invite_hash = invite_record.action_hashed().hash.clone();
+        let transcript_record =
+            match get_latest_game_transcript_for_game_invite(game_invite_hash.clone())? {
+                Some(record) => record,
+                // Game never started
+                None => continue,
+            };
+        let game_transcript = match get_entry_for_record(&transcript_record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => game_transcript,
+            _ => continue,
+        };
+        summaries.push(summarise_game(
+            agent,
+            game_invite_hash,
+            &game_invite,
+            &game_transcript,
+            transcript_record.action().timestamp(),
+            now,
+        ));
+    }
+    Ok(summaries)
+}
+
+pub fn compute_player_stats(summaries: &[GameSummary]) -> PlayerStats {
+    let count = |outcome: GameOutcome| {
+        summaries
+            .iter()
+            .filter(|summary| summary.outcome == outcome)
+            .count()
+    };
+    let wins: Vec<&GameSummary> = summaries
+        .iter()
+        .filter(|summary| summary.outcome == GameOutcome::Won)
+        .collect();
+    let shots_fired: usize = summaries.iter().map(|summary| summary.shots_fired).sum();
+    let hits: usize = summaries.iter().map(|summary| summary.hits).sum();
+    PlayerStats {
+        games_played: summaries.len(),
+        games_won: wins.len(),
+        games_lost: count(GameOutcome::Lost),
+        games_abandoned: count(GameOutcome::Abandoned),
+        average_shots_to_win: if wins.is_empty() {
+            None
+        } else {
+            Some(
+                wins.iter()
+                    .map(|summary| summary.shots_fired)
+                    .sum::<usize>() as f64
+                    / wins.len() as f64,
+            )
+        },
+        hit_ratio: if shots_fired == 0 {
+            None
+        } else {
+            Some(hits as f64 / shots_fired as f64)
+        },
+    }
+}
+
+#[hdk_extern]
+pub fn get_player_stats(agent: AgentPubKey) -> ExternResult<PlayerStats> {
+    Ok(compute_player_stats(&get_game_summaries(&agent)?))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Paging {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetGameHistoryInput {
+    pub agent: AgentPubKey,
+    pub paging: Option<Paging>,
+}
+
+/// Finished games, most recent first
+#[hdk_extern]
+pub fn get_game_history(input: GetGameHistoryInput) -> ExternResult<Vec<GameSummary>> {
+    let mut finished: Vec<GameSummary> = get_game_summaries(&input.agent)?
+        .into_iter()
+        .filter(|summary| matches!(summary.outcome, GameOutcome::Won | GameOutcome::Lost))
+        .collect();
+    finished.sort_by(|a, b| b.last_move_at.cmp(&a.last_move_at));
+    Ok(match input.paging {
+        Some(Paging { offset, limit }) => finished.into_iter().skip(offset).take(limit).collect(),
+        None => finished,
+    })
+}
@@ -0,0 +1,404 @@
+use battleships_integrity::*;
+use hdk::prelude::*;
+
+use crate::{
+    game_invite::create_game_invite, game_transcript::get_latest_game_transcript_for_game_invite,
+    get_entry_for_action, get_entry_for_record,
+};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateTournamentInput {
+    pub name: String,
+    pub format: TournamentFormat,
+    #[serde(default)]
+    pub rules: GameRules,
+}
+
+#[hdk_extern]
+pub fn create_tournament(input: CreateTournamentInput) -> ExternResult<Record> {
+    let tournament = Tournament {
+        name: input.name,
+        organiser: agent_info()?.agent_latest_pubkey,
+        format: input.format,
+        rules: input.rules,
+    };
+    let tournament_hash = create_entry(&EntryTypes::Tournament(tournament))?;
+    let record =
+        get(tournament_hash.clone(), GetOptions::default())?.ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::Tournament,
+        })?;
+    create_link(
+        tournaments_anchor_hash()?,
+        tournament_hash,
+        LinkTypes::Tournaments,
+        (),
+    )?;
+    Ok(record)
+}
+
+/// Every tournament, oldest first
+#[hdk_extern]
+pub fn get_tournaments(_: ()) -> ExternResult<Vec<Record>> {
+    let mut links = get_links(tournaments_anchor_hash()?, LinkTypes::Tournaments, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let get_input: Vec<GetInput> = links
+        .into_iter()
+        .map(|link| GetInput::new(ActionHash::from(link.target).into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(get_input))?;
+    Ok(records.into_iter().flatten().collect())
+}
+
+fn get_tournament(tournament_hash: &ActionHash) -> ExternResult<Tournament> {
+    match get_entry_for_action(tournament_hash)? {
+        Some(EntryTypes::Tournament(tournament)) => Ok(tournament),
+        _ => Err(BattleshipsError::NotFound {
+            kind: RecordKind::Tournament,
+        }
+        .into()),
+    }
+}
+
+/// The tournament's rounds in order. Rounds are only ever created by the organiser, but should
+/// they have created two for the same round, the first one linked counts.
+fn get_tournament_rounds(
+    tournament_hash: &ActionHash,
+) -> ExternResult<Vec<(ActionHash, TournamentRound)>> {
+    let mut links = get_links(tournament_hash.clone(), LinkTypes::TournamentRounds, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let mut rounds = Vec::new();
+    for link in links {
+        let round_hash = ActionHash::from(link.target);
+        if let Some(EntryTypes::TournamentRound(tournament_round)) =
+            get_entry_for_action(&round_hash)?
+        {
+            rounds.push((round_hash, tournament_round));
+        }
+    }
+    let mut chain: Vec<(ActionHash, TournamentRound)> = Vec::new();
+    loop {
+        let previous_round = chain.last().map(|(round_hash, _)| round_hash.clone());
+        match rounds
+            .iter()
+            .find(|(_, tournament_round)| tournament_round.previous_round == previous_round)
+        {
+            Some(next_round) => chain.push(next_round.clone()),
+            None => return Ok(chain),
+        }
+    }
+}
+
+/// Enters the caller into a tournament that hasn't started yet
+#[hdk_extern]
+pub fn register_for_tournament(tournament_hash: ActionHash) -> ExternResult<()> {
+    get_tournament(&tournament_hash)?;
+    if !get_tournament_rounds(&tournament_hash)?.is_empty() {
+        return Err(BattleshipsError::RegistrationClosed.into());
+    }
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    if get_tournament_entrants(tournament_hash.clone())?.contains(&my_pub_key) {
+        return Err(BattleshipsError::DuplicateEntrant.into());
+    }
+    create_link(
+        tournament_hash,
+        my_pub_key,
+        LinkTypes::TournamentEntrants,
+        (),
+    )?;
+    Ok(())
+}
+
+/// Everyone who has registered with the action of their TournamentEntrants link, in the order
+/// they did
+fn get_registrations(tournament_hash: ActionHash) -> ExternResult<Vec<(AgentPubKey, ActionHash)>> {
+    let mut links = get_links(tournament_hash, LinkTypes::TournamentEntrants, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    let mut registrations: Vec<(AgentPubKey, ActionHash)> = Vec::new();
+    for link in links {
+        let entrant = AgentPubKey::from(EntryHash::from(link.target));
+        if !registrations
+            .iter()
+            .any(|(registered, _)| registered == &entrant)
+        {
+            registrations.push((entrant, link.create_link_hash));
+        }
+    }
+    Ok(registrations)
+}
+
+/// Everyone who has registered, in the order they did. Once the tournament has started these
+/// are the entrants of its first round instead, so late registrations don't show up.
+#[hdk_extern]
+pub fn get_tournament_entrants(tournament_hash: ActionHash) -> ExternResult<Vec<AgentPubKey>> {
+    if let Some((_, first_round)) = get_tournament_rounds(&tournament_hash)?.into_iter().next() {
+        return Ok(first_round.entrants);
+    }
+    Ok(get_registrations(tournament_hash)?
+        .into_iter()
+        .map(|(entrant, _)| entrant)
+        .collect())
+}
+
+/// The game played for a pairing of a round, and its result once finished. Home players can
+/// only link one game from each round, so that's the one that counts.
+fn get_pairing_game(
+    round_hash: &ActionHash,
+    pairing: &Pairing<AgentPubKey>,
+) -> ExternResult<(Option<ActionHash>, Option<TournamentGameResult>)> {
+    let mut links = get_links(round_hash.clone(), LinkTypes::TournamentGames, None)?;
+    links.sort_by_key(|link| link.timestamp);
+    for link in links {
+        let game_invite_hash = ActionHash::from(link.target);
+        let game_invite = match get_entry_for_action(&game_invite_hash)? {
+            Some(EntryTypes::GameInvite(game_invite)) => game_invite,
+            _ => continue,
+        };
+        if game_invite.home_player != pairing.home
+            || Some(&game_invite.away_player) != pairing.away.as_ref()
+        {
+            continue;
+        }
+        let game_transcript_record =
+            match get_latest_game_transcript_for_game_invite(game_invite_hash.clone())? {
+                Some(record) => record,
+                None => return Ok((Some(game_invite_hash), None)),
+            };
+        let winner = match get_entry_for_record(&game_transcript_record)? {
+            Some(EntryTypes::GameTranscript(game_transcript)) => {
+                match get_game_turn(&game_transcript) {
+                    GameTurn::HomeWon => game_invite.home_player,
+                    GameTurn::AwayWon => game_invite.away_player,
+                    _ => return Ok((Some(game_invite_hash), None)),
+                }
+            }
+            _ => return Ok((Some(game_invite_hash), None)),
+        };
+        return Ok((
+            Some(game_invite_hash.clone()),
+            Some(TournamentGameResult {
+                game_invite: game_invite_hash,
+                game_link: link.create_link_hash,
+                game_transcript: game_transcript_record.action_address().clone(),
+                winner,
+            }),
+        ));
+    }
+    Ok((None, None))
+}
+
+/// Closes registration by creating the first round, or collects the results of the current
+/// round from its finished games and creates the next. Only the organiser can advance a
+/// tournament, and every game of the current round must be finished first.
+#[hdk_extern]
+pub fn advance_tournament(tournament_hash: ActionHash) -> ExternResult<Record> {
+    let tournament = get_tournament(&tournament_hash)?;
+    if tournament.organiser != agent_info()?.agent_latest_pubkey {
+        return Err(BattleshipsError::NotOrganiser.into());
+    }
+    let tournament_round = match get_tournament_rounds(&tournament_hash)?.pop() {
+        None => {
+            let (entrants, registrations): (Vec<AgentPubKey>, Vec<ActionHash>) =
+                get_registrations(tournament_hash.clone())?
+                    .into_iter()
+                    .unzip();
+            if entrants.len() < MIN_TOURNAMENT_ENTRANTS {
+                return Err(BattleshipsError::TooFewEntrants {
+                    min: MIN_TOURNAMENT_ENTRANTS,
+                    actual: entrants.len(),
+                }
+                .into());
+            }
+            TournamentRound {
+                tournament: tournament_hash.clone(),
+                round: 0,
+                previous_round: None,
+                pairings: tournament_pairings(&tournament.format, &entrants, 0, &[]),
+                entrants,
+                registrations,
+                previous_results: Vec::new(),
+            }
+        }
+        Some((round_hash, current_round)) => {
+            if current_round.pairings.is_empty() {
+                return Err(BattleshipsError::TournamentFinished.into());
+            }
+            let mut previous_results = Vec::new();
+            let mut winners = Vec::new();
+            for pairing in &current_round.pairings {
+                if pairing.is_bye() {
+                    winners.push(pairing.home.clone());
+                    continue;
+                }
+                match get_pairing_game(&round_hash, pairing)? {
+                    (_, Some(result)) => {
+                        winners.push(result.winner.clone());
+                        previous_results.push(result);
+                    }
+                    (_, None) => return Err(BattleshipsError::GameNotFinished.into()),
+                }
+            }
+            let round = current_round.round + 1;
+            let entrants = current_round.entrants;
+            TournamentRound {
+                tournament: tournament_hash.clone(),
+                round,
+                previous_round: Some(round_hash),
+                pairings: tournament_pairings(&tournament.format, &entrants, round, &winners),
+                entrants,
+                registrations: Vec::new(),
+                previous_results,
+            }
+        }
+    };
+    let round_hash = create_entry(&EntryTypes::TournamentRound(tournament_round))?;
+    let record =
+        get(round_hash.clone(), GetOptions::default())?.ok_or(BattleshipsError::NotFound {
+            kind: RecordKind::TournamentRound,
+        })?;
+    create_link(tournament_hash, round_hash, LinkTypes::TournamentRounds, ())?;
+    Ok(record)
+}
+
+/// Invites the caller's opponents in the current round, for every pairing where the caller is
+/// the home player and hasn't started a game yet. Away players just wait for the invite.
+#[hdk_extern]
+pub fn start_tournament_games(tournament_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let tournament = get_tournament(&tournament_hash)?;
+    let (round_hash, current_round) = match get_tournament_rounds(&tournament_hash)?.pop() {
+        Some(current_round) => current_round,
+        None => return Ok(Vec::new()),
+    };
+    let my_pub_key = agent_info()?.agent_latest_pubkey;
+    let mut game_invites = Vec::new();
+    for pairing in current_round.pairings {
+        let away_player = match &pairing.away {
+            Some(away_player) if pairing.home == my_pub_key => away_player.clone(),
+            _ => continue,
+        };
+        if let (Some(_), _) = get_pairing_game(&round_hash, &pairing)? {
+            continue;
+        }
+        let record = create_game_invite(GameInvite {
+            home_player: my_pub_key.clone(),
+            away_player,
+            rules: tournament.rules.clone(),
+        })?;
+        create_link(
+            round_hash.clone(),
+            record.action_address().clone(),
+            LinkTypes::TournamentGames,
+            (),
+        )?;
+        game_invites.push(record);
+    }
+    Ok(game_invites)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BracketGame {
+    pub pairing: Pairing<AgentPubKey>,
+    // None for byes and games not started yet
+    pub game_invite_hash: Option<ActionHash>,
+    pub winner: Option<AgentPubKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BracketRound {
+    pub round_hash: ActionHash,
+    pub round: usize,
+    pub games: Vec<BracketGame>,
+}
+
+/// Every round so far with its pairings and how their games went. The results of finished
+/// rounds are the ones the organiser recorded when advancing; the current round shows games
+/// as they finish.
+#[hdk_extern]
+pub fn get_tournament_bracket(tournament_hash: ActionHash) -> ExternResult<Vec<BracketRound>> {
+    let rounds = get_tournament_rounds(&tournament_hash)?;
+    let mut bracket = Vec::new();
+    for (i, (round_hash, tournament_round)) in rounds.iter().enumerate() {
+        let mut recorded_results = rounds
+            .get(i + 1)
+            .map(|(_, next_round)| next_round.previous_results.clone())
+            .unwrap_or_default()
+            .into_iter();
+        let mut games = Vec::new();
+        for pairing in &tournament_round.pairings {
+            let (game_invite_hash, winner) = if pairing.is_bye() {
+                (None, Some(pairing.home.clone()))
+            } else if let Some(result) = recorded_results.next() {
+                (Some(result.game_invite), Some(result.winner))
+            } else {
+                let (game_invite_hash, result) = get_pairing_game(round_hash, pairing)?;
+                (game_invite_hash, result.map(|result| result.winner))
+            };
+            games.push(BracketGame {
+                pairing: pairing.clone(),
+                game_invite_hash,
+                winner,
+            });
+        }
+        bracket.push(BracketRound {
+            round_hash: round_hash.clone(),
+            round: tournament_round.round,
+            games,
+        });
+    }
+    Ok(bracket)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TournamentStandings {
+    pub standings: Vec<Standing<AgentPubKey>>,
+    pub finished: bool,
+    // None until the tournament is finished, or if a round robin ends level at the top
+    pub champion: Option<AgentPubKey>,
+}
+
+/// Wins and losses of every entrant from the results recorded so far
+#[hdk_extern]
+pub fn get_tournament_standings(tournament_hash: ActionHash) -> ExternResult<TournamentStandings> {
+    let tournament = get_tournament(&tournament_hash)?;
+    let rounds = get_tournament_rounds(&tournament_hash)?;
+    let entrants = get_tournament_entrants(tournament_hash)?;
+    // Each round's results settle the games of the round before it
+    let mut results: Vec<(AgentPubKey, AgentPubKey)> = Vec::new();
+    for ((_, tournament_round), (_, next_round)) in rounds.iter().zip(rounds.iter().skip(1)) {
+        let games = tournament_round
+            .pairings
+            .iter()
+            .filter(|pairing| !pairing.is_bye());
+        for (pairing, result) in games.zip(&next_round.previous_results) {
+            let loser = if result.winner == pairing.home {
+                pairing.away.clone()
+            } else {
+                Some(pairing.home.clone())
+            };
+            if let Some(loser) = loser {
+                results.push((result.winner.clone(), loser));
+            }
+        }
+    }
+    let standings = tournament_standings(&entrants, &results);
+    let finished = rounds
+        .last()
+        .is_some_and(|(_, tournament_round)| tournament_round.pairings.is_empty());
+    let champion = if !finished {
+        None
+    } else {
+        match tournament.format {
+            // Whoever won the final
+            TournamentFormat::SingleElimination => results.last().map(|(winner, _)| winner.clone()),
+            TournamentFormat::RoundRobin => match standings.as_slice() {
+                [first, second, ..] if first.wins == second.wins => None,
+                [first, ..] => Some(first.player.clone()),
+                [] => None,
+            },
+        }
+    };
+    Ok(TournamentStandings {
+        standings,
+        finished,
+        champion,
+    })
+}
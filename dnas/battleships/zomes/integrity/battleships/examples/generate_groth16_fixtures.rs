@@ -0,0 +1,241 @@
+//! Writes the Groth16 fixtures for the unit tests in `src/groth16.rs`.
+//!
+//! ```text
+//! cargo run -p battleships_integrity --example generate_groth16_fixtures
+//! ```
+//!
+//! These are extras to the proofs of the real circuits that `circuits/build_all.sh` makes with
+//! each build. Every fixture verification key here is made up from a known trapdoor, which lets
+//! us forge valid proofs for any public inputs, so the tests can cover wrong keys and tampered
+//! proofs without circom or snarkjs. Each key takes the same public inputs as the circuit it
+//! stands in for, and the files use snarkjs's layout.
+
+use std::{fs, path::Path};
+
+use ark_bn254::{Bn254, Fq2, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{Field, UniformRand};
+use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+struct Proof {
+    a: G1Affine,
+    b: G2Affine,
+    c: G1Affine,
+}
+
+/// The secret scalars behind a verification key
+struct Trapdoor {
+    alpha: Fr,
+    beta: Fr,
+    gamma: Fr,
+    delta: Fr,
+    // One per public input, plus one for the constant term
+    ic: Vec<Fr>,
+}
+
+struct VerifyingKey {
+    alpha_g1: G1Affine,
+    beta_g2: G2Affine,
+    gamma_g2: G2Affine,
+    delta_g2: G2Affine,
+    ic: Vec<G1Affine>,
+}
+
+fn g1(scalar: Fr) -> G1Affine {
+    (G1Affine::generator() * scalar).into_affine()
+}
+
+fn g2(scalar: Fr) -> G2Affine {
+    (G2Affine::generator() * scalar).into_affine()
+}
+
+impl Trapdoor {
+    fn new(rng: &mut StdRng, n_public: usize) -> Self {
+        Trapdoor {
+            alpha: Fr::rand(rng),
+            beta: Fr::rand(rng),
+            gamma: Fr::rand(rng),
+            delta: Fr::rand(rng),
+            ic: (0..=n_public).map(|_| Fr::rand(rng)).collect(),
+        }
+    }
+
+    fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey {
+            alpha_g1: g1(self.alpha),
+            beta_g2: g2(self.beta),
+            gamma_g2: g2(self.gamma),
+            delta_g2: g2(self.delta),
+            ic: self.ic.iter().copied().map(g1).collect(),
+        }
+    }
+
+    /// Solves the verification equation for `c`, given random `a` and `b`
+    fn prove(&self, rng: &mut StdRng, public_inputs: &[Fr]) -> Proof {
+        let a = Fr::rand(rng);
+        let b = Fr::rand(rng);
+        let inputs: Fr = self.ic[0]
+            + public_inputs
+                .iter()
+                .zip(&self.ic[1..])
+                .map(|(input, ic)| *input * ic)
+                .sum::<Fr>();
+        let c =
+            (a * b - self.alpha * self.beta - self.gamma * inputs) * self.delta.inverse().unwrap();
+        Proof {
+            a: g1(a),
+            b: g2(b),
+            c: g1(c),
+        }
+    }
+}
+
+/// The same check as `Groth16::verify_proof`, from the public key alone
+fn verifies(vk: &VerifyingKey, proof: &Proof, public_inputs: &[Fr]) -> bool {
+    let inputs = public_inputs
+        .iter()
+        .zip(&vk.ic[1..])
+        .fold(vk.ic[0].into_group(), |sum, (input, ic)| sum + *ic * input);
+    Bn254::pairing(proof.a, proof.b)
+        == Bn254::pairing(vk.alpha_g1, vk.beta_g2)
+            + Bn254::pairing(inputs, vk.gamma_g2)
+            + Bn254::pairing(proof.c, vk.delta_g2)
+}
+
+fn strings(values: &[String]) -> String {
+    let quoted: Vec<String> = values
+        .iter()
+        .map(|value| format!("\"{}\"", value))
+        .collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+fn g1_json(point: &G1Affine) -> String {
+    strings(&[point.x.to_string(), point.y.to_string(), "1".into()])
+}
+
+fn fq2_json(value: &Fq2) -> String {
+    strings(&[value.c0.to_string(), value.c1.to_string()])
+}
+
+fn g2_json(point: &G2Affine) -> String {
+    format!(
+        "[{}, {}, [\"1\", \"0\"]]",
+        fq2_json(&point.x),
+        fq2_json(&point.y)
+    )
+}
+
+fn vk_json(vk: &VerifyingKey) -> String {
+    let ic: Vec<String> = vk
+        .ic
+        .iter()
+        .map(|ic| format!("    {}", g1_json(ic)))
+        .collect();
+    format!(
+        "{{\n  \"protocol\": \"groth16\",\n  \"curve\": \"bn128\",\n  \"nPublic\": {},\n  \
+         \"vk_alpha_1\": {},\n  \"vk_beta_2\": {},\n  \"vk_gamma_2\": {},\n  \
+         \"vk_delta_2\": {},\n  \"IC\": [\n{}\n  ]\n}}\n",
+        vk.ic.len() - 1,
+        g1_json(&vk.alpha_g1),
+        g2_json(&vk.beta_g2),
+        g2_json(&vk.gamma_g2),
+        g2_json(&vk.delta_g2),
+        ic.join(",\n"),
+    )
+}
+
+fn proof_json(proof: &Proof) -> String {
+    format!(
+        "{{\n  \"pi_a\": {},\n  \"pi_b\": {},\n  \"pi_c\": {},\n  \"protocol\": \"groth16\",\n  \
+         \"curve\": \"bn128\"\n}}\n",
+        g1_json(&proof.a),
+        g2_json(&proof.b),
+        g1_json(&proof.c),
+    )
+}
+
+fn public_json(public_inputs: &[Fr]) -> String {
+    let public_inputs: Vec<String> = public_inputs.iter().map(Fr::to_string).collect();
+    format!("{}\n", strings(&public_inputs))
+}
+
+/// Writes a valid proof of the public inputs, the same proof with `a` moved to another point on
+/// the curve, public inputs it doesn't prove and an unrelated key of the same shape
+fn write_circuit_fixtures(
+    dir: &Path,
+    rng: &mut StdRng,
+    public_inputs: &[Fr],
+    wrong_public_inputs: &[Fr],
+) {
+    let trapdoor = Trapdoor::new(rng, public_inputs.len());
+    let vk = trapdoor.verifying_key();
+    let wrong_vk = Trapdoor::new(rng, public_inputs.len()).verifying_key();
+    let proof = trapdoor.prove(rng, public_inputs);
+    let tampered_proof = Proof {
+        a: (proof.a + G1Affine::generator()).into_affine(),
+        ..proof
+    };
+
+    assert!(verifies(&vk, &proof, public_inputs));
+    assert!(!verifies(&vk, &tampered_proof, public_inputs));
+    assert!(!verifies(&vk, &proof, wrong_public_inputs));
+    assert!(!verifies(&wrong_vk, &proof, public_inputs));
+
+    fs::create_dir_all(dir).unwrap();
+    let files = [
+        ("verification_key.json", vk_json(&vk)),
+        ("wrong_verification_key.json", vk_json(&wrong_vk)),
+        ("proof.json", proof_json(&proof)),
+        ("tampered_proof.json", proof_json(&tampered_proof)),
+        ("public.json", public_json(public_inputs)),
+        ("wrong_public.json", public_json(wrong_public_inputs)),
+    ];
+    for (name, contents) in files {
+        fs::write(dir.join(name), contents).unwrap();
+    }
+}
+
+fn main() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/groth16");
+    let mut rng = StdRng::seed_from_u64(0);
+    let commitment = Fr::rand(&mut rng);
+
+    // create outputs the fleet commitment
+    write_circuit_fixtures(
+        &fixtures.join("create"),
+        &mut rng,
+        &[commitment],
+        &[commitment + Fr::from(1u64)],
+    );
+    // move outputs whether the shot hit, then takes the commitment and the shot
+    write_circuit_fixtures(
+        &fixtures.join("move"),
+        &mut rng,
+        &[Fr::from(1u64), commitment, Fr::from(3u64), Fr::from(7u64)],
+        &[Fr::from(0u64), commitment, Fr::from(3u64), Fr::from(7u64)],
+    );
+    // move_sunk also outputs the length of the ship the shot sank, and takes the earlier hits
+    let prior_hits = Fr::from((1u128 << 73) | (1u128 << 63));
+    write_circuit_fixtures(
+        &fixtures.join("move_sunk"),
+        &mut rng,
+        &[
+            Fr::from(1u64),
+            Fr::from(3u64),
+            commitment,
+            Fr::from(3u64),
+            Fr::from(7u64),
+            prior_hits,
+        ],
+        &[
+            Fr::from(1u64),
+            Fr::from(0u64),
+            commitment,
+            Fr::from(3u64),
+            Fr::from(7u64),
+            prior_hits,
+        ],
+    );
+    println!("Wrote {}", fixtures.display());
+}
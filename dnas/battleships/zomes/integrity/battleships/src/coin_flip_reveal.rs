@@ -0,0 +1,153 @@
+use hdi::prelude::*;
+
+use crate::{
+    decode_link_tag, helpers::must_get_valid_app_entry_and_author, BattleshipsError, EntryTypes,
+    LinkTypes, Player, RecordKind,
+};
+
+// Length in bytes of each player's coin flip secret
+pub const COIN_SECRET_LENGTH: usize = 32;
+
+/// Each player commits to a random secret in their deployment proof and reveals it once both
+/// fleets are deployed. Neither player can bias who opens without knowing the other's secret.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CoinFlipReveal {
+    pub invite: ActionHash,
+    pub deployment_proof: ActionHash,
+    pub secret: Vec<u8>,
+}
+
+/// Tag on the CoinFlipReveals links from a game invite to each player's reveal
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CoinFlipRevealLinkTag {
+    pub revealer: AgentPubKey,
+    pub secret: Vec<u8>,
+}
+
+/// What a coin commitment hashes. The deployer and the invite go in with the secret, so a
+/// commitment copied from the opponent can't be opened by the copier.
+pub fn coin_commitment_preimage(
+    deployer: &AgentPubKey,
+    invite: &ActionHash,
+    secret: &[u8],
+) -> Vec<u8> {
+    [deployer.get_raw_39(), invite.get_raw_39(), secret].concat()
+}
+
+pub fn coin_commitment(
+    deployer: &AgentPubKey,
+    invite: &ActionHash,
+    secret: &[u8],
+) -> ExternResult<Vec<u8>> {
+    hash_blake2b(
+        coin_commitment_preimage(deployer, invite, secret),
+        COIN_SECRET_LENGTH as u8,
+    )
+}
+
+/// The XOR of both secrets decides who opens
+pub fn first_player(home_player_secret: &[u8], away_player_secret: &[u8]) -> Player {
+    let parity = home_player_secret
+        .iter()
+        .zip(away_player_secret)
+        .fold(0u8, |acc, (home, away)| acc ^ home ^ away);
+    if parity & 1 == 1 {
+        Player::Home
+    } else {
+        Player::Away
+    }
+}
+
+pub fn validate_create_coin_flip_reveal(
+    action: EntryCreationAction,
+    coin_flip_reveal: CoinFlipReveal,
+) -> ExternResult<ValidateCallbackResult> {
+    if coin_flip_reveal.secret.len() != COIN_SECRET_LENGTH {
+        return BattleshipsError::InvalidCoinSecretLength {
+            expected: COIN_SECRET_LENGTH,
+            actual: coin_flip_reveal.secret.len(),
+        }
+        .invalid();
+    }
+    let deployment_proof =
+        match must_get_valid_app_entry_and_author(coin_flip_reveal.deployment_proof.clone())? {
+            (EntryTypes::ShipDeploymentProof(deployment_proof), author) => {
+                if &author != action.author() {
+                    return BattleshipsError::NotDeployer.invalid();
+                }
+                deployment_proof
+            }
+            _ => {
+                return BattleshipsError::WrongEntryType {
+                    field: "deployment_proof".into(),
+                    expected: RecordKind::ShipDeploymentProof,
+                }
+                .invalid()
+            }
+        };
+    if deployment_proof.invite != coin_flip_reveal.invite {
+        return BattleshipsError::WrongInvite {
+            kind: RecordKind::ShipDeploymentProof,
+        }
+        .invalid();
+    }
+    if coin_commitment(
+        action.author(),
+        &coin_flip_reveal.invite,
+        &coin_flip_reveal.secret,
+    )? != deployment_proof.coin_commitment
+    {
+        return BattleshipsError::CoinCommitmentMismatch.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_coin_flip_reveal(
+    _action: Update,
+    _coin_flip_reveal: CoinFlipReveal,
+    _original_action: EntryCreationAction,
+    _original_coin_flip_reveal: CoinFlipReveal,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::CoinFlipReveal,
+    }
+    .invalid()
+}
+pub fn validate_create_link_coin_flip_reveals(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::from(target_address);
+    let record = must_get_valid_record(action_hash)?;
+    let coin_flip_reveal: crate::CoinFlipReveal = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    if AnyLinkableHash::from(coin_flip_reveal.invite.clone()) != base_address {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::CoinFlipReveals,
+        }
+        .invalid();
+    }
+    let coin_flip_reveal_link_tag: CoinFlipRevealLinkTag = match decode_link_tag(&tag) {
+        Ok(coin_flip_reveal_link_tag) => coin_flip_reveal_link_tag,
+        Err(_) => {
+            return BattleshipsError::InvalidLinkTag {
+                link_type: LinkTypes::CoinFlipReveals,
+            }
+            .invalid()
+        }
+    };
+    if &coin_flip_reveal_link_tag.revealer != record.action().author()
+        || coin_flip_reveal_link_tag.secret != coin_flip_reveal.secret
+    {
+        return BattleshipsError::LinkTagMismatch {
+            link_type: LinkTypes::CoinFlipReveals,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
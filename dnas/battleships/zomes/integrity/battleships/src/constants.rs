@@ -1 +0,0 @@
-pub const BOARD_SIZE: usize = 10;
@@ -0,0 +1,661 @@
+use hdi::prelude::*;
+
+use crate::{GameTurn, LinkTypes, MoveError, PlacementError, Player, Shot};
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type")]
+pub enum RecordKind {
+    GameInvite,
+    ShipDeployment,
+    ShipDeploymentProof,
+    GameTranscript,
+    HitOrMissProof,
+    FleetReveal,
+    GameResult,
+    CoinFlipReveal,
+    GameMessage,
+    Profile,
+    Tournament,
+    TournamentRound,
+    Match,
+    AppEntry,
+    Link,
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type")]
+pub enum ProofKind {
+    ShipDeployment,
+    HitOrMiss,
+}
+
+/// Every failure surfaced by either zome. Errors are serialized as JSON tagged by `code`, both
+/// in `WasmErrorInner::Guest` and in `ValidateCallbackResult::Invalid`, so clients can match on
+/// the code rather than on the message text. Variants must not be renamed once released.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "code")]
+pub enum BattleshipsError {
+    // Lookups and entry shape
+    NotFound {
+        kind: RecordKind,
+    },
+    WrongEntryType {
+        field: String,
+        expected: RecordKind,
+    },
+    EntryNotDeserializable,
+    ExportNotSerializable {
+        reason: String,
+    },
+    UpdateNotAllowed {
+        kind: RecordKind,
+    },
+    DeleteNotAllowed {
+        kind: RecordKind,
+    },
+    // Structural problems with an op
+    EntryTypeChanged,
+    InvalidOriginalAction,
+    OriginalEntryMissing,
+    UnknownEntryType,
+    InvalidAgentValidationPkg,
+    MalformedProof,
+
+    // Invites and players
+    HomePlayerMustBeAuthor,
+    CannotInviteSelf,
+    NotAPlayer,
+    NotDeployer,
+    WrongInvite {
+        kind: RecordKind,
+    },
+
+    // Deployments
+    InvalidPlacement {
+        error: PlacementError,
+    },
+    InvalidProof {
+        kind: ProofKind,
+    },
+    WrongDeploymentCount {
+        expected: usize,
+        actual: usize,
+    },
+    MultipleDeployments,
+    DeploymentMismatch {
+        player: Player,
+    },
+    DeploymentNotInGame,
+    DeploymentCommitmentMismatch,
+
+    // Coin flip
+    MissingCoinCommitment,
+    InvalidCoinSecretLength {
+        expected: usize,
+        actual: usize,
+    },
+    CoinCommitmentMismatch,
+    CopiedCoinCommitment,
+    CoinFlipNotRevealed,
+    CoinFlipRevealMismatch {
+        player: Player,
+    },
+    FirstPlayerMismatch,
+
+    // Turn order
+    NotYourTurn {
+        turn: GameTurn,
+    },
+    GameFinished,
+    GameNotFinished,
+    CorruptTranscript,
+    InvalidTranscriptStart,
+    UnexpectedTranscriptChange {
+        player: Player,
+    },
+    MoveIndexOutOfRange {
+        move_index: usize,
+        moves: usize,
+    },
+
+    // Shots and proofs
+    WrongSalvoSize {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidShot {
+        shot: Shot,
+    },
+    MissingSalvo {
+        player: Player,
+    },
+    MissingProofs {
+        player: Player,
+    },
+    WrongProofCount {
+        expected: usize,
+        actual: usize,
+    },
+    OutcomeInconsistentWithProof {
+        shot: Shot,
+    },
+    ProofByWrongAuthor,
+    ProofForWrongDeployment,
+    ProofForWrongShot {
+        expected: Shot,
+        actual: Shot,
+    },
+    InvalidPriorHits,
+    PriorHitsMismatch,
+    InvalidSunkShipLength {
+        length: usize,
+    },
+    SunkProofRequired,
+
+    // Reveals and results
+    WrongFleetSize {
+        expected: usize,
+        actual: usize,
+    },
+    FleetCommitmentMismatch,
+    FleetInconsistentWithOutcomes {
+        shot: Shot,
+    },
+    FleetAlreadyRevealed,
+    GameResultMismatch,
+
+    // Chat
+    EmptyMessage,
+    MessageTooLong {
+        max: usize,
+        actual: usize,
+    },
+    MessageRateLimited {
+        limit: usize,
+        window_micros: i64,
+    },
+
+    // Profiles
+    InvalidNicknameLength {
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
+    InvalidNickname,
+    BioTooLong {
+        max: usize,
+        actual: usize,
+    },
+    ProfileAlreadyExists,
+    NotProfileOwner,
+    NicknameMismatch,
+
+    // Tournaments
+    InvalidTournamentNameLength {
+        max: usize,
+        actual: usize,
+    },
+    NotOrganiser,
+    TooFewEntrants {
+        min: usize,
+        actual: usize,
+    },
+    DuplicateEntrant,
+    RegistrationClosed,
+    WrongRegistrationCount {
+        expected: usize,
+        actual: usize,
+    },
+    NotRegistered,
+    WrongTournament,
+    WrongRoundNumber {
+        expected: usize,
+        actual: usize,
+    },
+    EntrantsChanged,
+    TournamentFinished,
+    WrongResultCount {
+        expected: usize,
+        actual: usize,
+    },
+    GameNotInRound,
+    PairingAlreadyStarted,
+    PairingsMismatch,
+
+    // Matches
+    InvalidMatchLength {
+        max: usize,
+        actual: usize,
+    },
+    GameNotInMatch,
+    WrongMatchRoles {
+        game_number: usize,
+    },
+    MatchGameOutOfRange {
+        game_number: usize,
+        best_of: usize,
+    },
+    MatchFinished,
+    MatchGameAlreadyStarted {
+        game_number: usize,
+    },
+    WrongPreviousMatchGame {
+        game_number: usize,
+    },
+    WrongMatchScore {
+        game_number: usize,
+    },
+
+    // Membrane
+    InvalidDnaProperties,
+    MockVerifierMismatch {
+        // Whether these zomes were built with the mock verifier
+        mock_verifier: bool,
+    },
+    MissingMembraneProof,
+    MalformedMembraneProof,
+    MembraneProofForWrongAgent,
+    MembraneProofForWrongNetwork,
+    InvalidMembraneProofSignature,
+    NotProgenitor,
+
+    // Links
+    LinkBaseMismatch {
+        link_type: LinkTypes,
+    },
+    LinkTargetMismatch {
+        link_type: LinkTypes,
+    },
+    InvalidLinkTag {
+        link_type: LinkTypes,
+    },
+    LinkTagMismatch {
+        link_type: LinkTypes,
+    },
+}
+
+impl BattleshipsError {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.to_string())
+    }
+
+    /// Recovers an error from the JSON carried by a guest error or invalid validation result
+    pub fn from_json(json: &str) -> Option<BattleshipsError> {
+        serde_json::from_str(json).ok()
+    }
+
+    pub fn invalid(self) -> ExternResult<ValidateCallbackResult> {
+        Ok(self.into())
+    }
+}
+
+impl From<BattleshipsError> for WasmError {
+    fn from(error: BattleshipsError) -> Self {
+        wasm_error!(WasmErrorInner::Guest(error.to_json()))
+    }
+}
+
+impl From<BattleshipsError> for ValidateCallbackResult {
+    fn from(error: BattleshipsError) -> Self {
+        ValidateCallbackResult::Invalid(error.to_json())
+    }
+}
+
+impl From<PlacementError> for BattleshipsError {
+    fn from(error: PlacementError) -> Self {
+        BattleshipsError::InvalidPlacement { error }
+    }
+}
+
+impl From<MoveError> for BattleshipsError {
+    fn from(error: MoveError) -> Self {
+        match error {
+            MoveError::NotYourTurn { turn } => BattleshipsError::NotYourTurn { turn },
+            MoveError::GameFinished => BattleshipsError::GameFinished,
+            MoveError::CorruptTranscript => BattleshipsError::CorruptTranscript,
+            MoveError::WrongSalvoSize { expected, actual } => {
+                BattleshipsError::WrongSalvoSize { expected, actual }
+            }
+            MoveError::InvalidShot { shot } => BattleshipsError::InvalidShot { shot },
+            MoveError::WrongOutcomeCount { expected, actual } => {
+                BattleshipsError::WrongProofCount { expected, actual }
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for BattleshipsError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            BattleshipsError::NotFound { kind } => write!(f, "{:?} not found", kind),
+            BattleshipsError::WrongEntryType { field, expected } => {
+                write!(f, "{} field is not a {:?}", field, expected)
+            }
+            BattleshipsError::EntryNotDeserializable => write!(f, "Could not deserialise entry"),
+            BattleshipsError::ExportNotSerializable { reason } => {
+                write!(f, "Could not serialise game export: {}", reason)
+            }
+            BattleshipsError::UpdateNotAllowed { kind } => {
+                write!(f, "{:?} cannot be updated", kind)
+            }
+            BattleshipsError::DeleteNotAllowed { kind } => {
+                write!(f, "{:?} cannot be deleted", kind)
+            }
+            BattleshipsError::EntryTypeChanged => {
+                write!(f, "Original and updated entry types must be the same")
+            }
+            BattleshipsError::InvalidOriginalAction => {
+                write!(f, "Original action must be a Create or Update action")
+            }
+            BattleshipsError::OriginalEntryMissing => {
+                write!(f, "Original record must contain an entry")
+            }
+            BattleshipsError::UnknownEntryType => {
+                write!(
+                    f,
+                    "Entry must be one of the defined entry types for this zome"
+                )
+            }
+            BattleshipsError::InvalidAgentValidationPkg => write!(
+                f,
+                "The previous action for a CreateAgent action must be an AgentValidationPkg"
+            ),
+            BattleshipsError::MalformedProof => write!(f, "Proof could not be parsed"),
+            BattleshipsError::HomePlayerMustBeAuthor => write!(f, "Home player must be author"),
+            BattleshipsError::CannotInviteSelf => write!(f, "Away player cannot be author"),
+            BattleshipsError::NotAPlayer => write!(f, "Author is not a player in this game"),
+            BattleshipsError::NotDeployer => {
+                write!(f, "Only the deploying player can reveal their deployment")
+            }
+            BattleshipsError::WrongInvite { kind } => {
+                write!(f, "{:?} is for a different invite", kind)
+            }
+            BattleshipsError::InvalidPlacement { error } => write!(f, "{}", error),
+            BattleshipsError::InvalidProof { kind } => write!(f, "Invalid {:?} proof", kind),
+            BattleshipsError::WrongDeploymentCount { expected, actual } => {
+                write!(f, "Expected {} deployments but found {}", expected, actual)
+            }
+            BattleshipsError::MultipleDeployments => write!(f, "You have multiple deployments"),
+            BattleshipsError::DeploymentMismatch { player } => {
+                write!(f, "{:?} player deployment doesn't match", player)
+            }
+            BattleshipsError::DeploymentNotInGame => {
+                write!(f, "Deployment proof is not part of this game")
+            }
+            BattleshipsError::DeploymentCommitmentMismatch => {
+                write!(f, "Deployment commitment doesn't match")
+            }
+            BattleshipsError::MissingCoinCommitment => write!(f, "Missing coin flip commitment"),
+            BattleshipsError::InvalidCoinSecretLength { expected, .. } => {
+                write!(f, "Coin flip secret must be {} bytes", expected)
+            }
+            BattleshipsError::CoinCommitmentMismatch => {
+                write!(f, "Revealed secret doesn't match coin flip commitment")
+            }
+            BattleshipsError::CopiedCoinCommitment => {
+                write!(f, "Both players made the same coin flip commitment")
+            }
+            BattleshipsError::CoinFlipNotRevealed => {
+                write!(f, "Coin flip has not been revealed by both players")
+            }
+            BattleshipsError::CoinFlipRevealMismatch { player } => {
+                write!(f, "{:?} player coin flip reveal doesn't match", player)
+            }
+            BattleshipsError::FirstPlayerMismatch => {
+                write!(f, "First player doesn't match the coin flip")
+            }
+            BattleshipsError::NotYourTurn { turn } => write!(f, "Not your turn: {:?}", turn),
+            BattleshipsError::GameFinished => write!(f, "Game is finished"),
+            BattleshipsError::GameNotFinished => write!(f, "Game is not finished"),
+            BattleshipsError::CorruptTranscript => write!(f, "Game transcript is corrupt"),
+            BattleshipsError::InvalidTranscriptStart => write!(
+                f,
+                "Transcript must start with a single salvo by the first player"
+            ),
+            BattleshipsError::UnexpectedTranscriptChange { player } => write!(
+                f,
+                "Only acceptable difference is the {:?} player's move",
+                player
+            ),
+            BattleshipsError::MoveIndexOutOfRange { move_index, moves } => write!(
+                f,
+                "No move {}, the game has {} moves so far",
+                move_index, moves
+            ),
+            BattleshipsError::WrongSalvoSize { expected, actual } => {
+                write!(f, "Expected {} shots but {} were fired", expected, actual)
+            }
+            BattleshipsError::InvalidShot { shot } => {
+                write!(f, "Invalid shot at ({}, {})", shot.x, shot.y)
+            }
+            BattleshipsError::MissingSalvo { player } => {
+                write!(f, "{:?} player shot not found", player)
+            }
+            BattleshipsError::MissingProofs { player } => {
+                write!(f, "{:?} player proof not found", player)
+            }
+            BattleshipsError::WrongProofCount { expected, actual } => write!(
+                f,
+                "Expected {} proofs but {} were provided",
+                expected, actual
+            ),
+            BattleshipsError::OutcomeInconsistentWithProof { shot } => write!(
+                f,
+                "Outcome of shot at ({}, {}) inconsistent with proof",
+                shot.x, shot.y
+            ),
+            BattleshipsError::ProofByWrongAuthor => {
+                write!(f, "Provided proof is by a different author")
+            }
+            BattleshipsError::ProofForWrongDeployment => {
+                write!(f, "Provided proof is for a different deployment")
+            }
+            BattleshipsError::ProofForWrongShot { .. } => {
+                write!(f, "Provided proof is for a different shot")
+            }
+            BattleshipsError::InvalidPriorHits => write!(f, "Invalid prior hits"),
+            BattleshipsError::PriorHitsMismatch => {
+                write!(f, "Prior hits inconsistent with transcript")
+            }
+            BattleshipsError::InvalidSunkShipLength { length } => {
+                write!(f, "Invalid sunk ship length {}", length)
+            }
+            BattleshipsError::SunkProofRequired => {
+                write!(
+                    f,
+                    "Hits, and every shot in Salvo games, require sunk ship proofs"
+                )
+            }
+            BattleshipsError::WrongFleetSize { expected, .. } => {
+                write!(f, "Fleet reveal must have {} ships", expected)
+            }
+            BattleshipsError::FleetCommitmentMismatch => {
+                write!(f, "Revealed fleet doesn't match deployment commitment")
+            }
+            BattleshipsError::FleetInconsistentWithOutcomes { shot } => write!(
+                f,
+                "Revealed fleet is inconsistent with the outcome at ({}, {})",
+                shot.x, shot.y
+            ),
+            BattleshipsError::FleetAlreadyRevealed => {
+                write!(f, "You have already revealed your fleet for this game")
+            }
+            BattleshipsError::GameResultMismatch => {
+                write!(f, "Game result doesn't match transcript")
+            }
+            BattleshipsError::EmptyMessage => write!(f, "Message is empty"),
+            BattleshipsError::MessageTooLong { max, actual } => write!(
+                f,
+                "Message is {} characters long but at most {} are allowed",
+                actual, max
+            ),
+            BattleshipsError::MessageRateLimited {
+                limit,
+                window_micros,
+            } => write!(
+                f,
+                "At most {} messages may be sent every {} seconds",
+                limit,
+                window_micros / 1_000_000
+            ),
+            BattleshipsError::InvalidNicknameLength { min, max, actual } => write!(
+                f,
+                "Nickname must be between {} and {} characters long but is {}",
+                min, max, actual
+            ),
+            BattleshipsError::InvalidNickname => write!(
+                f,
+                "Nickname must not have surrounding whitespace or control characters"
+            ),
+            BattleshipsError::BioTooLong { max, actual } => write!(
+                f,
+                "Bio is {} characters long but at most {} are allowed",
+                actual, max
+            ),
+            BattleshipsError::ProfileAlreadyExists => write!(f, "Agent already has a profile"),
+            BattleshipsError::NotProfileOwner => {
+                write!(f, "Only the profile's author can change it")
+            }
+            BattleshipsError::NicknameMismatch => {
+                write!(f, "Nickname link doesn't match the profile's nickname")
+            }
+            BattleshipsError::InvalidTournamentNameLength { max, actual } => write!(
+                f,
+                "Tournament name must be between 1 and {} characters long but is {}",
+                max, actual
+            ),
+            BattleshipsError::NotOrganiser => {
+                write!(f, "Only the tournament's organiser can do that")
+            }
+            BattleshipsError::TooFewEntrants { min, actual } => write!(
+                f,
+                "A tournament needs at least {} entrants but has {}",
+                min, actual
+            ),
+            BattleshipsError::DuplicateEntrant => {
+                write!(f, "An agent is entered in the tournament more than once")
+            }
+            BattleshipsError::RegistrationClosed => {
+                write!(f, "The tournament has already started")
+            }
+            BattleshipsError::WrongRegistrationCount { expected, actual } => write!(
+                f,
+                "Expected {} registrations but {} were given",
+                expected, actual
+            ),
+            BattleshipsError::NotRegistered => {
+                write!(f, "An entrant never registered for the tournament")
+            }
+            BattleshipsError::WrongTournament => {
+                write!(f, "Round belongs to a different tournament")
+            }
+            BattleshipsError::WrongRoundNumber { expected, actual } => {
+                write!(f, "Expected round {} but got round {}", expected, actual)
+            }
+            BattleshipsError::EntrantsChanged => {
+                write!(f, "Entrants can't change once the tournament has started")
+            }
+            BattleshipsError::TournamentFinished => write!(f, "The tournament is over"),
+            BattleshipsError::WrongResultCount { expected, actual } => write!(
+                f,
+                "The previous round had {} games but {} results were given",
+                expected, actual
+            ),
+            BattleshipsError::GameNotInRound => {
+                write!(f, "Game is not one of the round's pairings")
+            }
+            BattleshipsError::PairingAlreadyStarted => {
+                write!(f, "A game has already been started for this pairing")
+            }
+            BattleshipsError::PairingsMismatch => {
+                write!(
+                    f,
+                    "Pairings don't follow from the tournament's entrants and results"
+                )
+            }
+            BattleshipsError::InvalidMatchLength { max, actual } => write!(
+                f,
+                "A match must be an odd number of games up to {} but is {}",
+                max, actual
+            ),
+            BattleshipsError::GameNotInMatch => {
+                write!(f, "Game is not between the match's players under its rules")
+            }
+            BattleshipsError::WrongMatchRoles { game_number } => write!(
+                f,
+                "Game {} of the match has the players' home and away roles the wrong way round",
+                game_number
+            ),
+            BattleshipsError::MatchGameOutOfRange {
+                game_number,
+                best_of,
+            } => write!(
+                f,
+                "No game {} in a match of best of {}",
+                game_number, best_of
+            ),
+            BattleshipsError::MatchFinished => write!(f, "The match is over"),
+            BattleshipsError::MatchGameAlreadyStarted { game_number } => {
+                write!(
+                    f,
+                    "Game {} of the match has already been started",
+                    game_number
+                )
+            }
+            BattleshipsError::WrongPreviousMatchGame { game_number } => write!(
+                f,
+                "Game {} of the match must reference the finished game before it",
+                game_number
+            ),
+            BattleshipsError::WrongMatchScore { game_number } => write!(
+                f,
+                "Game {} of the match doesn't carry the score of the games before it",
+                game_number
+            ),
+            BattleshipsError::InvalidDnaProperties => write!(f, "DNA properties are invalid"),
+            BattleshipsError::MockVerifierMismatch { mock_verifier } => {
+                if *mock_verifier {
+                    write!(
+                        f,
+                        "Zomes built with the mock verifier need the mock_verifier DNA property"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "The mock_verifier DNA property needs zomes built with the mock verifier"
+                    )
+                }
+            }
+            BattleshipsError::MissingMembraneProof => {
+                write!(f, "A membrane proof is required to join this network")
+            }
+            BattleshipsError::MalformedMembraneProof => {
+                write!(f, "Membrane proof could not be parsed")
+            }
+            BattleshipsError::MembraneProofForWrongAgent => {
+                write!(f, "Membrane proof was issued to a different agent")
+            }
+            BattleshipsError::MembraneProofForWrongNetwork => {
+                write!(f, "Membrane proof was issued for a different network")
+            }
+            BattleshipsError::InvalidMembraneProofSignature => {
+                write!(f, "Membrane proof is not signed by the progenitor")
+            }
+            BattleshipsError::NotProgenitor => {
+                write!(f, "Only the progenitor can mint membrane proofs")
+            }
+            BattleshipsError::LinkBaseMismatch { link_type } => {
+                write!(f, "{:?} link has the wrong base", link_type)
+            }
+            BattleshipsError::LinkTargetMismatch { link_type } => {
+                write!(f, "{:?} link has the wrong target", link_type)
+            }
+            BattleshipsError::InvalidLinkTag { link_type } => {
+                write!(f, "{:?} link tag could not be decoded", link_type)
+            }
+            BattleshipsError::LinkTagMismatch { link_type } => {
+                write!(f, "{:?} link tag does not match its target", link_type)
+            }
+        }
+    }
+}
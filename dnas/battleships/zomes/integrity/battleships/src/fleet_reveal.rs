@@ -0,0 +1,204 @@
+use hdi::prelude::*;
+
+use crate::{
+    constants::SHIP_LENGTHS,
+    fleet_covers, get_game_turn,
+    helpers::{has_earlier_create_of, must_get_valid_app_entry_and_author},
+    poseidon::commitment_matches,
+    validate_placement, BattleshipsError, EntryTypes, GameTurn, LinkTypes, RecordKind, Ship,
+    UnitEntryTypes,
+};
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FleetReveal {
+    pub invite: ActionHash,
+    pub game_transcript: ActionHash,
+    pub deployment_proof: ActionHash,
+    pub ships: Vec<Ship>,
+    pub salt: String,
+}
+pub fn validate_create_fleet_reveal(
+    action: EntryCreationAction,
+    fleet_reveal: FleetReveal,
+) -> ExternResult<ValidateCallbackResult> {
+    if fleet_reveal.ships.len() != SHIP_LENGTHS.len() {
+        return BattleshipsError::WrongFleetSize {
+            expected: SHIP_LENGTHS.len(),
+            actual: fleet_reveal.ships.len(),
+        }
+        .invalid();
+    }
+    let game_transcript =
+        match must_get_valid_app_entry_and_author(fleet_reveal.game_transcript.clone())? {
+            (EntryTypes::GameTranscript(game_transcript), _) => game_transcript,
+            _ => {
+                return BattleshipsError::WrongEntryType {
+                    field: "game_transcript".into(),
+                    expected: RecordKind::GameTranscript,
+                }
+                .invalid()
+            }
+        };
+    if game_transcript.invite != fleet_reveal.invite {
+        return BattleshipsError::WrongInvite {
+            kind: RecordKind::GameTranscript,
+        }
+        .invalid();
+    }
+    if !matches!(
+        get_game_turn(&game_transcript),
+        GameTurn::HomeWon | GameTurn::AwayWon
+    ) {
+        return BattleshipsError::GameNotFinished.invalid();
+    }
+    // The revealer's proofs answer the opponent's shots
+    let (shot_outcomes, opponent_shots) =
+        if fleet_reveal.deployment_proof == game_transcript.home_player_deployment_proof {
+            (
+                &game_transcript.home_player_hit_or_miss_proofs,
+                &game_transcript.away_player_shots,
+            )
+        } else if fleet_reveal.deployment_proof == game_transcript.away_player_deployment_proof {
+            (
+                &game_transcript.away_player_hit_or_miss_proofs,
+                &game_transcript.home_player_shots,
+            )
+        } else {
+            return BattleshipsError::DeploymentNotInGame.invalid();
+        };
+    let deployment_proof =
+        match must_get_valid_app_entry_and_author(fleet_reveal.deployment_proof.clone())? {
+            (EntryTypes::ShipDeploymentProof(deployment_proof), author) => {
+                if &author != action.author() {
+                    return BattleshipsError::NotDeployer.invalid();
+                }
+                deployment_proof
+            }
+            _ => {
+                return BattleshipsError::WrongEntryType {
+                    field: "deployment_proof".into(),
+                    expected: RecordKind::ShipDeploymentProof,
+                }
+                .invalid()
+            }
+        };
+    if let Err(error) = check_revealed_fleet(
+        &fleet_reveal.ships,
+        &fleet_reveal.salt,
+        &deployment_proof.commitment,
+    ) {
+        return error.invalid();
+    }
+    if has_earlier_create_of(
+        &action,
+        UnitEntryTypes::FleetReveal,
+        |earlier: &FleetReveal| earlier.invite == fleet_reveal.invite,
+    )? {
+        return BattleshipsError::FleetAlreadyRevealed.invalid();
+    }
+    for (shot, shot_outcome) in opponent_shots
+        .iter()
+        .flatten()
+        .zip(shot_outcomes.iter().flatten())
+    {
+        if fleet_covers(&fleet_reveal.ships, shot) != shot_outcome.hit {
+            return BattleshipsError::FleetInconsistentWithOutcomes { shot: shot.clone() }
+                .invalid();
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+/// The commitment encodes each ship as x + 10y + 100d, which aliases off-board ships onto
+/// on-board ones, such as (10, 0) onto (0, 1), so the fleet has to be placed legally too
+pub fn check_revealed_fleet(
+    ships: &[Ship],
+    salt: &str,
+    commitment: &str,
+) -> Result<(), BattleshipsError> {
+    validate_placement(ships)?;
+    if !commitment_matches(ships, salt, commitment) {
+        return Err(BattleshipsError::FleetCommitmentMismatch);
+    }
+    Ok(())
+}
+pub fn validate_update_fleet_reveal(
+    _action: Update,
+    _fleet_reveal: FleetReveal,
+    _original_action: EntryCreationAction,
+    _original_fleet_reveal: FleetReveal,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::FleetReveal,
+    }
+    .invalid()
+}
+pub fn validate_create_link_fleet_reveals(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::from(target_address);
+    let record = must_get_valid_record(action_hash)?;
+    let fleet_reveal: crate::FleetReveal = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    if AnyLinkableHash::from(fleet_reveal.invite) != base_address {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::FleetReveals,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{poseidon::deployment_commitment, PlacementError};
+
+    const SALT: &str = "1234567";
+
+    fn stacked_fleet() -> Vec<Ship> {
+        (0..SHIP_LENGTHS.len())
+            .map(|i| Ship {
+                x: 0,
+                y: i * 2,
+                horizontal: true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn deployed_fleet_is_revealed() {
+        let fleet = stacked_fleet();
+        let commitment = deployment_commitment(&fleet, SALT).unwrap().to_string();
+        assert_eq!(check_revealed_fleet(&fleet, SALT, &commitment), Ok(()));
+        assert_eq!(
+            check_revealed_fleet(&fleet, "7654321", &commitment),
+            Err(BattleshipsError::FleetCommitmentMismatch)
+        );
+    }
+
+    #[test]
+    fn aliased_fleet_is_rejected() {
+        let fleet = stacked_fleet();
+        let commitment = deployment_commitment(&fleet, SALT).unwrap().to_string();
+        // The submarine at (0, 6) encodes the same as one at (10, 5)
+        let mut aliased = fleet.clone();
+        aliased[3] = Ship {
+            x: 10,
+            y: 5,
+            horizontal: true,
+        };
+        assert!(commitment_matches(&aliased, SALT, &commitment));
+        assert_eq!(
+            check_revealed_fleet(&aliased, SALT, &commitment),
+            Err(BattleshipsError::InvalidPlacement {
+                error: PlacementError::OutOfBounds { ship_index: 3 }
+            })
+        );
+    }
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+
+use hdi::prelude::*;
+
+use crate::{
+    check_move, check_transcript_start, coin_commitment_preimage, first_player, get_game_turn,
+    verify_hit_or_miss_proof, verify_ship_deployment_proof, BattleshipsError, CoinFlipReveal,
+    GameInvite, GameTranscript, GameTurn, HitOrMissProof, Player, ProofKind, RecordKind,
+    ShipDeploymentProof,
+};
+
+pub const GAME_EXPORT_VERSION: u32 = 2;
+
+/// An entry together with the signed action that created it, so that anyone holding the
+/// author's public key can check it wasn't tampered with
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedExport<T> {
+    pub action: SignedActionHashed,
+    pub entry: T,
+}
+
+impl<T> SignedExport<T> {
+    pub fn hash(&self) -> &ActionHash {
+        &self.action.hashed.hash
+    }
+
+    pub fn author(&self) -> &AgentPubKey {
+        self.action.action().author()
+    }
+}
+
+/// Everything needed to check a game without access to the network
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameExport {
+    pub version: u32,
+    pub game_invite: SignedExport<GameInvite>,
+    pub deployment_proofs: Vec<SignedExport<ShipDeploymentProof>>,
+    // The reveals the original transcript decides the first player with
+    pub coin_flip_reveals: Vec<SignedExport<CoinFlipReveal>>,
+    // The original transcript followed by every update, in the order they were made
+    pub transcript_revisions: Vec<SignedExport<GameTranscript>>,
+    pub hit_or_miss_proofs: Vec<SignedExport<HitOrMissProof>>,
+}
+
+/// The outcome of checking one step of an exported game
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MoveVerdict {
+    pub action_hash: ActionHash,
+    pub author: AgentPubKey,
+    pub step: AuditStep,
+    pub error: Option<BattleshipsError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum AuditStep {
+    Deployment { player: Option<Player> },
+    Move { revision: usize, turn: GameTurn },
+}
+
+/// Re-runs the validation rules over an exported game and gives a verdict per deployment and
+/// per transcript revision. Action signatures and hashes are not checked here since that needs
+/// host functions; the verifier CLI checks them natively. For the same reason coin commitments
+/// are recomputed with the given Blake2b function, which inside a zome is `hash_blake2b` with a
+/// `COIN_SECRET_LENGTH` output.
+pub fn audit_game_export<F>(game_export: &GameExport, blake2b: F) -> ExternResult<Vec<MoveVerdict>>
+where
+    F: Fn(&[u8]) -> ExternResult<Vec<u8>>,
+{
+    let game_invite_hash = game_export.game_invite.hash();
+    let game_invite = &game_export.game_invite.entry;
+    let mut verdicts = Vec::new();
+
+    let mut deployment_proofs: HashMap<&ActionHash, &SignedExport<ShipDeploymentProof>> =
+        HashMap::new();
+    for deployment_proof in &game_export.deployment_proofs {
+        deployment_proofs.insert(deployment_proof.hash(), deployment_proof);
+        let player = if deployment_proof.author() == &game_invite.home_player {
+            Some(Player::Home)
+        } else if deployment_proof.author() == &game_invite.away_player {
+            Some(Player::Away)
+        } else {
+            None
+        };
+        let error = if player.is_none() {
+            Some(BattleshipsError::NotAPlayer)
+        } else if &deployment_proof.entry.invite != game_invite_hash {
+            Some(BattleshipsError::WrongInvite {
+                kind: RecordKind::ShipDeploymentProof,
+            })
+        } else if !verify_ship_deployment_proof(&deployment_proof.entry)? {
+            Some(BattleshipsError::InvalidProof {
+                kind: ProofKind::ShipDeployment,
+            })
+        } else {
+            None
+        };
+        verdicts.push(MoveVerdict {
+            action_hash: deployment_proof.hash().clone(),
+            author: deployment_proof.author().clone(),
+            step: AuditStep::Deployment { player },
+            error,
+        });
+    }
+
+    let hit_or_miss_proofs: HashMap<&ActionHash, &SignedExport<HitOrMissProof>> = game_export
+        .hit_or_miss_proofs
+        .iter()
+        .map(|hit_or_miss_proof| (hit_or_miss_proof.hash(), hit_or_miss_proof))
+        .collect();
+    let coin_flip_reveals: HashMap<&ActionHash, &SignedExport<CoinFlipReveal>> = game_export
+        .coin_flip_reveals
+        .iter()
+        .map(|coin_flip_reveal| (coin_flip_reveal.hash(), coin_flip_reveal))
+        .collect();
+    let get_proof = |proof_hash: &ActionHash| {
+        Ok(hit_or_miss_proofs.get(proof_hash).map(|hit_or_miss_proof| {
+            (
+                hit_or_miss_proof.entry.clone(),
+                hit_or_miss_proof.author().clone(),
+            )
+        }))
+    };
+
+    let mut previous: Option<&SignedExport<GameTranscript>> = None;
+    for (revision, game_transcript) in game_export.transcript_revisions.iter().enumerate() {
+        let (turn, error) = match previous {
+            None => {
+                let turn = match game_transcript.entry.first_player {
+                    Player::Home => GameTurn::HomeShot,
+                    Player::Away => GameTurn::AwayShot,
+                };
+                let error = match check_transcript_deployments(
+                    &game_transcript.entry,
+                    game_invite,
+                    &deployment_proofs,
+                ) {
+                    Some(error) => Some(error),
+                    None => check_coin_flip(
+                        &game_transcript.entry,
+                        &deployment_proofs,
+                        &coin_flip_reveals,
+                        &blake2b,
+                    )?,
+                };
+                let error = match error {
+                    Some(error) => Some(error),
+                    None => check_transcript_start(
+                        game_invite,
+                        &game_transcript.entry,
+                        game_transcript.author(),
+                    ),
+                };
+                (turn, error)
+            }
+            Some(previous) => {
+                let turn = get_game_turn(&previous.entry);
+                let error = match game_transcript.action.action() {
+                    Action::Update(update)
+                        if &update.original_action_address == previous.hash() =>
+                    {
+                        check_move(
+                            game_invite,
+                            previous.entry.clone(),
+                            game_transcript.entry.clone(),
+                            game_transcript.author(),
+                            get_proof,
+                        )?
+                    }
+                    _ => Some(BattleshipsError::InvalidOriginalAction),
+                };
+                let error = match error {
+                    Some(error) => Some(error),
+                    None => check_new_proofs(
+                        &previous.entry,
+                        &game_transcript.entry,
+                        &turn,
+                        &hit_or_miss_proofs,
+                        &deployment_proofs,
+                    )?,
+                };
+                (turn, error)
+            }
+        };
+        let error = match error {
+            None if &game_transcript.entry.invite != game_invite_hash => {
+                Some(BattleshipsError::WrongInvite {
+                    kind: RecordKind::GameTranscript,
+                })
+            }
+            error => error,
+        };
+        verdicts.push(MoveVerdict {
+            action_hash: game_transcript.hash().clone(),
+            author: game_transcript.author().clone(),
+            step: AuditStep::Move { revision, turn },
+            error,
+        });
+        previous = Some(game_transcript);
+    }
+    Ok(verdicts)
+}
+
+fn check_transcript_deployments(
+    game_transcript: &GameTranscript,
+    game_invite: &GameInvite,
+    deployment_proofs: &HashMap<&ActionHash, &SignedExport<ShipDeploymentProof>>,
+) -> Option<BattleshipsError> {
+    for (player, deployment_proof_hash, deployer) in [
+        (
+            Player::Home,
+            &game_transcript.home_player_deployment_proof,
+            &game_invite.home_player,
+        ),
+        (
+            Player::Away,
+            &game_transcript.away_player_deployment_proof,
+            &game_invite.away_player,
+        ),
+    ] {
+        match deployment_proofs.get(deployment_proof_hash) {
+            Some(deployment_proof) if deployment_proof.author() == deployer => {}
+            Some(_) => return Some(BattleshipsError::DeploymentMismatch { player }),
+            None => {
+                return Some(BattleshipsError::NotFound {
+                    kind: RecordKind::ShipDeploymentProof,
+                })
+            }
+        }
+    }
+    None
+}
+
+// Repeats the coin flip checks of the reveals' and the original transcript's validators, once
+// check_transcript_deployments has made sure both deployment proofs are in the export
+fn check_coin_flip<F>(
+    game_transcript: &GameTranscript,
+    deployment_proofs: &HashMap<&ActionHash, &SignedExport<ShipDeploymentProof>>,
+    coin_flip_reveals: &HashMap<&ActionHash, &SignedExport<CoinFlipReveal>>,
+    blake2b: &F,
+) -> ExternResult<Option<BattleshipsError>>
+where
+    F: Fn(&[u8]) -> ExternResult<Vec<u8>>,
+{
+    if deployment_proofs[&game_transcript.home_player_deployment_proof]
+        .entry
+        .coin_commitment
+        == deployment_proofs[&game_transcript.away_player_deployment_proof]
+            .entry
+            .coin_commitment
+    {
+        return Ok(Some(BattleshipsError::CopiedCoinCommitment));
+    }
+    let mut secrets = Vec::new();
+    for (player, coin_flip_reveal_hash, deployment_proof_hash) in [
+        (
+            Player::Home,
+            &game_transcript.home_player_coin_flip_reveal,
+            &game_transcript.home_player_deployment_proof,
+        ),
+        (
+            Player::Away,
+            &game_transcript.away_player_coin_flip_reveal,
+            &game_transcript.away_player_deployment_proof,
+        ),
+    ] {
+        let coin_flip_reveal = match coin_flip_reveals.get(coin_flip_reveal_hash) {
+            Some(coin_flip_reveal) => coin_flip_reveal,
+            None => {
+                return Ok(Some(BattleshipsError::NotFound {
+                    kind: RecordKind::CoinFlipReveal,
+                }))
+            }
+        };
+        let deployment_proof = &deployment_proofs[deployment_proof_hash];
+        if &coin_flip_reveal.entry.deployment_proof != deployment_proof_hash
+            || coin_flip_reveal.entry.invite != game_transcript.invite
+        {
+            return Ok(Some(BattleshipsError::CoinFlipRevealMismatch { player }));
+        }
+        if coin_flip_reveal.author() != deployment_proof.author() {
+            return Ok(Some(BattleshipsError::NotDeployer));
+        }
+        let preimage = coin_commitment_preimage(
+            coin_flip_reveal.author(),
+            &coin_flip_reveal.entry.invite,
+            &coin_flip_reveal.entry.secret,
+        );
+        if blake2b(&preimage)? != deployment_proof.entry.coin_commitment {
+            return Ok(Some(BattleshipsError::CoinCommitmentMismatch));
+        }
+        secrets.push(&coin_flip_reveal.entry.secret);
+    }
+    if first_player(secrets[0], secrets[1]) != game_transcript.first_player {
+        return Ok(Some(BattleshipsError::FirstPlayerMismatch));
+    }
+    Ok(None)
+}
+
+// Validators check these when each proof is created rather than when it's added to the
+// transcript, so they're repeated here for the proofs a revision adds
+fn check_new_proofs(
+    previous: &GameTranscript,
+    game_transcript: &GameTranscript,
+    turn: &GameTurn,
+    hit_or_miss_proofs: &HashMap<&ActionHash, &SignedExport<HitOrMissProof>>,
+    deployment_proofs: &HashMap<&ActionHash, &SignedExport<ShipDeploymentProof>>,
+) -> ExternResult<Option<BattleshipsError>> {
+    let shot_outcomes = match turn {
+        GameTurn::HomeProof => game_transcript.home_player_hit_or_miss_proofs.last(),
+        GameTurn::AwayProof => game_transcript.away_player_hit_or_miss_proofs.last(),
+        _ => return Ok(None),
+    };
+    let deployment_proof_hash = match turn {
+        GameTurn::HomeProof => &previous.home_player_deployment_proof,
+        _ => &previous.away_player_deployment_proof,
+    };
+    let deployment_proof = match deployment_proofs.get(deployment_proof_hash) {
+        Some(deployment_proof) => deployment_proof,
+        None => {
+            return Ok(Some(BattleshipsError::NotFound {
+                kind: RecordKind::ShipDeploymentProof,
+            }))
+        }
+    };
+    for shot_outcome in shot_outcomes.into_iter().flatten() {
+        // check_move has already made sure every proof is in the export
+        let hit_or_miss_proof = &hit_or_miss_proofs[&shot_outcome.proof_hash].entry;
+        if hit_or_miss_proof.deployment_commitment != deployment_proof.entry.commitment {
+            return Ok(Some(BattleshipsError::DeploymentCommitmentMismatch));
+        }
+        if !verify_hit_or_miss_proof(hit_or_miss_proof)? {
+            return Ok(Some(BattleshipsError::InvalidProof {
+                kind: ProofKind::HitOrMiss,
+            }));
+        }
+    }
+    Ok(None)
+}
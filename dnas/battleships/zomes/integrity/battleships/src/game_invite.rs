@@ -1,23 +1,26 @@
 use hdi::prelude::*;
+
+use crate::{
+    decode_link_tag, BattleshipsError, DeploymentProofLinkTag, GameRules, InviteLinkTag, LinkTypes,
+    RecordKind,
+};
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct GameInvite {
     pub home_player: AgentPubKey,
     pub away_player: AgentPubKey,
+    #[serde(default)]
+    pub rules: GameRules,
 }
 pub fn validate_create_game_invite(
     action: EntryCreationAction,
     game_invite: GameInvite,
 ) -> ExternResult<ValidateCallbackResult> {
     if &game_invite.home_player != action.author() {
-        return Ok(ValidateCallbackResult::Invalid(String::from(
-            "Home player must be author",
-        )));
+        return BattleshipsError::HomePlayerMustBeAuthor.invalid();
     }
     if &game_invite.away_player == action.author() {
-        return Ok(ValidateCallbackResult::Invalid(String::from(
-            "Away player cannot be author",
-        )));
+        return BattleshipsError::CannotInviteSelf.invalid();
     }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -27,34 +30,63 @@ pub fn validate_update_game_invite(
     _original_action: EntryCreationAction,
     _original_game_invite: GameInvite,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Game Invites cannot be updated",
-    )))
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::GameInvite,
+    }
+    .invalid()
 }
 pub fn validate_delete_game_invite(
     _action: Delete,
     _original_action: EntryCreationAction,
     _original_game_invite: GameInvite,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Game Invites cannot be deleted",
-    )))
+    BattleshipsError::DeleteNotAllowed {
+        kind: RecordKind::GameInvite,
+    }
+    .invalid()
 }
 pub fn validate_create_link_invites(
     _action: CreateLink,
-    _base_address: AnyLinkableHash,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     let action_hash = ActionHash::from(target_address);
     let record = must_get_valid_record(action_hash)?;
-    let _game_invite: crate::GameInvite = record
+    let game_invite: crate::GameInvite = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Linked action must reference an entry"
-        ))))?;
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    let invite_link_tag: InviteLinkTag = match decode_link_tag(&tag) {
+        Ok(invite_link_tag) => invite_link_tag,
+        Err(_) => {
+            return BattleshipsError::InvalidLinkTag {
+                link_type: LinkTypes::Invites,
+            }
+            .invalid()
+        }
+    };
+    let (participant, opponent) = if invite_link_tag.is_home_player {
+        (&game_invite.home_player, &game_invite.away_player)
+    } else {
+        (&game_invite.away_player, &game_invite.home_player)
+    };
+    if base_address != AnyLinkableHash::from(participant.clone()) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::Invites,
+        }
+        .invalid();
+    }
+    if &invite_link_tag.opponent != opponent
+        || invite_link_tag.rules != game_invite.rules
+        || invite_link_tag.created_at != record.action().timestamp()
+    {
+        return BattleshipsError::LinkTagMismatch {
+            link_type: LinkTypes::Invites,
+        }
+        .invalid();
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -70,9 +102,7 @@ pub fn validate_create_link_game_transcript(
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Linked action must reference an entry"
-        ))))?;
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -89,18 +119,61 @@ pub fn validate_create_link_deployment(
 }
 pub fn validate_create_link_deployment_proof(
     _action: CreateLink,
-    _base_address: AnyLinkableHash,
+    base_address: AnyLinkableHash,
     target_address: AnyLinkableHash,
-    _tag: LinkTag,
+    tag: LinkTag,
 ) -> ExternResult<ValidateCallbackResult> {
     let action_hash = ActionHash::from(target_address);
     let record = must_get_valid_record(action_hash)?;
-    let _ship_deployment_proof: crate::ShipDeploymentProof = record
+    let ship_deployment_proof: crate::ShipDeploymentProof = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Linked action must reference an entry"
-        ))))?;
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    if base_address != AnyLinkableHash::from(ship_deployment_proof.invite) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::DeploymentProofs,
+        }
+        .invalid();
+    }
+    let deployment_proof_link_tag: DeploymentProofLinkTag = match decode_link_tag(&tag) {
+        Ok(deployment_proof_link_tag) => deployment_proof_link_tag,
+        Err(_) => {
+            return BattleshipsError::InvalidLinkTag {
+                link_type: LinkTypes::DeploymentProofs,
+            }
+            .invalid()
+        }
+    };
+    if &deployment_proof_link_tag.deployer != record.action().author()
+        || deployment_proof_link_tag.deployed_at != record.action().timestamp()
+    {
+        return BattleshipsError::LinkTagMismatch {
+            link_type: LinkTypes::DeploymentProofs,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_spectators(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::from(base_address);
+    let record = must_get_valid_record(action_hash)?;
+    let _game_invite: crate::GameInvite = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    // Agents can only subscribe themselves
+    if target_address != AnyLinkableHash::from(action.author) {
+        return BattleshipsError::LinkTargetMismatch {
+            link_type: LinkTypes::Spectators,
+        }
+        .invalid();
+    }
     Ok(ValidateCallbackResult::Valid)
 }
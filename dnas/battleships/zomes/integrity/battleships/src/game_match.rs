@@ -0,0 +1,230 @@
+use hdi::prelude::*;
+
+use crate::{
+    decode_link_tag, get_game_turn,
+    helpers::{
+        has_earlier_create_link, must_get_create_link_of, must_get_valid_app_entry_and_author,
+    },
+    BattleshipsError, EntryTypes, GameRules, GameTurn, LinkTypes, MatchGameLinkTag, RecordKind,
+};
+
+pub const MAX_MATCH_GAMES: usize = 9;
+
+/// A series of games between the same two players, who take turns at being home
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Match {
+    // Home in the first game and every other one after it
+    pub home_player: AgentPubKey,
+    pub away_player: AgentPubKey,
+    // Whoever wins more than half of this many games wins the match
+    pub best_of: usize,
+    #[serde(default)]
+    pub rules: GameRules,
+}
+
+impl Match {
+    pub fn wins_needed(&self) -> usize {
+        self.best_of / 2 + 1
+    }
+
+    /// Home and away players of the given game, counting from zero
+    pub fn game_players(&self, game_number: usize) -> (AgentPubKey, AgentPubKey) {
+        if game_number % 2 == 1 {
+            (self.away_player.clone(), self.home_player.clone())
+        } else {
+            (self.home_player.clone(), self.away_player.clone())
+        }
+    }
+}
+
+pub fn validate_create_match(
+    action: EntryCreationAction,
+    game_match: Match,
+) -> ExternResult<ValidateCallbackResult> {
+    if &game_match.home_player != action.author() {
+        return BattleshipsError::HomePlayerMustBeAuthor.invalid();
+    }
+    if &game_match.away_player == action.author() {
+        return BattleshipsError::CannotInviteSelf.invalid();
+    }
+    if game_match.best_of % 2 != 1 || game_match.best_of > MAX_MATCH_GAMES {
+        return BattleshipsError::InvalidMatchLength {
+            max: MAX_MATCH_GAMES,
+            actual: game_match.best_of,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_match(
+    _action: Update,
+    _game_match: Match,
+    _original_action: EntryCreationAction,
+    _original_game_match: Match,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::Match,
+    }
+    .invalid()
+}
+
+fn must_get_match(match_hash: ActionHash) -> ExternResult<Option<Match>> {
+    match must_get_valid_app_entry_and_author(match_hash)? {
+        (EntryTypes::Match(game_match), _) => Ok(Some(game_match)),
+        _ => Ok(None),
+    }
+}
+
+/// Checks that a game's tag references the game before it in the same match, finished, and
+/// carries the score after it
+fn validate_previous_match_game(
+    game_match: &Match,
+    base_address: &AnyLinkableHash,
+    match_game_link_tag: &MatchGameLinkTag,
+) -> ExternResult<Option<BattleshipsError>> {
+    let game_number = match_game_link_tag.game_number;
+    let wrong_previous_game = BattleshipsError::WrongPreviousMatchGame { game_number };
+    let score = (
+        match_game_link_tag.home_player_wins,
+        match_game_link_tag.away_player_wins,
+    );
+    let previous_game = match (game_number, &match_game_link_tag.previous_game) {
+        (0, None) if score == (0, 0) => return Ok(None),
+        (0, None) => return Ok(Some(BattleshipsError::WrongMatchScore { game_number })),
+        (0, Some(_)) | (_, None) => return Ok(Some(wrong_previous_game)),
+        (_, Some(previous_game)) => previous_game,
+    };
+    let previous_link =
+        match must_get_create_link_of(previous_game.link.clone(), LinkTypes::MatchGames)? {
+            Some(create_link) if &create_link.base_address == base_address => create_link,
+            _ => return Ok(Some(wrong_previous_game)),
+        };
+    let previous_tag = match decode_link_tag::<MatchGameLinkTag>(&previous_link.tag) {
+        Ok(previous_tag) if previous_tag.game_number + 1 == game_number => previous_tag,
+        _ => return Ok(Some(wrong_previous_game)),
+    };
+    let game_transcript =
+        match must_get_valid_app_entry_and_author(previous_game.game_transcript.clone())? {
+            (EntryTypes::GameTranscript(game_transcript), _) => game_transcript,
+            _ => {
+                return Ok(Some(BattleshipsError::WrongEntryType {
+                    field: "game_transcript".into(),
+                    expected: RecordKind::GameTranscript,
+                }))
+            }
+        };
+    if AnyLinkableHash::from(game_transcript.invite.clone()) != previous_link.target_address {
+        return Ok(Some(wrong_previous_game));
+    }
+    let (home_player, away_player) = game_match.game_players(previous_tag.game_number);
+    let winner = match get_game_turn(&game_transcript) {
+        GameTurn::HomeWon => home_player,
+        GameTurn::AwayWon => away_player,
+        _ => return Ok(Some(BattleshipsError::GameNotFinished)),
+    };
+    let mut expected_score = (previous_tag.home_player_wins, previous_tag.away_player_wins);
+    if winner == game_match.home_player {
+        expected_score.0 += 1;
+    } else {
+        expected_score.1 += 1;
+    }
+    if score != expected_score {
+        return Ok(Some(BattleshipsError::WrongMatchScore { game_number }));
+    }
+    Ok(None)
+}
+
+pub fn validate_create_link_match_games(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let game_match = match must_get_match(ActionHash::from(base_address.clone()))? {
+        Some(game_match) => game_match,
+        None => {
+            return BattleshipsError::LinkBaseMismatch {
+                link_type: LinkTypes::MatchGames,
+            }
+            .invalid()
+        }
+    };
+    let game_invite = match must_get_valid_app_entry_and_author(ActionHash::from(target_address))? {
+        (EntryTypes::GameInvite(game_invite), _) => game_invite,
+        _ => return BattleshipsError::EntryNotDeserializable.invalid(),
+    };
+    let match_game_link_tag: MatchGameLinkTag = match decode_link_tag(&tag) {
+        Ok(match_game_link_tag) => match_game_link_tag,
+        Err(_) => {
+            return BattleshipsError::InvalidLinkTag {
+                link_type: LinkTypes::MatchGames,
+            }
+            .invalid()
+        }
+    };
+    let game_number = match_game_link_tag.game_number;
+    if game_number >= game_match.best_of {
+        return BattleshipsError::MatchGameOutOfRange {
+            game_number,
+            best_of: game_match.best_of,
+        }
+        .invalid();
+    }
+    let players = (
+        game_invite.home_player.clone(),
+        game_invite.away_player.clone(),
+    );
+    let (home_player, away_player) = game_match.game_players(game_number);
+    if players == (away_player.clone(), home_player.clone()) {
+        return BattleshipsError::WrongMatchRoles { game_number }.invalid();
+    }
+    if players != (home_player, away_player) || game_invite.rules != game_match.rules {
+        return BattleshipsError::GameNotInMatch.invalid();
+    }
+    if game_invite.home_player != action.author {
+        return BattleshipsError::HomePlayerMustBeAuthor.invalid();
+    }
+    if let Some(error) =
+        validate_previous_match_game(&game_match, &base_address, &match_game_link_tag)?
+    {
+        return error.invalid();
+    }
+    // The score is checked against every game before this one, so it decides whether the match
+    // is still on
+    let wins_needed = game_match.wins_needed();
+    if match_game_link_tag.home_player_wins >= wins_needed
+        || match_game_link_tag.away_player_wins >= wins_needed
+    {
+        return BattleshipsError::MatchFinished.invalid();
+    }
+    // Only the game's home player can link it, so any earlier link for it is in their chain
+    if has_earlier_create_link(&action, LinkTypes::MatchGames, |earlier| {
+        earlier.base_address == base_address
+            && decode_link_tag::<MatchGameLinkTag>(&earlier.tag)
+                .is_ok_and(|earlier_tag| earlier_tag.game_number == game_number)
+    })? {
+        return BattleshipsError::MatchGameAlreadyStarted { game_number }.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_player_matches(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let game_match = match must_get_match(ActionHash::from(target_address))? {
+        Some(game_match) => game_match,
+        None => return BattleshipsError::EntryNotDeserializable.invalid(),
+    };
+    if base_address != AnyLinkableHash::from(game_match.home_player)
+        && base_address != AnyLinkableHash::from(game_match.away_player)
+    {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::PlayerMatches,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
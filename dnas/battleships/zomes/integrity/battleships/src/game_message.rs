@@ -0,0 +1,148 @@
+use hdi::prelude::*;
+
+use crate::{
+    helpers::{is_create_of, must_get_valid_app_entry_and_author},
+    BattleshipsError, EntryTypes, LinkTypes, RecordKind, UnitEntryTypes,
+};
+
+pub const MAX_MESSAGE_LENGTH: usize = 280;
+// At most MESSAGE_RATE_LIMIT messages may be posted within MESSAGE_RATE_WINDOW_MICROS
+pub const MESSAGE_RATE_LIMIT: usize = 5;
+pub const MESSAGE_RATE_WINDOW_MICROS: i64 = 30 * 1_000_000;
+// How many of the author's actions to fetch at a time while looking for recent messages
+const MESSAGE_RATE_BATCH: u32 = 50;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(tag = "type")]
+pub enum Emote {
+    Salute,
+    Laugh,
+    Wow,
+    Sad,
+    Angry,
+    GoodGame,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
+#[serde(tag = "type")]
+pub enum GameMessageContent {
+    Text { text: String },
+    Emote { emote: Emote },
+}
+
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct GameMessage {
+    pub invite: ActionHash,
+    pub content: GameMessageContent,
+}
+
+/// Counts the author's messages, including this one, posted within the rate window. Walks
+/// back through the author's chain until it reaches an action from before the window, however
+/// many other actions came in between.
+fn recent_message_count(action: &EntryCreationAction) -> ExternResult<usize> {
+    let window_start = action.timestamp().as_micros() - MESSAGE_RATE_WINDOW_MICROS;
+    let mut count = 1;
+    let mut next_batch = Some(action.prev_action().clone());
+    while let Some(chain_top) = next_batch.take() {
+        let activity = must_get_agent_activity(
+            action.author().clone(),
+            ChainFilter::new(chain_top).take(MESSAGE_RATE_BATCH),
+        )?;
+        let mut reached_window_start = false;
+        let mut earliest: Option<Action> = None;
+        for registered in activity {
+            let previous = registered.action.hashed.content;
+            if previous.timestamp().as_micros() > window_start {
+                if is_create_of(&previous, UnitEntryTypes::GameMessage)? {
+                    count += 1;
+                }
+            } else {
+                reached_window_start = true;
+            }
+            if !earliest
+                .as_ref()
+                .is_some_and(|earliest| earliest.action_seq() < previous.action_seq())
+            {
+                earliest = Some(previous);
+            }
+        }
+        // Timestamps only increase along a chain, so nothing before this batch is in the window
+        if !reached_window_start {
+            next_batch = earliest.and_then(|earliest| earliest.prev_action().cloned());
+        }
+    }
+    Ok(count)
+}
+
+pub fn validate_create_game_message(
+    action: EntryCreationAction,
+    game_message: GameMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    if let GameMessageContent::Text { text } = &game_message.content {
+        if text.trim().is_empty() {
+            return BattleshipsError::EmptyMessage.invalid();
+        }
+        let length = text.chars().count();
+        if length > MAX_MESSAGE_LENGTH {
+            return BattleshipsError::MessageTooLong {
+                max: MAX_MESSAGE_LENGTH,
+                actual: length,
+            }
+            .invalid();
+        }
+    }
+    let game_invite = match must_get_valid_app_entry_and_author(game_message.invite.clone())? {
+        (EntryTypes::GameInvite(game_invite), _) => game_invite,
+        _ => {
+            return BattleshipsError::WrongEntryType {
+                field: "invite".into(),
+                expected: RecordKind::GameInvite,
+            }
+            .invalid()
+        }
+    };
+    if action.author() != &game_invite.home_player && action.author() != &game_invite.away_player {
+        return BattleshipsError::NotAPlayer.invalid();
+    }
+    if recent_message_count(&action)? > MESSAGE_RATE_LIMIT {
+        return BattleshipsError::MessageRateLimited {
+            limit: MESSAGE_RATE_LIMIT,
+            window_micros: MESSAGE_RATE_WINDOW_MICROS,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_game_message(
+    _action: Update,
+    _game_message: GameMessage,
+    _original_action: EntryCreationAction,
+    _original_game_message: GameMessage,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::GameMessage,
+    }
+    .invalid()
+}
+pub fn validate_create_link_game_messages(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let action_hash = ActionHash::from(target_address);
+    let record = must_get_valid_record(action_hash)?;
+    let game_message: crate::GameMessage = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    if AnyLinkableHash::from(game_message.invite) != base_address {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::GameMessages,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,99 @@
+use hdi::{hash_path::path::Path, prelude::*};
+
+use crate::{
+    get_game_turn, helpers::must_get_valid_app_entry_and_author, BattleshipsError, EntryTypes,
+    GameTurn, LinkTypes, RecordKind,
+};
+
+// All ladder results are linked from this anchor
+pub const LADDER_ANCHOR: &str = "ladder";
+
+pub fn ladder_anchor_hash() -> ExternResult<EntryHash> {
+    Path::from(LADDER_ANCHOR).path_entry_hash()
+}
+
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct GameResult {
+    pub invite: ActionHash,
+    // Final revision of the game transcript
+    pub game_transcript: ActionHash,
+    pub winner: AgentPubKey,
+    pub loser: AgentPubKey,
+}
+pub fn validate_create_game_result(
+    action: EntryCreationAction,
+    game_result: GameResult,
+) -> ExternResult<ValidateCallbackResult> {
+    let game_transcript =
+        match must_get_valid_app_entry_and_author(game_result.game_transcript.clone())? {
+            (EntryTypes::GameTranscript(game_transcript), _) => game_transcript,
+            _ => {
+                return BattleshipsError::WrongEntryType {
+                    field: "game_transcript".into(),
+                    expected: RecordKind::GameTranscript,
+                }
+                .invalid()
+            }
+        };
+    if game_transcript.invite != game_result.invite {
+        return BattleshipsError::WrongInvite {
+            kind: RecordKind::GameTranscript,
+        }
+        .invalid();
+    }
+    let game_invite = match must_get_valid_app_entry_and_author(game_result.invite.clone())? {
+        (EntryTypes::GameInvite(game_invite), _) => game_invite,
+        _ => {
+            return BattleshipsError::WrongEntryType {
+                field: "invite".into(),
+                expected: RecordKind::GameInvite,
+            }
+            .invalid()
+        }
+    };
+    if action.author() != &game_invite.home_player && action.author() != &game_invite.away_player {
+        return BattleshipsError::NotAPlayer.invalid();
+    }
+    let (winner, loser) = match get_game_turn(&game_transcript) {
+        GameTurn::HomeWon => (game_invite.home_player, game_invite.away_player),
+        GameTurn::AwayWon => (game_invite.away_player, game_invite.home_player),
+        _ => return BattleshipsError::GameNotFinished.invalid(),
+    };
+    if game_result.winner != winner || game_result.loser != loser {
+        return BattleshipsError::GameResultMismatch.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_game_result(
+    _action: Update,
+    _game_result: GameResult,
+    _original_action: EntryCreationAction,
+    _original_game_result: GameResult,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::GameResult,
+    }
+    .invalid()
+}
+pub fn validate_create_link_ladder_results(
+    _action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address != AnyLinkableHash::from(ladder_anchor_hash()?) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::LadderResults,
+        }
+        .invalid();
+    }
+    let action_hash = ActionHash::from(target_address);
+    let record = must_get_valid_record(action_hash)?;
+    let _game_result: crate::GameResult = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,27 +1,67 @@
 use hdi::prelude::*;
 
-use crate::{constants::BOARD_SIZE, helpers::must_get_valid_app_entry_and_author, EntryTypes};
-#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
-pub struct Shot {
-    pub x: usize,
-    pub y: usize,
-}
+use crate::{
+    check_salvo, first_player, game_turn, helpers::must_get_valid_app_entry_and_author,
+    hits_bitmask, BattleshipsError, EntryTypes, GameInvite, GameRules, GameTurn, HitOrMissProof,
+    HitOrMissProofKind, Outcome, Player, RecordKind, Shot, Transcript,
+};
 #[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
 pub struct ShotOutcome {
     pub proof_hash: ActionHash,
     pub hit: bool,
+    #[serde(default)]
+    pub sunk_ship_length: Option<usize>,
+}
+
+impl Outcome for ShotOutcome {
+    fn hit(&self) -> bool {
+        self.hit
+    }
+
+    fn sunk_ship_length(&self) -> Option<usize> {
+        self.sunk_ship_length
+    }
 }
+// Shots and outcomes are batched per turn. In Classic games every batch holds a single shot,
+// in Salvo games a batch holds one shot per surviving ship of the shooter.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct GameTranscript {
     pub invite: ActionHash,
     pub home_player_deployment_proof: ActionHash,
     pub away_player_deployment_proof: ActionHash,
-    pub home_player_shots: Vec<Shot>,
-    pub away_player_shots: Vec<Shot>,
-    pub home_player_hit_or_miss_proofs: Vec<ShotOutcome>,
-    pub away_player_hit_or_miss_proofs: Vec<ShotOutcome>,
+    pub home_player_coin_flip_reveal: ActionHash,
+    pub away_player_coin_flip_reveal: ActionHash,
+    // Decided by the coin flip reveals
+    pub first_player: Player,
+    pub home_player_shots: Vec<Vec<Shot>>,
+    pub away_player_shots: Vec<Vec<Shot>>,
+    pub home_player_hit_or_miss_proofs: Vec<Vec<ShotOutcome>>,
+    pub away_player_hit_or_miss_proofs: Vec<Vec<ShotOutcome>>,
+}
+
+impl Transcript for GameTranscript {
+    type Outcome = ShotOutcome;
+
+    fn first_player(&self) -> &Player {
+        &self.first_player
+    }
+
+    fn shots(&self, player: &Player) -> &[Vec<Shot>] {
+        match player {
+            Player::Home => &self.home_player_shots,
+            Player::Away => &self.away_player_shots,
+        }
+    }
+
+    fn outcomes(&self, player: &Player) -> &[Vec<ShotOutcome>] {
+        match player {
+            Player::Home => &self.home_player_hit_or_miss_proofs,
+            Player::Away => &self.away_player_hit_or_miss_proofs,
+        }
+    }
 }
+
 pub fn validate_create_game_transcript(
     action: EntryCreationAction,
     game_transcript: GameTranscript,
@@ -31,75 +71,152 @@ pub fn validate_create_game_transcript(
         // succeed to allow other validation to kick in down the road.
         return Ok(ValidateCallbackResult::Valid);
     }
-    if !game_transcript.home_player_shots.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Cannot start transcript with home shots fired".into(),
-        ));
-    }
-    if game_transcript.away_player_shots.len() != 1 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Away player should open game with first shot".into(),
-        ));
-    }
-    if !shot_is_on_board(&game_transcript.away_player_shots[0]) {
-        return Ok(ValidateCallbackResult::Invalid(
-            "First shot out of bounds".into(),
-        ));
-    }
-    if !game_transcript.home_player_hit_or_miss_proofs.is_empty()
-        || !game_transcript.away_player_hit_or_miss_proofs.is_empty()
-    {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Cannot start game with Hit Or Miss Proofs prefilled".into(),
-        ));
-    }
-
-    let game_invite = match must_get_valid_app_entry_and_author(game_transcript.invite)? {
+    let game_invite = match must_get_valid_app_entry_and_author(game_transcript.invite.clone())? {
         (EntryTypes::GameInvite(game_invite), _) => game_invite,
         _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "invite field is wrong type".into(),
-            ))
+            return BattleshipsError::WrongEntryType {
+                field: "invite".into(),
+                expected: RecordKind::GameInvite,
+            }
+            .invalid()
         }
     };
-    if &game_invite.away_player != action.author() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Away player must start game and take first shot".into(),
-        ));
+    if let Some(error) = check_transcript_start(&game_invite, &game_transcript, action.author()) {
+        return error.invalid();
     }
 
-    match must_get_valid_app_entry_and_author(game_transcript.home_player_deployment_proof)? {
-        (EntryTypes::ShipDeploymentProof(_), author) => {
+    let home_player_coin_commitment = match must_get_valid_app_entry_and_author(
+        game_transcript.home_player_deployment_proof.clone(),
+    )? {
+        (EntryTypes::ShipDeploymentProof(deployment_proof), author) => {
             if author != game_invite.home_player {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Home player deployment doesn't match".into(),
-                ));
+                return BattleshipsError::DeploymentMismatch {
+                    player: Player::Home,
+                }
+                .invalid();
             }
+            deployment_proof.coin_commitment
         }
         _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "home_player_deployment_proof field is wrong type".into(),
-            ));
+            return BattleshipsError::WrongEntryType {
+                field: "home_player_deployment_proof".into(),
+                expected: RecordKind::ShipDeploymentProof,
+            }
+            .invalid();
         }
-    }
+    };
 
-    match must_get_valid_app_entry_and_author(game_transcript.away_player_deployment_proof)? {
-        (EntryTypes::ShipDeploymentProof(_), author) => {
+    let away_player_coin_commitment = match must_get_valid_app_entry_and_author(
+        game_transcript.away_player_deployment_proof.clone(),
+    )? {
+        (EntryTypes::ShipDeploymentProof(deployment_proof), author) => {
             if author != game_invite.away_player {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Away player deployment doesn't match".into(),
-                ));
+                return BattleshipsError::DeploymentMismatch {
+                    player: Player::Away,
+                }
+                .invalid();
+            }
+            deployment_proof.coin_commitment
+        }
+        _ => {
+            return BattleshipsError::WrongEntryType {
+                field: "away_player_deployment_proof".into(),
+                expected: RecordKind::ShipDeploymentProof,
+            }
+            .invalid();
+        }
+    };
+
+    // Only a copy of the opponent's commitment can match it
+    if home_player_coin_commitment == away_player_coin_commitment {
+        return BattleshipsError::CopiedCoinCommitment.invalid();
+    }
+
+    let home_player_secret = match must_get_valid_app_entry_and_author(
+        game_transcript.home_player_coin_flip_reveal.clone(),
+    )? {
+        (EntryTypes::CoinFlipReveal(coin_flip_reveal), _)
+            if coin_flip_reveal.deployment_proof
+                == game_transcript.home_player_deployment_proof =>
+        {
+            coin_flip_reveal.secret
+        }
+        _ => {
+            return BattleshipsError::CoinFlipRevealMismatch {
+                player: Player::Home,
             }
+            .invalid()
+        }
+    };
+    let away_player_secret = match must_get_valid_app_entry_and_author(
+        game_transcript.away_player_coin_flip_reveal.clone(),
+    )? {
+        (EntryTypes::CoinFlipReveal(coin_flip_reveal), _)
+            if coin_flip_reveal.deployment_proof
+                == game_transcript.away_player_deployment_proof =>
+        {
+            coin_flip_reveal.secret
         }
         _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "away_player_deployment_proof field is wrong type".into(),
-            ));
+            return BattleshipsError::CoinFlipRevealMismatch {
+                player: Player::Away,
+            }
+            .invalid()
         }
+    };
+    if first_player(&home_player_secret, &away_player_secret) != game_transcript.first_player {
+        return BattleshipsError::FirstPlayerMismatch.invalid();
     }
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Checks the opening salvo of a new transcript
+pub fn check_transcript_start(
+    game_invite: &GameInvite,
+    game_transcript: &GameTranscript,
+    author: &AgentPubKey,
+) -> Option<BattleshipsError> {
+    let (opener_shots, other_shots) = match game_transcript.first_player {
+        Player::Home => (
+            &game_transcript.home_player_shots,
+            &game_transcript.away_player_shots,
+        ),
+        Player::Away => (
+            &game_transcript.away_player_shots,
+            &game_transcript.home_player_shots,
+        ),
+    };
+    if !other_shots.is_empty() {
+        return Some(BattleshipsError::InvalidTranscriptStart);
+    }
+    if opener_shots.len() != 1 {
+        return Some(BattleshipsError::InvalidTranscriptStart);
+    }
+    if !game_transcript.home_player_hit_or_miss_proofs.is_empty()
+        || !game_transcript.away_player_hit_or_miss_proofs.is_empty()
+    {
+        return Some(BattleshipsError::InvalidTranscriptStart);
+    }
+
+    let opener = match game_transcript.first_player {
+        Player::Home => &game_invite.home_player,
+        Player::Away => &game_invite.away_player,
+    };
+    if opener != author {
+        return Some(BattleshipsError::NotYourTurn {
+            turn: match game_transcript.first_player {
+                Player::Home => GameTurn::HomeShot,
+                Player::Away => GameTurn::AwayShot,
+            },
+        });
+    }
+    let no_outcomes: &[Vec<ShotOutcome>] = &[];
+    check_salvo(&game_invite.rules, no_outcomes, &[], &opener_shots[0])
+        .err()
+        .map(BattleshipsError::from)
+}
+
 pub fn validate_update_game_transcript(
     action: Update,
     game_transcript: GameTranscript,
@@ -108,62 +225,75 @@ pub fn validate_update_game_transcript(
 ) -> ExternResult<ValidateCallbackResult> {
     let game_invite = match must_get_valid_app_entry_and_author(game_transcript.invite.clone())? {
         // Note that we don't bother trying to check whether the invite has been tampered here
-        // because that in effect happens anyway at the end of validate_add_shot and
-        // validate_add_proof via the full equality check.
+        // because that in effect happens anyway at the end of check_add_shot and
+        // check_add_proof via the full equality check.
         (EntryTypes::GameInvite(game_invite), _) => game_invite,
         _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "invite field is wrong type".into(),
-            ))
-        }
-    };
-    match get_game_turn(&original_game_transcript) {
-        GameTurn::AwayShot => {
-            // All lists are the same length therefore we're at the beginning of the cycle.
-            // Step 1 - Away player should be adding a shot
-            if action.author != game_invite.away_player {
-                return Ok(ValidateCallbackResult::Invalid("Away player's turn".into()));
-            }
-            validate_add_shot(game_transcript, original_game_transcript, false)
-        }
-        GameTurn::HomeProof => {
-            // Step 2 - Home player should be adding a proof
-            if action.author != game_invite.home_player {
-                return Ok(ValidateCallbackResult::Invalid("Home player's turn".into()));
+            return BattleshipsError::WrongEntryType {
+                field: "invite".into(),
+                expected: RecordKind::GameInvite,
             }
-            validate_add_proof(
-                game_transcript,
-                original_game_transcript,
-                action.author,
-                true,
-            )
+            .invalid()
         }
-        GameTurn::HomeShot => {
-            // Step 3 - Home player should be adding a shot
-            if action.author != game_invite.home_player {
-                return Ok(ValidateCallbackResult::Invalid("Home player's turn".into()));
-            }
-            validate_add_shot(game_transcript, original_game_transcript, true)
-        }
-        GameTurn::AwayProof => {
-            // Step 4 - Away player should be adding a proof
-            if action.author != game_invite.away_player {
-                return Ok(ValidateCallbackResult::Invalid("Away player's turn".into()));
+    };
+    let get_proof =
+        |proof_hash: &ActionHash| match must_get_valid_app_entry_and_author(proof_hash.clone())? {
+            (EntryTypes::HitOrMissProof(hit_or_miss_proof), author) => {
+                Ok(Some((hit_or_miss_proof, author)))
             }
-            validate_add_proof(
-                game_transcript,
-                original_game_transcript,
-                action.author,
-                false,
-            )
-        }
-        GameTurn::Corrupt => {
-            // This should never happen. If it does that means a corrupt state previously made it
-            // through validation.
-            Ok(ValidateCallbackResult::Invalid(
-                "Original is corrupt".into(),
-            ))
-        }
+            _ => Ok(None),
+        };
+    match check_move(
+        &game_invite,
+        original_game_transcript,
+        game_transcript,
+        &action.author,
+        get_proof,
+    )? {
+        Some(error) => error.invalid(),
+        None => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+/// Checks a revision of a transcript against the one it updates. get_proof looks up a
+/// HitOrMissProof and its author by action hash.
+pub fn check_move<F>(
+    game_invite: &GameInvite,
+    original_game_transcript: GameTranscript,
+    game_transcript: GameTranscript,
+    author: &AgentPubKey,
+    get_proof: F,
+) -> ExternResult<Option<BattleshipsError>>
+where
+    F: Fn(&ActionHash) -> ExternResult<Option<(HitOrMissProof, AgentPubKey)>>,
+{
+    let turn = get_game_turn(&original_game_transcript);
+    let (mover, is_by_home_player) = match turn {
+        GameTurn::HomeShot | GameTurn::HomeProof => (&game_invite.home_player, true),
+        GameTurn::AwayShot | GameTurn::AwayProof => (&game_invite.away_player, false),
+        GameTurn::HomeWon | GameTurn::AwayWon => return Ok(Some(BattleshipsError::GameFinished)),
+        // This should never happen. If it does that means a corrupt state previously made it
+        // through validation.
+        GameTurn::Corrupt => return Ok(Some(BattleshipsError::CorruptTranscript)),
+    };
+    if author != mover {
+        return Ok(Some(BattleshipsError::NotYourTurn { turn }));
+    }
+    match turn {
+        GameTurn::HomeShot | GameTurn::AwayShot => Ok(check_add_shot(
+            game_transcript,
+            original_game_transcript,
+            is_by_home_player,
+            &game_invite.rules,
+        )),
+        _ => check_add_proof(
+            game_transcript,
+            original_game_transcript,
+            author,
+            is_by_home_player,
+            &game_invite.rules,
+            get_proof,
+        ),
     }
 }
 
@@ -172,166 +302,164 @@ pub fn validate_delete_game_transcript(
     _original_action: EntryCreationAction,
     _original_game_transcript: GameTranscript,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Game Transcripts cannot be deleted",
-    )))
-}
-
-fn shot_is_on_board(shot: &Shot) -> bool {
-    shot.x < BOARD_SIZE && shot.y < BOARD_SIZE
-}
-
-#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
-#[serde(tag = "type")]
-pub enum GameTurn {
-    AwayShot,
-    HomeProof,
-    HomeShot,
-    AwayProof,
-    Corrupt,
-}
-
-pub fn get_game_turn(game_transcript: &GameTranscript) -> GameTurn {
-    // Infer who's turn it should be
-    // Sequence of permitted steps is:
-    // 1. away fires shot (included in create too)
-    // 2. home adds hit/miss proof
-    // 3. home fires shot
-    // 4. away adds hit/miss proof
-    // 5. repeat steps 1-4 until game ends (17 hits made by one player)
-    // Therefore the step (1-4) can be inferred from shot & proof vec lengths
-    let away_shot_len = game_transcript.away_player_shots.len();
-    let home_proof_len = game_transcript.home_player_hit_or_miss_proofs.len();
-    let home_shot_len = game_transcript.home_player_shots.len();
-    let away_proof_len = game_transcript.away_player_hit_or_miss_proofs.len();
-    let baseline = std::cmp::min(
-        std::cmp::min(home_shot_len, away_shot_len),
-        std::cmp::min(home_proof_len, away_proof_len),
-    );
-    match (
-        away_shot_len - baseline,
-        home_proof_len - baseline,
-        home_shot_len - baseline,
-        away_proof_len - baseline,
-    ) {
-        // All lists are the same length therefore we're at the beginning of the cycle.
-        (0, 0, 0, 0) => GameTurn::AwayShot,
-        (1, 0, 0, 0) => GameTurn::HomeProof,
-        (1, 1, 0, 0) => GameTurn::HomeShot,
-        (1, 1, 1, 0) => GameTurn::AwayProof,
-        // This should never happen
-        _ => GameTurn::Corrupt,
+    BattleshipsError::DeleteNotAllowed {
+        kind: RecordKind::GameTranscript,
     }
+    .invalid()
 }
 
-fn additional_shot_is_valid(existing_shots: &[Shot], shot: &Shot) -> bool {
-    shot_is_on_board(shot) && !existing_shots.contains(shot)
+pub fn get_game_turn(game_transcript: &GameTranscript) -> GameTurn {
+    game_turn(game_transcript)
 }
 
-fn validate_add_shot(
+pub fn check_add_shot(
     mut game_transcript: GameTranscript,
     original_game_transcript: GameTranscript,
     is_by_home_player: bool,
-) -> ExternResult<ValidateCallbackResult> {
-    // After isolating the additional shot the transcripts should be identical
-    let changed_shots = if is_by_home_player {
-        &mut game_transcript.home_player_shots
+    rules: &GameRules,
+) -> Option<BattleshipsError> {
+    let player = if is_by_home_player {
+        Player::Home
     } else {
-        &mut game_transcript.away_player_shots
+        Player::Away
     };
-    let shot = match changed_shots.pop() {
-        Some(shot) => shot,
-        None => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Away player shot not found".into(),
-            ))
-        }
+    // After isolating the additional salvo the transcripts should be identical
+    let (changed_salvos, own_shot_outcomes) = if is_by_home_player {
+        (
+            &mut game_transcript.home_player_shots,
+            &game_transcript.home_player_hit_or_miss_proofs,
+        )
+    } else {
+        (
+            &mut game_transcript.away_player_shots,
+            &game_transcript.away_player_hit_or_miss_proofs,
+        )
+    };
+    let salvo = match changed_salvos.pop() {
+        Some(salvo) => salvo,
+        None => return Some(BattleshipsError::MissingSalvo { player }),
     };
-    if !additional_shot_is_valid(changed_shots, &shot) {
-        return Ok(ValidateCallbackResult::Invalid("Invalid shot".into()));
+    if let Err(error) = check_salvo(rules, own_shot_outcomes, changed_salvos, &salvo) {
+        return Some(error.into());
     }
     if game_transcript != original_game_transcript {
-        return Ok(ValidateCallbackResult::Invalid(format!(
-            "Only acceptable difference is additional {} player shot",
-            if is_by_home_player { "home" } else { "away" }
-        )));
+        return Some(BattleshipsError::UnexpectedTranscriptChange { player });
     }
-    Ok(ValidateCallbackResult::Valid)
+    None
 }
 
-fn validate_add_proof(
+pub fn check_add_proof<F>(
     mut game_transcript: GameTranscript,
     original_game_transcript: GameTranscript,
-    author: AgentPubKey,
+    author: &AgentPubKey,
     is_by_home_player: bool,
-) -> ExternResult<ValidateCallbackResult> {
+    rules: &GameRules,
+    get_proof: F,
+) -> ExternResult<Option<BattleshipsError>>
+where
+    F: Fn(&ActionHash) -> ExternResult<Option<(HitOrMissProof, AgentPubKey)>>,
+{
+    let player = if is_by_home_player {
+        Player::Home
+    } else {
+        Player::Away
+    };
     let changed_proofs = if is_by_home_player {
         &mut game_transcript.home_player_hit_or_miss_proofs
     } else {
         &mut game_transcript.away_player_hit_or_miss_proofs
     };
-    let shot_outcome = match changed_proofs.pop() {
-        Some(shot_outcome) => shot_outcome,
-        None => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Home player proof not found".into(),
-            ))
-        }
+    let shot_outcomes = match changed_proofs.pop() {
+        Some(shot_outcomes) => shot_outcomes,
+        None => return Ok(Some(BattleshipsError::MissingProofs { player })),
     };
-    let (hit_or_miss_proof, proof_author) =
-        match must_get_valid_app_entry_and_author(shot_outcome.proof_hash)? {
-            (EntryTypes::HitOrMissProof(hit_or_miss_proof), proof_author) => {
-                (hit_or_miss_proof, proof_author)
-            }
-            _ => {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Provided hash is not for a Hit Or Miss Proof".into(),
-                ))
-            }
-        };
-    if shot_outcome.hit != hit_or_miss_proof.hit {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Shot outcome inconsistent with proof".into(),
-        ));
-    }
-    if proof_author != author {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Provided proof is by a different author".into(),
-        ));
-    }
-    let expected_deployment_proof = if is_by_home_player {
-        &game_transcript.home_player_deployment_proof
+    let (earlier_shot_outcomes, opponent_salvos, expected_deployment_proof) = if is_by_home_player {
+        (
+            &game_transcript.home_player_hit_or_miss_proofs,
+            &game_transcript.away_player_shots,
+            &game_transcript.home_player_deployment_proof,
+        )
     } else {
-        &game_transcript.away_player_deployment_proof
+        (
+            &game_transcript.away_player_hit_or_miss_proofs,
+            &game_transcript.home_player_shots,
+            &game_transcript.away_player_deployment_proof,
+        )
     };
-    // We don't interrogate whether the deployment commitment matches here since that will have
-    // been done during proof creation validation. Therefore it is sufficient to test shallowly.
-    if &hit_or_miss_proof.deployment_proof != expected_deployment_proof {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Provided proof is for a different deployment".into(),
-        ));
+    let opponent_salvo = opponent_salvos
+        .last()
+        .expect("player_shots count is baseline + 1");
+    if shot_outcomes.len() != opponent_salvo.len() {
+        return Ok(Some(BattleshipsError::WrongProofCount {
+            expected: opponent_salvo.len(),
+            actual: shot_outcomes.len(),
+        }));
     }
-    let opponent_shots = if is_by_home_player {
-        &game_transcript.away_player_shots
-    } else {
-        &game_transcript.home_player_shots
-    };
-    if &hit_or_miss_proof.shot
-        != opponent_shots
-            .last()
-            .expect("player_shots count is baseline + 1")
-    {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Provided proof is for a different shot".into(),
-        ));
+    // Earlier outcomes line up with the opponent's earlier salvos
+    let mut proven_hits: Vec<Shot> = opponent_salvos
+        .iter()
+        .flatten()
+        .zip(earlier_shot_outcomes.iter().flatten())
+        .filter(|(_, shot_outcome)| shot_outcome.hit)
+        .map(|(shot, _)| shot.clone())
+        .collect();
+    for (shot, shot_outcome) in opponent_salvo.iter().zip(shot_outcomes) {
+        let (hit_or_miss_proof, proof_author) = match get_proof(&shot_outcome.proof_hash)? {
+            Some(proof_and_author) => proof_and_author,
+            None => {
+                return Ok(Some(BattleshipsError::WrongEntryType {
+                    field: "proof_hash".into(),
+                    expected: RecordKind::HitOrMissProof,
+                }))
+            }
+        };
+        if shot_outcome.hit != hit_or_miss_proof.hit
+            || shot_outcome.sunk_ship_length != hit_or_miss_proof.sunk_ship_length()
+        {
+            return Ok(Some(BattleshipsError::OutcomeInconsistentWithProof {
+                shot: shot.clone(),
+            }));
+        }
+        if &proof_author != author {
+            return Ok(Some(BattleshipsError::ProofByWrongAuthor));
+        }
+        // We don't interrogate whether the deployment commitment matches here since that will have
+        // been done during proof creation validation. Therefore it is sufficient to test shallowly.
+        if &hit_or_miss_proof.deployment_proof != expected_deployment_proof {
+            return Ok(Some(BattleshipsError::ProofForWrongDeployment));
+        }
+        if &hit_or_miss_proof.shot != shot {
+            return Ok(Some(BattleshipsError::ProofForWrongShot {
+                expected: shot.clone(),
+                actual: hit_or_miss_proof.shot,
+            }));
+        }
+        match &hit_or_miss_proof.kind {
+            HitOrMissProofKind::WithSunk { prior_hits, .. } => {
+                if prior_hits.len() != proven_hits.len()
+                    || hits_bitmask(prior_hits) != hits_bitmask(&proven_hits)
+                {
+                    return Ok(Some(BattleshipsError::PriorHitsMismatch));
+                }
+            }
+            HitOrMissProofKind::HitOrMiss => {
+                // Every hit has to announce whether it sank a ship, and Salvo sizes also depend
+                // on the ships proven sunk, so only Classic misses may leave it out
+                if shot_outcome.hit || rules == &GameRules::Salvo {
+                    return Ok(Some(BattleshipsError::SunkProofRequired));
+                }
+            }
+        }
+        if shot_outcome.hit {
+            proven_hits.push(shot.clone());
+        }
     }
     if game_transcript != original_game_transcript {
-        return Ok(ValidateCallbackResult::Invalid(format!(
-            "Only acceptable difference is additional {} player proof",
-            if is_by_home_player { "home" } else { "away" }
-        )));
+        return Ok(Some(BattleshipsError::UnexpectedTranscriptChange {
+            player,
+        }));
     }
-    Ok(ValidateCallbackResult::Valid)
+    Ok(None)
 }
 
 pub fn validate_create_link_game_transcript_updates(
@@ -346,18 +474,14 @@ pub fn validate_create_link_game_transcript_updates(
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Linked action must reference an entry"
-        ))))?;
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
     let action_hash = ActionHash::from(target_address);
     let record = must_get_valid_record(action_hash)?;
     let _game_transcript: crate::GameTranscript = record
         .entry()
         .to_app_option()
         .map_err(|e| wasm_error!(e))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest(String::from(
-            "Linked action must reference an entry"
-        ))))?;
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
     // TODO: should we also validate the diff between the original record and ones that came between?
     Ok(ValidateCallbackResult::Valid)
 }
@@ -1,3 +1,6 @@
+// With the mock verifier the Groth16 parsing below still compiles, unused
+#![cfg_attr(feature = "mock-verifier", allow(dead_code))]
+
 use std::str::FromStr;
 
 use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
@@ -5,6 +8,32 @@ use ark_ff::Fp;
 use ark_groth16::{prepare_verifying_key, Groth16, Proof, VerifyingKey};
 use hdi::prelude::*;
 
+use crate::BattleshipsError;
+
+#[cfg(all(feature = "mock-verifier", not(debug_assertions)))]
+compile_error!(
+    "The mock-verifier feature accepts forged proofs and must not be used in release builds"
+);
+
+/// The verification key of a circuit in `circuits/build`. With the mock verifier it is just the
+/// circuit's name, so the zomes build without the circuits
+#[cfg(not(feature = "mock-verifier"))]
+macro_rules! verification_key {
+    ($circuit:literal) => {
+        include_str!(concat!(
+            "../../../../../../circuits/build/",
+            $circuit,
+            "/verification_key.json"
+        ))
+    };
+}
+#[cfg(feature = "mock-verifier")]
+macro_rules! verification_key {
+    ($circuit:literal) => {
+        $circuit
+    };
+}
+
 type G1Json = [String; 3];
 type G2Json = [[String; 2]; 3];
 
@@ -76,6 +105,7 @@ fn parse_public_inputs(public_input_strs: &[&str]) -> Vec<Fr> {
         .collect()
 }
 
+#[cfg(not(feature = "mock-verifier"))]
 pub fn verify(
     vk_json_str: &str,
     proof_str: &str,
@@ -86,5 +116,285 @@ pub fn verify(
     let proof = parse_proof(proof_str);
     let public_inputs = parse_public_inputs(public_input_strs);
     Groth16::<Bn254>::verify_proof(&pvk, &proof, &public_inputs)
-        .map_err(|_| wasm_error!(WasmErrorInner::Guest(String::from("Groth16 error"))))
+        .map_err(|_| WasmError::from(BattleshipsError::MalformedProof))
+}
+
+/// The mock proof of a circuit's public inputs, in the order the circuit outputs them
+#[cfg(feature = "mock-verifier")]
+pub fn mock_proof(circuit: &str, public_input_strs: &[&str]) -> String {
+    format!("mock:{}:{}", circuit, public_input_strs.join(","))
+}
+
+/// Accepts exactly the proofs made by `mock_proof` for the circuit and public inputs
+#[cfg(feature = "mock-verifier")]
+pub fn verify(circuit: &str, proof_str: &str, public_input_strs: &[&str]) -> ExternResult<bool> {
+    if !proof_str.starts_with("mock:") {
+        return Err(BattleshipsError::MalformedProof.into());
+    }
+    Ok(proof_str == mock_proof(circuit, public_input_strs))
+}
+
+// The synthetic fixtures are written by `examples/generate_groth16_fixtures.rs`, and the proofs
+// of the built circuits by `circuits/build_all.sh`
+#[cfg(all(test, not(feature = "mock-verifier")))]
+mod tests {
+    use super::*;
+    use crate::{constants::SHIP_LENGTHS, poseidon::deployment_commitment, Ship};
+
+    struct Fixtures {
+        vk: &'static str,
+        wrong_vk: &'static str,
+        proof: &'static str,
+        tampered_proof: &'static str,
+        public: &'static str,
+        wrong_public: &'static str,
+    }
+
+    macro_rules! fixtures {
+        ($circuit:literal) => {
+            Fixtures {
+                vk: include_str!(concat!(
+                    "../fixtures/groth16/",
+                    $circuit,
+                    "/verification_key.json"
+                )),
+                wrong_vk: include_str!(concat!(
+                    "../fixtures/groth16/",
+                    $circuit,
+                    "/wrong_verification_key.json"
+                )),
+                proof: include_str!(concat!("../fixtures/groth16/", $circuit, "/proof.json")),
+                tampered_proof: include_str!(concat!(
+                    "../fixtures/groth16/",
+                    $circuit,
+                    "/tampered_proof.json"
+                )),
+                public: include_str!(concat!("../fixtures/groth16/", $circuit, "/public.json")),
+                wrong_public: include_str!(concat!(
+                    "../fixtures/groth16/",
+                    $circuit,
+                    "/wrong_public.json"
+                )),
+            }
+        };
+    }
+
+    fn circuits() -> [Fixtures; 3] {
+        [
+            fixtures!("create"),
+            fixtures!("move"),
+            fixtures!("move_sunk"),
+        ]
+    }
+
+    /// Flattens a snarkjs proof the same way the UI does before sending it to the zomes
+    fn proof_str(proof_json: &str) -> String {
+        let proof: serde_json::Value = serde_json::from_str(proof_json).unwrap();
+        [
+            &proof["pi_a"][0],
+            &proof["pi_a"][1],
+            &proof["pi_b"][0][0],
+            &proof["pi_b"][0][1],
+            &proof["pi_b"][1][0],
+            &proof["pi_b"][1][1],
+            &proof["pi_c"][0],
+            &proof["pi_c"][1],
+        ]
+        .map(|part| part.as_str().unwrap())
+        .join(",")
+    }
+
+    fn public_input_strs(public_json: &str) -> Vec<String> {
+        serde_json::from_str(public_json).unwrap()
+    }
+
+    fn verify_fixture(vk: &str, proof: &str, public: &str) -> ExternResult<bool> {
+        let public_inputs = public_input_strs(public);
+        let public_inputs: Vec<&str> = public_inputs.iter().map(String::as_str).collect();
+        verify(vk, &proof_str(proof), &public_inputs)
+    }
+
+    #[test]
+    fn parse_vk_json_reads_snarkjs_keys() {
+        for (fixtures, n_public) in circuits().iter().zip([1, 4, 6]) {
+            let vk = parse_vk_json(fixtures.vk);
+            assert_eq!(vk.gamma_abc_g1.len(), n_public + 1);
+            assert!(vk.alpha_g1.is_on_curve());
+            assert!(vk.beta_g2.is_on_curve());
+            assert!(vk.gamma_g2.is_on_curve());
+            assert!(vk.delta_g2.is_on_curve());
+            assert!(vk.gamma_abc_g1.iter().all(G1Affine::is_on_curve));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_vk_json_rejects_other_proving_systems() {
+        parse_vk_json(&fixtures!("move").vk.replace("groth16", "plonk"));
+    }
+
+    #[test]
+    fn parse_proof_reads_comma_separated_points() {
+        let proof_json: serde_json::Value = serde_json::from_str(fixtures!("move").proof).unwrap();
+        let proof = parse_proof(&proof_str(fixtures!("move").proof));
+        assert_eq!(proof.a.x, parse_fq(proof_json["pi_a"][0].as_str().unwrap()));
+        assert_eq!(
+            proof.b.x.c1,
+            parse_fq(proof_json["pi_b"][0][1].as_str().unwrap())
+        );
+        assert_eq!(proof.c.y, parse_fq(proof_json["pi_c"][1].as_str().unwrap()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_proof_needs_eight_numbers() {
+        let proof = proof_str(fixtures!("move").proof);
+        parse_proof(proof.rsplit_once(',').unwrap().0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_proof_rejects_points_off_the_curve() {
+        let proof = proof_str(fixtures!("move").proof);
+        let mut parts: Vec<&str> = proof.split(',').collect();
+        parts[1] = "1";
+        parse_proof(&parts.join(","));
+    }
+
+    #[test]
+    fn parse_public_inputs_reads_decimal_field_elements() {
+        assert_eq!(
+            parse_public_inputs(&["0", "1", "42"]),
+            vec![Fr::from(0u64), Fr::from(1u64), Fr::from(42u64)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_public_inputs_rejects_non_numbers() {
+        parse_public_inputs(&["0x2a"]);
+    }
+
+    #[test]
+    fn verify_accepts_valid_proofs() {
+        for fixtures in circuits() {
+            assert!(verify_fixture(fixtures.vk, fixtures.proof, fixtures.public).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_rejects_tampered_proofs() {
+        for fixtures in circuits() {
+            assert!(
+                !verify_fixture(fixtures.vk, fixtures.tampered_proof, fixtures.public).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn verify_rejects_wrong_public_inputs() {
+        for fixtures in circuits() {
+            assert!(!verify_fixture(fixtures.vk, fixtures.proof, fixtures.wrong_public).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_rejects_proofs_for_another_key() {
+        for fixtures in circuits() {
+            assert!(!verify_fixture(fixtures.wrong_vk, fixtures.proof, fixtures.public).unwrap());
+        }
+    }
+
+    /// A proof of the circuit's inputs in `circuits/fixtures`, from the same build as the key
+    /// the zomes embed
+    struct CircuitProof {
+        vk: &'static str,
+        proof: &'static str,
+        public: &'static str,
+    }
+
+    macro_rules! circuit_proof {
+        ($circuit:literal) => {
+            CircuitProof {
+                vk: verification_key!($circuit),
+                proof: include_str!(concat!(
+                    "../../../../../../circuits/build/",
+                    $circuit,
+                    "/proof.json"
+                )),
+                public: include_str!(concat!(
+                    "../../../../../../circuits/build/",
+                    $circuit,
+                    "/public.json"
+                )),
+            }
+        };
+    }
+
+    fn fixture_commitment() -> String {
+        let ships: Vec<Ship> = (0..SHIP_LENGTHS.len())
+            .map(|i| Ship {
+                x: 0,
+                y: i * 2,
+                horizontal: true,
+            })
+            .collect();
+        deployment_commitment(&ships, "1234567")
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn verify_accepts_proofs_of_the_built_circuits() {
+        let commitment = fixture_commitment();
+        // The destroyer on row 8 is shot at (1, 8), after (0, 8) was already hit
+        for (circuit, expected_public) in [
+            (circuit_proof!("create"), vec![commitment.as_str()]),
+            (circuit_proof!("move"), vec!["1", &commitment, "1", "8"]),
+            (
+                circuit_proof!("move_sunk"),
+                vec!["1", "2", &commitment, "1", "8", "1208925819614629174706176"],
+            ),
+        ] {
+            assert_eq!(public_input_strs(circuit.public), expected_public);
+            assert!(verify_fixture(circuit.vk, circuit.proof, circuit.public).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_rejects_the_built_circuits_proofs_of_other_outcomes() {
+        // Claiming a miss, and a hit that sank nothing
+        for (circuit, output, claimed) in [
+            (circuit_proof!("move"), 0, "0"),
+            (circuit_proof!("move_sunk"), 1, "0"),
+        ] {
+            let mut public_inputs = public_input_strs(circuit.public);
+            public_inputs[output] = claimed.into();
+            let public_inputs: Vec<&str> = public_inputs.iter().map(String::as_str).collect();
+            assert!(!verify(circuit.vk, &proof_str(circuit.proof), &public_inputs).unwrap());
+        }
+    }
+
+    #[test]
+    fn verify_fails_on_the_wrong_number_of_public_inputs() {
+        let fixtures = fixtures!("move");
+        let proof = proof_str(fixtures.proof);
+        let public_inputs = public_input_strs(fixtures.public);
+        let public_inputs: Vec<&str> = public_inputs.iter().map(String::as_str).collect();
+        assert!(verify(fixtures.vk, &proof, &public_inputs[1..]).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "mock-verifier"))]
+mod mock_tests {
+    use super::*;
+
+    #[test]
+    fn mock_proofs_only_verify_their_own_circuit_and_inputs() {
+        let proof = mock_proof("move", &["1", "42", "3", "4"]);
+        assert!(verify("move", &proof, &["1", "42", "3", "4"]).unwrap());
+        assert!(!verify("move", &proof, &["0", "42", "3", "4"]).unwrap());
+        assert!(!verify("move_sunk", &proof, &["1", "42", "3", "4"]).unwrap());
+        assert!(verify("move", "1,2,3,4,5,6,7,8", &["1", "42", "3", "4"]).is_err());
+    }
 }
@@ -1,6 +1,6 @@
 use hdi::prelude::*;
 
-use crate::EntryTypes;
+use crate::{BattleshipsError, EntryTypes, LinkTypes, UnitEntryTypes};
 
 pub fn must_get_valid_app_entry_and_author(
     action_hash: ActionHash,
@@ -9,20 +9,14 @@ pub fn must_get_valid_app_entry_and_author(
     let author = record.action().author().clone();
     match get_entry_for_record(&record)? {
         Some(app_entry) => Ok((app_entry, author)),
-        None => Err(wasm_error!(WasmErrorInner::Guest(
-            "Could not deserialise entry".into()
-        ))),
+        None => Err(BattleshipsError::EntryNotDeserializable.into()),
     }
 }
 
 pub fn get_entry_for_record(record: &Record) -> ExternResult<Option<EntryTypes>> {
     let entry = match record.entry().as_option() {
         Some(entry) => entry,
-        None => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Entry not present".into(),
-            )))
-        }
+        None => return Err(BattleshipsError::EntryNotDeserializable.into()),
     };
     let (zome_index, entry_index) = match record.action().entry_type() {
         Some(EntryType::App(AppEntryDef {
@@ -30,11 +24,105 @@ pub fn get_entry_for_record(record: &Record) -> ExternResult<Option<EntryTypes>>
             entry_index,
             ..
         })) => (zome_index, entry_index),
-        _ => {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Not an app entry type".into(),
-            )))
-        }
+        _ => return Err(BattleshipsError::EntryNotDeserializable.into()),
     };
     EntryTypes::deserialize_from_type(*zome_index, *entry_index, entry)
 }
+
+/// Whether the action creates an entry of the given type
+pub fn is_create_of(action: &Action, entry_type: UnitEntryTypes) -> ExternResult<bool> {
+    let scoped_entry_type = ScopedEntryDefIndex::try_from(entry_type)?;
+    Ok(match action {
+        Action::Create(Create {
+            entry_type: EntryType::App(app_entry_def),
+            ..
+        }) => {
+            app_entry_def.zome_index == scoped_entry_type.zome_index
+                && app_entry_def.entry_index == scoped_entry_type.zome_type
+        }
+        _ => false,
+    })
+}
+
+/// Whether the action creates a link of the given type
+pub fn is_create_link_of(action: &Action, link_type: LinkTypes) -> ExternResult<bool> {
+    let scoped_link_type = ScopedLinkType::try_from(link_type)?;
+    Ok(match action {
+        Action::CreateLink(create_link) => {
+            create_link.zome_index == scoped_link_type.zome_index
+                && create_link.link_type == scoped_link_type.zome_type
+        }
+        _ => false,
+    })
+}
+
+/// The link creation an action hash refers to, if it creates a link of the given type
+pub fn must_get_create_link_of(
+    action_hash: ActionHash,
+    link_type: LinkTypes,
+) -> ExternResult<Option<CreateLink>> {
+    let record = must_get_valid_record(action_hash)?;
+    if !is_create_link_of(record.action(), link_type)? {
+        return Ok(None);
+    }
+    match record.action() {
+        Action::CreateLink(create_link) => Ok(Some(create_link.clone())),
+        _ => Ok(None),
+    }
+}
+
+/// Whether the author already created an entry of the given type, earlier in their chain than
+/// this action, for which `matches` holds
+pub fn has_earlier_create_of<T, F>(
+    action: &EntryCreationAction,
+    entry_type: UnitEntryTypes,
+    matches: F,
+) -> ExternResult<bool>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    F: Fn(&T) -> bool,
+{
+    let activity = must_get_agent_activity(
+        action.author().clone(),
+        ChainFilter::new(action.prev_action().clone()),
+    )?;
+    for registered in activity {
+        if !is_create_of(&registered.action.hashed.content, entry_type)? {
+            continue;
+        }
+        let record = must_get_valid_record(registered.action.hashed.hash)?;
+        let earlier: Option<T> = record.entry().to_app_option().map_err(|e| wasm_error!(e))?;
+        if earlier.as_ref().is_some_and(&matches) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Whether the author already created a link of the given type, earlier in their chain than
+/// this one, for which `matches` holds
+pub fn has_earlier_create_link<F>(
+    action: &CreateLink,
+    link_type: LinkTypes,
+    matches: F,
+) -> ExternResult<bool>
+where
+    F: Fn(&CreateLink) -> bool,
+{
+    let activity = must_get_agent_activity(
+        action.author.clone(),
+        ChainFilter::new(action.prev_action.clone()),
+    )?;
+    for registered in activity {
+        let earlier = &registered.action.hashed.content;
+        if !is_create_link_of(earlier, link_type)? {
+            continue;
+        }
+        if let Action::CreateLink(create_link) = earlier {
+            if matches(create_link) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
@@ -1,6 +1,24 @@
 use hdi::prelude::*;
 
-use crate::{groth16::verify, helpers::must_get_valid_app_entry_and_author, EntryTypes, Shot};
+use crate::{
+    constants::{BOARD_SIZE, SHIP_LENGTHS},
+    groth16::verify,
+    helpers::must_get_valid_app_entry_and_author,
+    hits_bitmask, BattleshipsError, EntryTypes, ProofKind, RecordKind, Shot,
+};
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug, Default)]
+#[serde(tag = "type")]
+pub enum HitOrMissProofKind {
+    // Proven with the move circuit
+    #[default]
+    HitOrMiss,
+    // Proven with the move_sunk circuit. prior_hits are the hits previously proven against the
+    // same deployment, which must match the transcript when the proof is added to it.
+    WithSunk {
+        prior_hits: Vec<Shot>,
+        sunk_ship_length: Option<usize>,
+    },
+}
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct HitOrMissProof {
@@ -9,42 +27,104 @@ pub struct HitOrMissProof {
     pub shot: Shot,
     pub hit: bool,
     pub proof: String,
+    #[serde(default)]
+    pub kind: HitOrMissProofKind,
+}
+
+impl HitOrMissProof {
+    pub fn sunk_ship_length(&self) -> Option<usize> {
+        match &self.kind {
+            HitOrMissProofKind::HitOrMiss => None,
+            HitOrMissProofKind::WithSunk {
+                sunk_ship_length, ..
+            } => *sunk_ship_length,
+        }
+    }
 }
 
-const VERIFICATION_KEY_JSON: &str =
-    include_str!("../../../../../../circuits/build/move/verification_key.json");
+const VERIFICATION_KEY_JSON: &str = verification_key!("move");
+const SUNK_VERIFICATION_KEY_JSON: &str = verification_key!("move_sunk");
+
+fn prior_hits_are_valid(prior_hits: &[Shot], shot: &Shot) -> bool {
+    prior_hits.iter().enumerate().all(|(i, prior_hit)| {
+        prior_hit.x < BOARD_SIZE
+            && prior_hit.y < BOARD_SIZE
+            && prior_hit != shot
+            && !prior_hits[..i].contains(prior_hit)
+    })
+}
+
+/// Checks the zero knowledge proof against the claimed outcome
+pub fn verify_hit_or_miss_proof(hit_or_miss_proof: &HitOrMissProof) -> ExternResult<bool> {
+    match &hit_or_miss_proof.kind {
+        HitOrMissProofKind::HitOrMiss => verify(
+            VERIFICATION_KEY_JSON,
+            hit_or_miss_proof.proof.as_str(),
+            &[
+                if hit_or_miss_proof.hit { "1" } else { "0" },
+                hit_or_miss_proof.deployment_commitment.as_str(),
+                hit_or_miss_proof.shot.x.to_string().as_str(),
+                hit_or_miss_proof.shot.y.to_string().as_str(),
+            ],
+        ),
+        HitOrMissProofKind::WithSunk {
+            prior_hits,
+            sunk_ship_length,
+        } => verify(
+            SUNK_VERIFICATION_KEY_JSON,
+            hit_or_miss_proof.proof.as_str(),
+            &[
+                if hit_or_miss_proof.hit { "1" } else { "0" },
+                sunk_ship_length.unwrap_or(0).to_string().as_str(),
+                hit_or_miss_proof.deployment_commitment.as_str(),
+                hit_or_miss_proof.shot.x.to_string().as_str(),
+                hit_or_miss_proof.shot.y.to_string().as_str(),
+                hits_bitmask(prior_hits).to_string().as_str(),
+            ],
+        ),
+    }
+}
 
 pub fn validate_create_hit_or_miss_proof(
     _action: EntryCreationAction,
     hit_or_miss_proof: HitOrMissProof,
 ) -> ExternResult<ValidateCallbackResult> {
-    match must_get_valid_app_entry_and_author(hit_or_miss_proof.deployment_proof)? {
+    match must_get_valid_app_entry_and_author(hit_or_miss_proof.deployment_proof.clone())? {
         (EntryTypes::ShipDeploymentProof(deployment_proof), _) => {
             if deployment_proof.commitment != hit_or_miss_proof.deployment_commitment {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Deployment commitment doesn't match".into(),
-                ));
+                return BattleshipsError::DeploymentCommitmentMismatch.invalid();
             }
         }
         _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "deployment_proof field is not a ShipDeploymentProof".into(),
-            ))
+            return BattleshipsError::WrongEntryType {
+                field: "deployment_proof".into(),
+                expected: RecordKind::ShipDeploymentProof,
+            }
+            .invalid()
         }
     }
-    if !verify(
-        VERIFICATION_KEY_JSON,
-        hit_or_miss_proof.proof.as_str(),
-        &[
-            if hit_or_miss_proof.hit { "1" } else { "0" },
-            hit_or_miss_proof.deployment_commitment.as_str(),
-            hit_or_miss_proof.shot.x.to_string().as_str(),
-            hit_or_miss_proof.shot.y.to_string().as_str(),
-        ],
-    )? {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Invalid Hit Or Miss Proof".into(),
-        ));
+    if let HitOrMissProofKind::WithSunk {
+        prior_hits,
+        sunk_ship_length,
+    } = &hit_or_miss_proof.kind
+    {
+        if !prior_hits_are_valid(prior_hits, &hit_or_miss_proof.shot) {
+            return BattleshipsError::InvalidPriorHits.invalid();
+        }
+        if let Some(sunk_ship_length) = sunk_ship_length {
+            if !hit_or_miss_proof.hit || !SHIP_LENGTHS.contains(sunk_ship_length) {
+                return BattleshipsError::InvalidSunkShipLength {
+                    length: *sunk_ship_length,
+                }
+                .invalid();
+            }
+        }
+    }
+    if !verify_hit_or_miss_proof(&hit_or_miss_proof)? {
+        return BattleshipsError::InvalidProof {
+            kind: ProofKind::HitOrMiss,
+        }
+        .invalid();
     }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -54,16 +134,18 @@ pub fn validate_update_hit_or_miss_proof(
     _original_action: EntryCreationAction,
     _original_hit_or_miss_proof: HitOrMissProof,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Hit Or Miss Proofs cannot be updated",
-    )))
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::HitOrMissProof,
+    }
+    .invalid()
 }
 pub fn validate_delete_hit_or_miss_proof(
     _action: Delete,
     _original_action: EntryCreationAction,
     _original_hit_or_miss_proof: HitOrMissProof,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Hit Or Miss Proofs cannot be deleted",
-    )))
+    BattleshipsError::DeleteNotAllowed {
+        kind: RecordKind::HitOrMissProof,
+    }
+    .invalid()
 }
@@ -1,5 +1,8 @@
-mod constants;
+pub use battleships_rules::*;
+#[macro_use]
 mod groth16;
+#[cfg(feature = "mock-verifier")]
+pub use groth16::mock_proof;
 pub mod hit_or_miss_proof;
 pub use hit_or_miss_proof::*;
 pub mod ship_deployment_proof;
@@ -10,7 +13,30 @@ pub mod game_invite;
 pub use game_invite::*;
 pub mod game_transcript;
 pub use game_transcript::*;
+pub mod fleet_reveal;
+pub use fleet_reveal::*;
+pub mod game_result;
+pub use game_result::*;
+pub mod link_tags;
+pub use link_tags::*;
+pub mod coin_flip_reveal;
+pub use coin_flip_reveal::*;
+pub mod error;
+pub use error::*;
+pub mod game_message;
+pub use game_message::*;
+pub mod profile;
+pub use profile::*;
+pub mod membrane_proof;
+pub use membrane_proof::*;
+pub mod game_export;
+pub use game_export::*;
+pub mod tournament;
+pub use tournament::*;
+pub mod game_match;
+pub use game_match::*;
 mod helpers;
+mod poseidon;
 use hdi::prelude::*;
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
@@ -23,6 +49,14 @@ pub enum EntryTypes {
     ShipDeploymentProof(ShipDeploymentProof),
     GameTranscript(GameTranscript),
     HitOrMissProof(HitOrMissProof),
+    FleetReveal(FleetReveal),
+    GameResult(GameResult),
+    CoinFlipReveal(CoinFlipReveal),
+    GameMessage(GameMessage),
+    Profile(Profile),
+    Tournament(Tournament),
+    TournamentRound(TournamentRound),
+    Match(Match),
 }
 #[derive(Serialize, Deserialize)]
 #[hdk_link_types]
@@ -32,16 +66,50 @@ pub enum LinkTypes {
     DeploymentProofs,
     GameTranscript,
     GameTranscriptUpdates,
+    FleetReveals,
+    LadderResults,
+    Spectators,
+    CoinFlipReveals,
+    GameMessages,
+    AgentToProfile,
+    NicknamePrefix,
+    Tournaments,
+    TournamentEntrants,
+    TournamentRounds,
+    TournamentGames,
+    MatchGames,
+    PlayerMatches,
 }
 #[hdk_extern]
-pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+pub fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+    // Lets an agent without a valid membrane proof find out before joining the network
+    let properties = DnaProperties::from_serialized_bytes(data.dna_info.properties)?;
+    if let Some(error) = check_mock_verifier(&properties) {
+        return error.invalid();
+    }
+    match check_membrane_proof(
+        &properties,
+        &data.dna_info.hash,
+        &data.agent_key,
+        &data.membrane_proof,
+    )? {
+        Some(error) => error.invalid(),
+        None => Ok(ValidateCallbackResult::Valid),
+    }
 }
 pub fn validate_agent_joining(
-    _agent_pub_key: AgentPubKey,
-    _membrane_proof: &Option<MembraneProof>,
+    agent_pub_key: AgentPubKey,
+    membrane_proof: &Option<MembraneProof>,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+    let dna_info = dna_info()?;
+    let properties = DnaProperties::from_serialized_bytes(dna_info.properties)?;
+    if let Some(error) = check_mock_verifier(&properties) {
+        return error.invalid();
+    }
+    match check_membrane_proof(&properties, &dna_info.hash, &agent_pub_key, membrane_proof)? {
+        Some(error) => error.invalid(),
+        None => Ok(ValidateCallbackResult::Valid),
+    }
 }
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
@@ -69,6 +137,32 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryCreationAction::Create(action),
                     hit_or_miss_proof,
                 ),
+                EntryTypes::FleetReveal(fleet_reveal) => {
+                    validate_create_fleet_reveal(EntryCreationAction::Create(action), fleet_reveal)
+                }
+                EntryTypes::GameResult(game_result) => {
+                    validate_create_game_result(EntryCreationAction::Create(action), game_result)
+                }
+                EntryTypes::CoinFlipReveal(coin_flip_reveal) => validate_create_coin_flip_reveal(
+                    EntryCreationAction::Create(action),
+                    coin_flip_reveal,
+                ),
+                EntryTypes::GameMessage(game_message) => {
+                    validate_create_game_message(EntryCreationAction::Create(action), game_message)
+                }
+                EntryTypes::Profile(profile) => {
+                    validate_create_profile(EntryCreationAction::Create(action), profile)
+                }
+                EntryTypes::Tournament(tournament) => {
+                    validate_create_tournament(EntryCreationAction::Create(action), tournament)
+                }
+                EntryTypes::TournamentRound(tournament_round) => validate_create_tournament_round(
+                    EntryCreationAction::Create(action),
+                    tournament_round,
+                ),
+                EntryTypes::Match(game_match) => {
+                    validate_create_match(EntryCreationAction::Create(action), game_match)
+                }
             },
             OpEntry::UpdateEntry {
                 app_entry, action, ..
@@ -94,6 +188,32 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryCreationAction::Update(action),
                     hit_or_miss_proof,
                 ),
+                EntryTypes::FleetReveal(fleet_reveal) => {
+                    validate_create_fleet_reveal(EntryCreationAction::Update(action), fleet_reveal)
+                }
+                EntryTypes::GameResult(game_result) => {
+                    validate_create_game_result(EntryCreationAction::Update(action), game_result)
+                }
+                EntryTypes::CoinFlipReveal(coin_flip_reveal) => validate_create_coin_flip_reveal(
+                    EntryCreationAction::Update(action),
+                    coin_flip_reveal,
+                ),
+                EntryTypes::GameMessage(game_message) => {
+                    validate_create_game_message(EntryCreationAction::Update(action), game_message)
+                }
+                EntryTypes::Profile(profile) => {
+                    validate_create_profile(EntryCreationAction::Update(action), profile)
+                }
+                EntryTypes::Tournament(tournament) => {
+                    validate_create_tournament(EntryCreationAction::Update(action), tournament)
+                }
+                EntryTypes::TournamentRound(tournament_round) => validate_create_tournament_round(
+                    EntryCreationAction::Update(action),
+                    tournament_round,
+                ),
+                EntryTypes::Match(game_match) => {
+                    validate_create_match(EntryCreationAction::Update(action), game_match)
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -149,16 +269,75 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     original_action,
                     original_hit_or_miss_proof,
                 ),
-                _ => Ok(ValidateCallbackResult::Invalid(
-                    "Original and updated entry types must be the same".to_string(),
-                )),
+                (
+                    EntryTypes::FleetReveal(fleet_reveal),
+                    EntryTypes::FleetReveal(original_fleet_reveal),
+                ) => validate_update_fleet_reveal(
+                    action,
+                    fleet_reveal,
+                    original_action,
+                    original_fleet_reveal,
+                ),
+                (
+                    EntryTypes::GameResult(game_result),
+                    EntryTypes::GameResult(original_game_result),
+                ) => validate_update_game_result(
+                    action,
+                    game_result,
+                    original_action,
+                    original_game_result,
+                ),
+                (
+                    EntryTypes::CoinFlipReveal(coin_flip_reveal),
+                    EntryTypes::CoinFlipReveal(original_coin_flip_reveal),
+                ) => validate_update_coin_flip_reveal(
+                    action,
+                    coin_flip_reveal,
+                    original_action,
+                    original_coin_flip_reveal,
+                ),
+                (
+                    EntryTypes::GameMessage(game_message),
+                    EntryTypes::GameMessage(original_game_message),
+                ) => validate_update_game_message(
+                    action,
+                    game_message,
+                    original_action,
+                    original_game_message,
+                ),
+                (EntryTypes::Profile(profile), EntryTypes::Profile(original_profile)) => {
+                    validate_update_profile(action, profile, original_action, original_profile)
+                }
+                (
+                    EntryTypes::Tournament(tournament),
+                    EntryTypes::Tournament(original_tournament),
+                ) => validate_update_tournament(
+                    action,
+                    tournament,
+                    original_action,
+                    original_tournament,
+                ),
+                (
+                    EntryTypes::TournamentRound(tournament_round),
+                    EntryTypes::TournamentRound(original_tournament_round),
+                ) => validate_update_tournament_round(
+                    action,
+                    tournament_round,
+                    original_action,
+                    original_tournament_round,
+                ),
+                (EntryTypes::Match(game_match), EntryTypes::Match(original_game_match)) => {
+                    validate_update_match(action, game_match, original_action, original_game_match)
+                }
+                _ => BattleshipsError::EntryTypeChanged.invalid(),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterDelete(delete_entry) => match delete_entry {
-            OpDelete::Entry { .. } => Ok(ValidateCallbackResult::Invalid(String::from(
-                "App entries cannot be deleted",
-            ))),
+            OpDelete::Entry { .. } => BattleshipsError::DeleteNotAllowed {
+                kind: RecordKind::AppEntry,
+            }
+            .invalid(),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterCreateLink {
@@ -186,10 +365,50 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 target_address,
                 tag,
             ),
+            LinkTypes::FleetReveals => {
+                validate_create_link_fleet_reveals(action, base_address, target_address, tag)
+            }
+            LinkTypes::LadderResults => {
+                validate_create_link_ladder_results(action, base_address, target_address, tag)
+            }
+            LinkTypes::Spectators => {
+                validate_create_link_spectators(action, base_address, target_address, tag)
+            }
+            LinkTypes::CoinFlipReveals => {
+                validate_create_link_coin_flip_reveals(action, base_address, target_address, tag)
+            }
+            LinkTypes::GameMessages => {
+                validate_create_link_game_messages(action, base_address, target_address, tag)
+            }
+            LinkTypes::AgentToProfile => {
+                validate_create_link_agent_to_profile(action, base_address, target_address, tag)
+            }
+            LinkTypes::NicknamePrefix => {
+                validate_create_link_nickname_prefix(action, base_address, target_address, tag)
+            }
+            LinkTypes::Tournaments => {
+                validate_create_link_tournaments(action, base_address, target_address, tag)
+            }
+            LinkTypes::TournamentEntrants => {
+                validate_create_link_tournament_entrants(action, base_address, target_address, tag)
+            }
+            LinkTypes::TournamentRounds => {
+                validate_create_link_tournament_rounds(action, base_address, target_address, tag)
+            }
+            LinkTypes::TournamentGames => {
+                validate_create_link_tournament_games(action, base_address, target_address, tag)
+            }
+            LinkTypes::MatchGames => {
+                validate_create_link_match_games(action, base_address, target_address, tag)
+            }
+            LinkTypes::PlayerMatches => {
+                validate_create_link_player_matches(action, base_address, target_address, tag)
+            }
         },
-        FlatOp::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Invalid(String::from(
-            "Links cannot be deleted",
-        ))),
+        FlatOp::RegisterDeleteLink { .. } => BattleshipsError::DeleteNotAllowed {
+            kind: RecordKind::Link,
+        }
+        .invalid(),
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::GameInvite(game_invite) => {
@@ -213,6 +432,32 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     EntryCreationAction::Create(action),
                     hit_or_miss_proof,
                 ),
+                EntryTypes::FleetReveal(fleet_reveal) => {
+                    validate_create_fleet_reveal(EntryCreationAction::Create(action), fleet_reveal)
+                }
+                EntryTypes::GameResult(game_result) => {
+                    validate_create_game_result(EntryCreationAction::Create(action), game_result)
+                }
+                EntryTypes::CoinFlipReveal(coin_flip_reveal) => validate_create_coin_flip_reveal(
+                    EntryCreationAction::Create(action),
+                    coin_flip_reveal,
+                ),
+                EntryTypes::GameMessage(game_message) => {
+                    validate_create_game_message(EntryCreationAction::Create(action), game_message)
+                }
+                EntryTypes::Profile(profile) => {
+                    validate_create_profile(EntryCreationAction::Create(action), profile)
+                }
+                EntryTypes::Tournament(tournament) => {
+                    validate_create_tournament(EntryCreationAction::Create(action), tournament)
+                }
+                EntryTypes::TournamentRound(tournament_round) => validate_create_tournament_round(
+                    EntryCreationAction::Create(action),
+                    tournament_round,
+                ),
+                EntryTypes::Match(game_match) => {
+                    validate_create_match(EntryCreationAction::Create(action), game_match)
+                }
             },
             OpRecord::UpdateEntry {
                 original_action_hash,
@@ -226,10 +471,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     Action::Create(create) => EntryCreationAction::Create(create),
                     Action::Update(update) => EntryCreationAction::Update(update),
                     _ => {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Original action for an update must be a Create or Update action"
-                                .to_string(),
-                        ));
+                        return BattleshipsError::InvalidOriginalAction.invalid();
                     }
                 };
                 match app_entry {
@@ -246,12 +488,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             let original_game_invite = match original_game_invite {
                                 Some(game_invite) => game_invite,
                                 None => {
-                                    return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
+                                    return BattleshipsError::EntryTypeChanged.invalid();
                                 }
                             };
                             validate_update_game_invite(
@@ -277,12 +514,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             let original_ship_deployment = match original_ship_deployment {
                                 Some(ship_deployment) => ship_deployment,
                                 None => {
-                                    return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
+                                    return BattleshipsError::EntryTypeChanged.invalid();
                                 }
                             };
                             validate_update_ship_deployment(
@@ -310,12 +542,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                                 match original_ship_deployment_proof {
                                     Some(ship_deployment_proof) => ship_deployment_proof,
                                     None => {
-                                        return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
+                                        return BattleshipsError::EntryTypeChanged.invalid();
                                     }
                                 };
                             validate_update_ship_deployment_proof(
@@ -336,12 +563,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         let original_game_transcript = match original_game_transcript {
                             Some(game_transcript) => game_transcript,
                             None => {
-                                return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
+                                return BattleshipsError::EntryTypeChanged.invalid();
                             }
                         };
                         validate_update_game_transcript(
@@ -365,12 +587,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             let original_hit_or_miss_proof = match original_hit_or_miss_proof {
                                 Some(hit_or_miss_proof) => hit_or_miss_proof,
                                 None => {
-                                    return Ok(
-                                            ValidateCallbackResult::Invalid(
-                                                "The updated entry type must be the same as the original entry type"
-                                                    .to_string(),
-                                            ),
-                                        );
+                                    return BattleshipsError::EntryTypeChanged.invalid();
                                 }
                             };
                             validate_update_hit_or_miss_proof(
@@ -383,6 +600,215 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             Ok(result)
                         }
                     }
+                    EntryTypes::FleetReveal(fleet_reveal) => {
+                        let result = validate_create_fleet_reveal(
+                            EntryCreationAction::Update(action.clone()),
+                            fleet_reveal.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_fleet_reveal: Option<FleetReveal> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_fleet_reveal = match original_fleet_reveal {
+                                Some(fleet_reveal) => fleet_reveal,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_fleet_reveal(
+                                action,
+                                fleet_reveal,
+                                original_action,
+                                original_fleet_reveal,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::GameResult(game_result) => {
+                        let result = validate_create_game_result(
+                            EntryCreationAction::Update(action.clone()),
+                            game_result.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_game_result: Option<GameResult> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_game_result = match original_game_result {
+                                Some(game_result) => game_result,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_game_result(
+                                action,
+                                game_result,
+                                original_action,
+                                original_game_result,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::CoinFlipReveal(coin_flip_reveal) => {
+                        let result = validate_create_coin_flip_reveal(
+                            EntryCreationAction::Update(action.clone()),
+                            coin_flip_reveal.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_coin_flip_reveal: Option<CoinFlipReveal> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_coin_flip_reveal = match original_coin_flip_reveal {
+                                Some(coin_flip_reveal) => coin_flip_reveal,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_coin_flip_reveal(
+                                action,
+                                coin_flip_reveal,
+                                original_action,
+                                original_coin_flip_reveal,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::GameMessage(game_message) => {
+                        let result = validate_create_game_message(
+                            EntryCreationAction::Update(action.clone()),
+                            game_message.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_game_message: Option<GameMessage> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_game_message = match original_game_message {
+                                Some(game_message) => game_message,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_game_message(
+                                action,
+                                game_message,
+                                original_action,
+                                original_game_message,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::Profile(profile) => {
+                        let result = validate_create_profile(
+                            EntryCreationAction::Update(action.clone()),
+                            profile.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_profile: Option<Profile> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_profile = match original_profile {
+                                Some(profile) => profile,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_profile(
+                                action,
+                                profile,
+                                original_action,
+                                original_profile,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::Tournament(tournament) => {
+                        let result = validate_create_tournament(
+                            EntryCreationAction::Update(action.clone()),
+                            tournament.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_tournament: Option<Tournament> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_tournament = match original_tournament {
+                                Some(tournament) => tournament,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_tournament(
+                                action,
+                                tournament,
+                                original_action,
+                                original_tournament,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::TournamentRound(tournament_round) => {
+                        let result = validate_create_tournament_round(
+                            EntryCreationAction::Update(action.clone()),
+                            tournament_round.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_tournament_round: Option<TournamentRound> =
+                                original_record
+                                    .entry()
+                                    .to_app_option()
+                                    .map_err(|e| wasm_error!(e))?;
+                            let original_tournament_round = match original_tournament_round {
+                                Some(tournament_round) => tournament_round,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_tournament_round(
+                                action,
+                                tournament_round,
+                                original_action,
+                                original_tournament_round,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
+                    EntryTypes::Match(game_match) => {
+                        let result = validate_create_match(
+                            EntryCreationAction::Update(action.clone()),
+                            game_match.clone(),
+                        )?;
+                        if let ValidateCallbackResult::Valid = result {
+                            let original_game_match: Option<Match> = original_record
+                                .entry()
+                                .to_app_option()
+                                .map_err(|e| wasm_error!(e))?;
+                            let original_game_match = match original_game_match {
+                                Some(game_match) => game_match,
+                                None => {
+                                    return BattleshipsError::EntryTypeChanged.invalid();
+                                }
+                            };
+                            validate_update_match(
+                                action,
+                                game_match,
+                                original_action,
+                                original_game_match,
+                            )
+                        } else {
+                            Ok(result)
+                        }
+                    }
                 }
             }
             OpRecord::DeleteEntry {
@@ -395,10 +821,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     Action::Create(create) => EntryCreationAction::Create(create),
                     Action::Update(update) => EntryCreationAction::Update(update),
                     _ => {
-                        return Ok(ValidateCallbackResult::Invalid(
-                            "Original action for a delete must be a Create or Update action"
-                                .to_string(),
-                        ));
+                        return BattleshipsError::InvalidOriginalAction.invalid();
                     }
                 };
                 let app_entry_type = match original_action.entry_type() {
@@ -411,12 +834,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     Some(entry) => entry,
                     None => {
                         if original_action.entry_type().visibility().is_public() {
-                            return Ok(
-                                    ValidateCallbackResult::Invalid(
-                                        "Original record for a delete of a public entry must contain an entry"
-                                            .to_string(),
-                                    ),
-                                );
+                            return BattleshipsError::OriginalEntryMissing.invalid();
                         } else {
                             return Ok(ValidateCallbackResult::Valid);
                         }
@@ -429,17 +847,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 )? {
                     Some(app_entry) => app_entry,
                     None => {
-                        return Ok(
-                                ValidateCallbackResult::Invalid(
-                                    "Original app entry must be one of the defined entry types for this zome"
-                                        .to_string(),
-                                ),
-                            );
+                        return BattleshipsError::UnknownEntryType.invalid();
                     }
                 };
-                Ok(ValidateCallbackResult::Invalid(String::from(
-                    "App entries cannot be deleted",
-                )))
+                BattleshipsError::DeleteNotAllowed {
+                    kind: RecordKind::AppEntry,
+                }
+                .invalid()
             }
             OpRecord::CreateLink {
                 base_address,
@@ -466,10 +880,59 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                     target_address,
                     tag,
                 ),
+                LinkTypes::FleetReveals => {
+                    validate_create_link_fleet_reveals(action, base_address, target_address, tag)
+                }
+                LinkTypes::LadderResults => {
+                    validate_create_link_ladder_results(action, base_address, target_address, tag)
+                }
+                LinkTypes::Spectators => {
+                    validate_create_link_spectators(action, base_address, target_address, tag)
+                }
+                LinkTypes::CoinFlipReveals => validate_create_link_coin_flip_reveals(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::GameMessages => {
+                    validate_create_link_game_messages(action, base_address, target_address, tag)
+                }
+                LinkTypes::AgentToProfile => {
+                    validate_create_link_agent_to_profile(action, base_address, target_address, tag)
+                }
+                LinkTypes::NicknamePrefix => {
+                    validate_create_link_nickname_prefix(action, base_address, target_address, tag)
+                }
+                LinkTypes::Tournaments => {
+                    validate_create_link_tournaments(action, base_address, target_address, tag)
+                }
+                LinkTypes::TournamentEntrants => validate_create_link_tournament_entrants(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::TournamentRounds => validate_create_link_tournament_rounds(
+                    action,
+                    base_address,
+                    target_address,
+                    tag,
+                ),
+                LinkTypes::TournamentGames => {
+                    validate_create_link_tournament_games(action, base_address, target_address, tag)
+                }
+                LinkTypes::MatchGames => {
+                    validate_create_link_match_games(action, base_address, target_address, tag)
+                }
+                LinkTypes::PlayerMatches => {
+                    validate_create_link_player_matches(action, base_address, target_address, tag)
+                }
             },
-            OpRecord::DeleteLink { .. } => Ok(ValidateCallbackResult::Invalid(
-                "Links cannot be deleted".to_string(),
-            )),
+            OpRecord::DeleteLink { .. } => BattleshipsError::DeleteNotAllowed {
+                kind: RecordKind::Link,
+            }
+            .invalid(),
             OpRecord::CreatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
             OpRecord::UpdatePrivateEntry { .. } => Ok(ValidateCallbackResult::Valid),
             OpRecord::CreateCapClaim { .. } => Ok(ValidateCallbackResult::Valid),
@@ -486,18 +949,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             OpActivity::CreateAgent { agent, action } => {
                 let previous_action = must_get_action(action.prev_action)?;
                 match previous_action.action() {
-                        Action::AgentValidationPkg(
-                            AgentValidationPkg { membrane_proof, .. },
-                        ) => validate_agent_joining(agent, membrane_proof),
-                        _ => {
-                            Ok(
-                                ValidateCallbackResult::Invalid(
-                                    "The previous action for a `CreateAgent` action must be an `AgentValidationPkg`"
-                                        .to_string(),
-                                ),
-                            )
-                        }
+                    Action::AgentValidationPkg(AgentValidationPkg { membrane_proof, .. }) => {
+                        validate_agent_joining(agent, membrane_proof)
                     }
+                    _ => BattleshipsError::InvalidAgentValidationPkg.invalid(),
+                }
             }
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -0,0 +1,56 @@
+use hdi::prelude::*;
+
+use crate::GameRules;
+
+/// Tag on the Invites links from each player to a game invite, so invite lists can be
+/// rendered without fetching the invites themselves
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InviteLinkTag {
+    pub opponent: AgentPubKey,
+    // Whether the agent the link is based on is the home player
+    pub is_home_player: bool,
+    pub rules: GameRules,
+    pub created_at: Timestamp,
+}
+
+/// Tag on the DeploymentProofs links from a game invite to each player's deployment proof
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeploymentProofLinkTag {
+    pub deployer: AgentPubKey,
+    pub deployed_at: Timestamp,
+}
+
+/// Tag on the MatchGames links from a match to each of its game invites
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchGameLinkTag {
+    // Counting from zero; the match's home player is home in the even numbered games
+    pub game_number: usize,
+    // None only for the first game
+    #[serde(default)]
+    pub previous_game: Option<PreviousMatchGame>,
+    // Wins of the match's home and away players in the games before this one
+    #[serde(default)]
+    pub home_player_wins: usize,
+    #[serde(default)]
+    pub away_player_wins: usize,
+}
+
+/// The game before the one being linked, which has to be finished first
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PreviousMatchGame {
+    // Its MatchGames link
+    pub link: ActionHash,
+    // Final revision of its game transcript
+    pub game_transcript: ActionHash,
+}
+
+pub fn encode_link_tag<T: Serialize + std::fmt::Debug>(content: &T) -> ExternResult<LinkTag> {
+    let bytes = ExternIO::encode(content).map_err(|e| wasm_error!(e))?;
+    Ok(LinkTag::new(bytes.0))
+}
+
+pub fn decode_link_tag<T: serde::de::DeserializeOwned + std::fmt::Debug>(
+    tag: &LinkTag,
+) -> ExternResult<T> {
+    ExternIO(tag.0.clone()).decode().map_err(|e| wasm_error!(e))
+}
@@ -0,0 +1,94 @@
+use hdi::prelude::*;
+
+use crate::BattleshipsError;
+
+/// Properties set in dna.yaml. Without a progenitor the network is open to everyone.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, Default)]
+pub struct DnaProperties {
+    #[serde(default)]
+    pub progenitor: Option<AgentPubKeyB64>,
+    // Set only in DNAs for tests, whose zomes are built with the mock-verifier feature
+    #[serde(default)]
+    pub mock_verifier: bool,
+}
+
+impl DnaProperties {
+    pub fn from_serialized_bytes(properties: SerializedBytes) -> ExternResult<Self> {
+        let properties: Option<DnaProperties> = ExternIO(properties.bytes().clone())
+            .decode()
+            .map_err(|_| BattleshipsError::InvalidDnaProperties)?;
+        Ok(properties.unwrap_or_default())
+    }
+}
+
+pub fn dna_properties() -> ExternResult<DnaProperties> {
+    DnaProperties::from_serialized_bytes(dna_info()?.properties)
+}
+
+/// Zomes built with the mock verifier only run in DNAs that declare it, and the other way round,
+/// so a DNA accepting forged proofs can never pass for one that checks them
+pub fn check_mock_verifier(properties: &DnaProperties) -> Option<BattleshipsError> {
+    let mock_verifier = cfg!(feature = "mock-verifier");
+    (properties.mock_verifier != mock_verifier)
+        .then_some(BattleshipsError::MockVerifierMismatch { mock_verifier })
+}
+
+/// What the progenitor signs to let an agent join. A progenitor can run several networks from
+/// the same DNA with different network seeds, so the proof names the one it admits the agent to.
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct MembraneProofPayload {
+    pub agent: AgentPubKey,
+    pub dna_hash: DnaHash,
+}
+
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone, PartialEq)]
+pub struct SignedMembraneProof {
+    pub payload: MembraneProofPayload,
+    pub signature: Signature,
+}
+
+impl SignedMembraneProof {
+    pub fn decode(membrane_proof: &MembraneProof) -> ExternResult<Self> {
+        SignedMembraneProof::try_from((**membrane_proof).clone())
+            .map_err(|_| BattleshipsError::MalformedMembraneProof.into())
+    }
+}
+
+/// Checks that the agent holds a membrane proof for this network signed by the progenitor, if
+/// there is one. The progenitor itself joins without a proof.
+pub fn check_membrane_proof(
+    properties: &DnaProperties,
+    dna_hash: &DnaHash,
+    agent: &AgentPubKey,
+    membrane_proof: &Option<MembraneProof>,
+) -> ExternResult<Option<BattleshipsError>> {
+    let progenitor = match &properties.progenitor {
+        Some(progenitor) => AgentPubKey::from(progenitor.clone()),
+        None => return Ok(None),
+    };
+    if agent == &progenitor {
+        return Ok(None);
+    }
+    let membrane_proof = match membrane_proof {
+        Some(membrane_proof) => membrane_proof,
+        None => return Ok(Some(BattleshipsError::MissingMembraneProof)),
+    };
+    let signed_membrane_proof = match SignedMembraneProof::decode(membrane_proof) {
+        Ok(signed_membrane_proof) => signed_membrane_proof,
+        Err(_) => return Ok(Some(BattleshipsError::MalformedMembraneProof)),
+    };
+    if &signed_membrane_proof.payload.agent != agent {
+        return Ok(Some(BattleshipsError::MembraneProofForWrongAgent));
+    }
+    if &signed_membrane_proof.payload.dna_hash != dna_hash {
+        return Ok(Some(BattleshipsError::MembraneProofForWrongNetwork));
+    }
+    if !verify_signature(
+        progenitor,
+        signed_membrane_proof.signature,
+        signed_membrane_proof.payload,
+    )? {
+        return Ok(Some(BattleshipsError::InvalidMembraneProofSignature));
+    }
+    Ok(None)
+}
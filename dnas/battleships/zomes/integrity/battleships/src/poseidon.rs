@@ -0,0 +1,28 @@
+use std::str::FromStr;
+
+use ark_bn254::Fr;
+use light_poseidon::{Poseidon, PoseidonHasher};
+
+use crate::Ship;
+
+// Mirrors the commitment computed by create.circom & move.circom:
+// Poseidon(nonce, x + 10y + 100d for each ship) where d is 1 for horizontal ships
+pub fn deployment_commitment(ships: &[Ship], salt: &str) -> Option<Fr> {
+    let mut inputs = vec![Fr::from_str(salt).ok()?];
+    for ship in ships {
+        let encoded = ship.x + ship.y * 10 + if ship.horizontal { 100 } else { 0 };
+        inputs.push(Fr::from(encoded as u64));
+    }
+    let mut poseidon = Poseidon::<Fr>::new_circom(inputs.len()).ok()?;
+    poseidon.hash(&inputs).ok()
+}
+
+pub fn commitment_matches(ships: &[Ship], salt: &str, commitment: &str) -> bool {
+    match (
+        deployment_commitment(ships, salt),
+        Fr::from_str(commitment).ok(),
+    ) {
+        (Some(computed), Some(expected)) => computed == expected,
+        _ => false,
+    }
+}
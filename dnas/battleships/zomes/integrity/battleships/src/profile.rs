@@ -0,0 +1,215 @@
+use hdi::{
+    hash_path::path::{Component, Path},
+    prelude::*,
+};
+
+use crate::{
+    decode_link_tag, encode_link_tag,
+    helpers::{is_create_of, must_get_valid_app_entry_and_author},
+    BattleshipsError, EntryTypes, LinkTypes, RecordKind, UnitEntryTypes,
+};
+
+pub const MIN_NICKNAME_LENGTH: usize = 3;
+pub const MAX_NICKNAME_LENGTH: usize = 24;
+pub const MAX_BIO_LENGTH: usize = 500;
+// Nicknames are indexed under a path per lowercased prefix of this many characters, so
+// searches must be at least this long
+pub const NICKNAME_PREFIX_LENGTH: usize = 3;
+pub const NICKNAMES_PATH: &str = "nicknames";
+
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Profile {
+    pub nickname: String,
+    // URL or data URI of the avatar image
+    pub avatar: Option<String>,
+    pub bio: String,
+}
+
+pub fn nickname_prefix(nickname: &str) -> String {
+    nickname
+        .to_lowercase()
+        .chars()
+        .take(NICKNAME_PREFIX_LENGTH)
+        .collect()
+}
+
+pub fn nickname_prefix_path(nickname: &str) -> Path {
+    Path::from(vec![
+        Component::from(NICKNAMES_PATH),
+        Component::from(nickname_prefix(nickname)),
+    ])
+}
+
+/// Tag on the NicknamePrefix links: the raw lowercased nickname, so that get_links can filter on
+/// a tag prefix, then a NUL and the profile action it was linked for
+pub fn nickname_link_tag(nickname: &str, profile_hash: &ActionHash) -> ExternResult<LinkTag> {
+    let mut tag = nickname_search_tag(nickname).0;
+    tag.push(0);
+    tag.extend(encode_link_tag(profile_hash)?.0);
+    Ok(LinkTag::new(tag))
+}
+
+/// Tag prefix that finds the NicknamePrefix links of nicknames starting with `nickname_prefix`
+pub fn nickname_search_tag(nickname_prefix: &str) -> LinkTag {
+    LinkTag::new(nickname_prefix.to_lowercase().into_bytes())
+}
+
+/// The nickname and profile action of a NicknamePrefix link tag
+pub fn decode_nickname_link_tag(tag: &LinkTag) -> Option<(String, ActionHash)> {
+    let separator = tag.0.iter().position(|byte| *byte == 0)?;
+    let nickname = String::from_utf8(tag.0[..separator].to_vec()).ok()?;
+    let profile_hash = decode_link_tag(&LinkTag::new(&tag.0[separator + 1..])).ok()?;
+    Some((nickname, profile_hash))
+}
+
+fn validate_profile(profile: &Profile) -> Option<BattleshipsError> {
+    let length = profile.nickname.chars().count();
+    if !(MIN_NICKNAME_LENGTH..=MAX_NICKNAME_LENGTH).contains(&length) {
+        return Some(BattleshipsError::InvalidNicknameLength {
+            min: MIN_NICKNAME_LENGTH,
+            max: MAX_NICKNAME_LENGTH,
+            actual: length,
+        });
+    }
+    if profile.nickname.trim() != profile.nickname || profile.nickname.chars().any(char::is_control)
+    {
+        return Some(BattleshipsError::InvalidNickname);
+    }
+    let bio_length = profile.bio.chars().count();
+    if bio_length > MAX_BIO_LENGTH {
+        return Some(BattleshipsError::BioTooLong {
+            max: MAX_BIO_LENGTH,
+            actual: bio_length,
+        });
+    }
+    None
+}
+
+pub fn validate_create_profile(
+    action: EntryCreationAction,
+    profile: Profile,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Some(error) = validate_profile(&profile) {
+        return error.invalid();
+    }
+    if let EntryCreationAction::Create(_) = action {
+        let activity = must_get_agent_activity(
+            action.author().clone(),
+            ChainFilter::new(action.prev_action().clone()),
+        )?;
+        for registered in activity {
+            if is_create_of(&registered.action.hashed.content, UnitEntryTypes::Profile)? {
+                return BattleshipsError::ProfileAlreadyExists.invalid();
+            }
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_profile(
+    action: Update,
+    profile: Profile,
+    original_action: EntryCreationAction,
+    _original_profile: Profile,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Some(error) = validate_profile(&profile) {
+        return error.invalid();
+    }
+    if &action.author != original_action.author() {
+        return BattleshipsError::NotProfileOwner.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_agent_to_profile(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address != AnyLinkableHash::from(action.author.clone()) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::AgentToProfile,
+        }
+        .invalid();
+    }
+    let record = must_get_valid_record(ActionHash::from(target_address))?;
+    let _profile: crate::Profile = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(BattleshipsError::EntryNotDeserializable)?;
+    if record.action().author() != &action.author {
+        return BattleshipsError::NotProfileOwner.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_nickname_prefix(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let (nickname, profile_hash) = match decode_nickname_link_tag(&tag) {
+        Some((nickname, profile_hash)) if nickname == nickname.to_lowercase() => {
+            (nickname, profile_hash)
+        }
+        _ => {
+            return BattleshipsError::InvalidLinkTag {
+                link_type: LinkTypes::NicknamePrefix,
+            }
+            .invalid()
+        }
+    };
+    if base_address != AnyLinkableHash::from(nickname_prefix_path(&nickname).path_entry_hash()?) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::NicknamePrefix,
+        }
+        .invalid();
+    }
+    if target_address != AnyLinkableHash::from(action.author.clone()) {
+        return BattleshipsError::LinkTargetMismatch {
+            link_type: LinkTypes::NicknamePrefix,
+        }
+        .invalid();
+    }
+    // Only the nickname of one of the author's own profiles can be indexed
+    let profile = match must_get_valid_app_entry_and_author(profile_hash)? {
+        (EntryTypes::Profile(profile), author) => {
+            if author != action.author {
+                return BattleshipsError::NotProfileOwner.invalid();
+            }
+            profile
+        }
+        _ => {
+            return BattleshipsError::WrongEntryType {
+                field: "profile".into(),
+                expected: RecordKind::Profile,
+            }
+            .invalid()
+        }
+    };
+    if profile.nickname.to_lowercase() != nickname {
+        return BattleshipsError::NicknameMismatch.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nickname_link_tags_hold_the_profile_they_index() {
+        let profile_hash = ActionHash::from_raw_36(vec![0; 36]);
+        let tag = nickname_link_tag("Admiral", &profile_hash).unwrap();
+        assert!(tag.0.starts_with(&nickname_search_tag("ADM").0));
+        assert_eq!(
+            decode_nickname_link_tag(&tag),
+            Some(("admiral".to_string(), profile_hash))
+        );
+        assert_eq!(
+            decode_nickname_link_tag(&nickname_search_tag("admiral")),
+            None
+        );
+    }
+}
@@ -1,44 +1,24 @@
 use hdi::prelude::*;
-#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
-pub struct Ship {
-    pub x: usize,
-    pub y: usize,
-    pub horizontal: bool,
-}
+
+use crate::{validate_placement, BattleshipsError, RecordKind, Ship};
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct ShipDeployment {
     pub invite: ActionHash,
     pub ships: Vec<Ship>,
     pub salt: String,
+    // Kept private until revealed in a CoinFlipReveal
+    #[serde(default)]
+    pub coin_secret: Vec<u8>,
 }
 pub fn validate_create_ship_deployment(
     _action: EntryCreationAction,
     ship_deployment: ShipDeployment,
 ) -> ExternResult<ValidateCallbackResult> {
-    if ship_deployment.ships.len() != 5 {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Deployment must have 5 ships".into(),
-        ));
-    }
-    let mut occupied_squares = [[false; 10]; 10];
-    let ship_lengths: [usize; 5] = [5, 4, 3, 3, 2];
-    for (ship, ship_length) in ship_deployment.ships.iter().zip(ship_lengths) {
-        for i in 0..ship_length {
-            let (x, y) = if ship.horizontal {
-                (ship.x + i, ship.y)
-            } else {
-                (ship.x, ship.y + i)
-            };
-            if occupied_squares[x][y] {
-                return Ok(ValidateCallbackResult::Invalid(
-                    "Ship deployment collision".into(),
-                ));
-            }
-            occupied_squares[x][y] = true;
-        }
+    match validate_placement(&ship_deployment.ships) {
+        Ok(()) => Ok(ValidateCallbackResult::Valid),
+        Err(placement_error) => BattleshipsError::from(placement_error).invalid(),
     }
-    Ok(ValidateCallbackResult::Valid)
 }
 pub fn validate_update_ship_deployment(
     _action: Update,
@@ -46,7 +26,8 @@ pub fn validate_update_ship_deployment(
     _original_action: EntryCreationAction,
     _original_ship_deployment: ShipDeployment,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Ship Placements cannot be updated",
-    )))
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::ShipDeployment,
+    }
+    .invalid()
 }
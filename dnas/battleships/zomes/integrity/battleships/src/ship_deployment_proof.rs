@@ -1,6 +1,9 @@
 use hdi::prelude::*;
 
-use crate::groth16::verify;
+use crate::{
+    groth16::verify, helpers::has_earlier_create_of, BattleshipsError, ProofKind, RecordKind,
+    UnitEntryTypes, COIN_SECRET_LENGTH,
+};
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct ShipDeploymentProof {
@@ -8,25 +11,47 @@ pub struct ShipDeploymentProof {
     pub private_entry: ActionHash,
     pub commitment: String,
     pub proof: String,
+    // Hash of the deployer's coin flip secret
+    #[serde(default)]
+    pub coin_commitment: Vec<u8>,
 }
 
-const VERIFICATION_KEY_JSON: &str =
-    include_str!("../../../../../../circuits/build/create/verification_key.json");
+const VERIFICATION_KEY_JSON: &str = verification_key!("create");
 
-pub fn validate_create_ship_deployment_proof(
-    _action: EntryCreationAction,
-    ship_deployment_proof: ShipDeploymentProof,
-) -> ExternResult<ValidateCallbackResult> {
-    if verify(
+/// Checks the zero knowledge proof that the committed fleet is a legal placement
+pub fn verify_ship_deployment_proof(
+    ship_deployment_proof: &ShipDeploymentProof,
+) -> ExternResult<bool> {
+    verify(
         VERIFICATION_KEY_JSON,
         ship_deployment_proof.proof.as_str(),
         &[ship_deployment_proof.commitment.as_str()],
+    )
+}
+
+pub fn validate_create_ship_deployment_proof(
+    action: EntryCreationAction,
+    ship_deployment_proof: ShipDeploymentProof,
+) -> ExternResult<ValidateCallbackResult> {
+    if ship_deployment_proof.coin_commitment.len() != COIN_SECRET_LENGTH {
+        return BattleshipsError::MissingCoinCommitment.invalid();
+    }
+    // A second proof would carry a second coin commitment, letting its author pick whichever
+    // wins the coin flip once the opponent has revealed
+    if has_earlier_create_of(
+        &action,
+        UnitEntryTypes::ShipDeploymentProof,
+        |earlier: &ShipDeploymentProof| earlier.invite == ship_deployment_proof.invite,
     )? {
+        return BattleshipsError::MultipleDeployments.invalid();
+    }
+    if verify_ship_deployment_proof(&ship_deployment_proof)? {
         Ok(ValidateCallbackResult::Valid)
     } else {
-        Ok(ValidateCallbackResult::Invalid(
-            "Invalid ship placement proof".into(),
-        ))
+        BattleshipsError::InvalidProof {
+            kind: ProofKind::ShipDeployment,
+        }
+        .invalid()
     }
 }
 pub fn validate_update_ship_deployment_proof(
@@ -35,16 +60,18 @@ pub fn validate_update_ship_deployment_proof(
     _original_action: EntryCreationAction,
     _original_ship_deployment_proof: ShipDeploymentProof,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Ship Placements Proofs cannot be updated",
-    )))
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::ShipDeploymentProof,
+    }
+    .invalid()
 }
 pub fn validate_delete_ship_deployment_proof(
     _action: Delete,
     _original_action: EntryCreationAction,
     _original_ship_deployment_proof: ShipDeploymentProof,
 ) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Invalid(String::from(
-        "Ship Placements Proofs cannot be deleted",
-    )))
+    BattleshipsError::DeleteNotAllowed {
+        kind: RecordKind::ShipDeploymentProof,
+    }
+    .invalid()
 }
@@ -0,0 +1,443 @@
+use hdi::{hash_path::path::Path, prelude::*};
+
+use crate::{
+    get_game_turn,
+    helpers::{
+        has_earlier_create_link, must_get_create_link_of, must_get_valid_app_entry_and_author,
+    },
+    tournament_pairings, BattleshipsError, EntryTypes, GameInvite, GameRules, GameTurn, LinkTypes,
+    Pairing, RecordKind, TournamentFormat,
+};
+
+pub const MAX_TOURNAMENT_NAME_LENGTH: usize = 64;
+pub const MIN_TOURNAMENT_ENTRANTS: usize = 2;
+// All tournaments are linked from this anchor
+pub const TOURNAMENTS_ANCHOR: &str = "tournaments";
+
+pub fn tournaments_anchor_hash() -> ExternResult<EntryHash> {
+    Path::from(TOURNAMENTS_ANCHOR).path_entry_hash()
+}
+
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Tournament {
+    pub name: String,
+    pub organiser: AgentPubKey,
+    pub format: TournamentFormat,
+    // Every game of the tournament is played under these
+    #[serde(default)]
+    pub rules: GameRules,
+}
+
+/// The winner of one game of a round, checked against the game's final transcript
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TournamentGameResult {
+    pub game_invite: ActionHash,
+    // The round's TournamentGames link to the game, so only the game started for the pairing
+    // can settle it
+    pub game_link: ActionHash,
+    // Final revision of the game transcript
+    pub game_transcript: ActionHash,
+    pub winner: AgentPubKey,
+}
+
+/// Created by the organiser to start each round. The first round closes registration, and
+/// each later one records the results of the round before it, so the pairings always follow
+/// from what is on the DHT.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct TournamentRound {
+    pub tournament: ActionHash,
+    // Counting from zero
+    pub round: usize,
+    pub previous_round: Option<ActionHash>,
+    pub entrants: Vec<AgentPubKey>,
+    // The TournamentEntrants links of the first round's entrants, in the same order. Empty for
+    // later rounds, whose entrants can't change.
+    #[serde(default)]
+    pub registrations: Vec<ActionHash>,
+    // One per game of the previous round, in the order of its pairings, byes skipped
+    pub previous_results: Vec<TournamentGameResult>,
+    // Empty once the tournament is over
+    pub pairings: Vec<Pairing<AgentPubKey>>,
+}
+
+fn must_get_tournament(
+    tournament_hash: ActionHash,
+) -> ExternResult<Result<Tournament, BattleshipsError>> {
+    Ok(
+        match must_get_valid_app_entry_and_author(tournament_hash)? {
+            (EntryTypes::Tournament(tournament), _) => Ok(tournament),
+            _ => Err(BattleshipsError::WrongEntryType {
+                field: "tournament".into(),
+                expected: RecordKind::Tournament,
+            }),
+        },
+    )
+}
+
+pub fn validate_create_tournament(
+    action: EntryCreationAction,
+    tournament: Tournament,
+) -> ExternResult<ValidateCallbackResult> {
+    let length = tournament.name.chars().count();
+    if length == 0 || length > MAX_TOURNAMENT_NAME_LENGTH {
+        return BattleshipsError::InvalidTournamentNameLength {
+            max: MAX_TOURNAMENT_NAME_LENGTH,
+            actual: length,
+        }
+        .invalid();
+    }
+    if &tournament.organiser != action.author() {
+        return BattleshipsError::NotOrganiser.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_tournament(
+    _action: Update,
+    _tournament: Tournament,
+    _original_action: EntryCreationAction,
+    _original_tournament: Tournament,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::Tournament,
+    }
+    .invalid()
+}
+
+/// Checks that every entrant registered for the tournament themselves
+fn validate_registrations(
+    tournament_round: &TournamentRound,
+) -> ExternResult<Option<BattleshipsError>> {
+    if tournament_round.registrations.len() != tournament_round.entrants.len() {
+        return Ok(Some(BattleshipsError::WrongRegistrationCount {
+            expected: tournament_round.entrants.len(),
+            actual: tournament_round.registrations.len(),
+        }));
+    }
+    for (entrant, registration) in tournament_round
+        .entrants
+        .iter()
+        .zip(&tournament_round.registrations)
+    {
+        let registered =
+            must_get_create_link_of(registration.clone(), LinkTypes::TournamentEntrants)?
+                .is_some_and(|create_link| {
+                    create_link.base_address
+                        == AnyLinkableHash::from(tournament_round.tournament.clone())
+                        && create_link.target_address == AnyLinkableHash::from(entrant.clone())
+                });
+        if !registered {
+            return Ok(Some(BattleshipsError::NotRegistered));
+        }
+    }
+    Ok(None)
+}
+
+/// Checks a result against the pairing it claims to settle
+fn validate_tournament_game_result(
+    tournament: &Tournament,
+    round_hash: &ActionHash,
+    pairing: &Pairing<AgentPubKey>,
+    result: &TournamentGameResult,
+    round_started_at: Timestamp,
+) -> ExternResult<Option<BattleshipsError>> {
+    let linked_from_round = must_get_create_link_of(
+        result.game_link.clone(),
+        LinkTypes::TournamentGames,
+    )?
+    .is_some_and(|create_link| {
+        create_link.base_address == AnyLinkableHash::from(round_hash.clone())
+            && create_link.target_address == AnyLinkableHash::from(result.game_invite.clone())
+    });
+    if !linked_from_round {
+        return Ok(Some(BattleshipsError::GameNotInRound));
+    }
+    let invite_record = must_get_valid_record(result.game_invite.clone())?;
+    let game_invite: GameInvite = match invite_record.entry().to_app_option() {
+        Ok(Some(game_invite)) => game_invite,
+        _ => {
+            return Ok(Some(BattleshipsError::WrongEntryType {
+                field: "game_invite".into(),
+                expected: RecordKind::GameInvite,
+            }))
+        }
+    };
+    // Games from before the round started can't count for it
+    if pairing.home != game_invite.home_player
+        || pairing.away.as_ref() != Some(&game_invite.away_player)
+        || game_invite.rules != tournament.rules
+        || invite_record.action().timestamp() < round_started_at
+    {
+        return Ok(Some(BattleshipsError::GameNotInRound));
+    }
+    let game_transcript = match must_get_valid_app_entry_and_author(result.game_transcript.clone())?
+    {
+        (EntryTypes::GameTranscript(game_transcript), _) => game_transcript,
+        _ => {
+            return Ok(Some(BattleshipsError::WrongEntryType {
+                field: "game_transcript".into(),
+                expected: RecordKind::GameTranscript,
+            }))
+        }
+    };
+    if game_transcript.invite != result.game_invite {
+        return Ok(Some(BattleshipsError::WrongInvite {
+            kind: RecordKind::GameTranscript,
+        }));
+    }
+    let winner = match get_game_turn(&game_transcript) {
+        GameTurn::HomeWon => game_invite.home_player,
+        GameTurn::AwayWon => game_invite.away_player,
+        _ => return Ok(Some(BattleshipsError::GameNotFinished)),
+    };
+    if result.winner != winner {
+        return Ok(Some(BattleshipsError::GameResultMismatch));
+    }
+    Ok(None)
+}
+
+pub fn validate_create_tournament_round(
+    action: EntryCreationAction,
+    tournament_round: TournamentRound,
+) -> ExternResult<ValidateCallbackResult> {
+    let tournament = match must_get_tournament(tournament_round.tournament.clone())? {
+        Ok(tournament) => tournament,
+        Err(error) => return error.invalid(),
+    };
+    if &tournament.organiser != action.author() {
+        return BattleshipsError::NotOrganiser.invalid();
+    }
+    let entrants = &tournament_round.entrants;
+    if entrants.len() < MIN_TOURNAMENT_ENTRANTS {
+        return BattleshipsError::TooFewEntrants {
+            min: MIN_TOURNAMENT_ENTRANTS,
+            actual: entrants.len(),
+        }
+        .invalid();
+    }
+    for (i, entrant) in entrants.iter().enumerate() {
+        if entrants[..i].contains(entrant) {
+            return BattleshipsError::DuplicateEntrant.invalid();
+        }
+    }
+
+    let previous_winners = match &tournament_round.previous_round {
+        None => {
+            if tournament_round.round != 0 {
+                return BattleshipsError::WrongRoundNumber {
+                    expected: 0,
+                    actual: tournament_round.round,
+                }
+                .invalid();
+            }
+            if !tournament_round.previous_results.is_empty() {
+                return BattleshipsError::WrongResultCount {
+                    expected: 0,
+                    actual: tournament_round.previous_results.len(),
+                }
+                .invalid();
+            }
+            if let Some(error) = validate_registrations(&tournament_round)? {
+                return error.invalid();
+            }
+            Vec::new()
+        }
+        Some(previous_round_hash) => {
+            let previous_record = must_get_valid_record(previous_round_hash.clone())?;
+            let previous_round: TournamentRound = match previous_record.entry().to_app_option() {
+                Ok(Some(previous_round)) => previous_round,
+                _ => {
+                    return BattleshipsError::WrongEntryType {
+                        field: "previous_round".into(),
+                        expected: RecordKind::TournamentRound,
+                    }
+                    .invalid()
+                }
+            };
+            if previous_round.tournament != tournament_round.tournament {
+                return BattleshipsError::WrongTournament.invalid();
+            }
+            if tournament_round.round != previous_round.round + 1 {
+                return BattleshipsError::WrongRoundNumber {
+                    expected: previous_round.round + 1,
+                    actual: tournament_round.round,
+                }
+                .invalid();
+            }
+            if &previous_round.entrants != entrants {
+                return BattleshipsError::EntrantsChanged.invalid();
+            }
+            if !tournament_round.registrations.is_empty() {
+                return BattleshipsError::WrongRegistrationCount {
+                    expected: 0,
+                    actual: tournament_round.registrations.len(),
+                }
+                .invalid();
+            }
+            if previous_round.pairings.is_empty() {
+                return BattleshipsError::TournamentFinished.invalid();
+            }
+            let games = previous_round
+                .pairings
+                .iter()
+                .filter(|pairing| !pairing.is_bye())
+                .count();
+            if tournament_round.previous_results.len() != games {
+                return BattleshipsError::WrongResultCount {
+                    expected: games,
+                    actual: tournament_round.previous_results.len(),
+                }
+                .invalid();
+            }
+            let mut results = tournament_round.previous_results.iter();
+            let mut previous_winners = Vec::new();
+            for pairing in &previous_round.pairings {
+                if pairing.is_bye() {
+                    previous_winners.push(pairing.home.clone());
+                    continue;
+                }
+                let result = match results.next() {
+                    Some(result) => result,
+                    None => break,
+                };
+                if let Some(error) = validate_tournament_game_result(
+                    &tournament,
+                    previous_round_hash,
+                    pairing,
+                    result,
+                    previous_record.action().timestamp(),
+                )? {
+                    return error.invalid();
+                }
+                previous_winners.push(result.winner.clone());
+            }
+            previous_winners
+        }
+    };
+
+    let pairings = tournament_pairings(
+        &tournament.format,
+        entrants,
+        tournament_round.round,
+        &previous_winners,
+    );
+    if pairings != tournament_round.pairings {
+        return BattleshipsError::PairingsMismatch.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_update_tournament_round(
+    _action: Update,
+    _tournament_round: TournamentRound,
+    _original_action: EntryCreationAction,
+    _original_tournament_round: TournamentRound,
+) -> ExternResult<ValidateCallbackResult> {
+    BattleshipsError::UpdateNotAllowed {
+        kind: RecordKind::TournamentRound,
+    }
+    .invalid()
+}
+
+pub fn validate_create_link_tournaments(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if base_address != AnyLinkableHash::from(tournaments_anchor_hash()?) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::Tournaments,
+        }
+        .invalid();
+    }
+    let tournament = match must_get_tournament(ActionHash::from(target_address))? {
+        Ok(tournament) => tournament,
+        Err(error) => return error.invalid(),
+    };
+    if tournament.organiser != action.author {
+        return BattleshipsError::NotOrganiser.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_tournament_entrants(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    if let Err(error) = must_get_tournament(ActionHash::from(base_address))? {
+        return error.invalid();
+    }
+    // Agents can only register themselves
+    if target_address != AnyLinkableHash::from(action.author) {
+        return BattleshipsError::LinkTargetMismatch {
+            link_type: LinkTypes::TournamentEntrants,
+        }
+        .invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+pub fn validate_create_link_tournament_rounds(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let tournament_round =
+        match must_get_valid_app_entry_and_author(ActionHash::from(target_address))? {
+            (EntryTypes::TournamentRound(tournament_round), _) => tournament_round,
+            _ => return BattleshipsError::EntryNotDeserializable.invalid(),
+        };
+    if base_address != AnyLinkableHash::from(tournament_round.tournament.clone()) {
+        return BattleshipsError::LinkBaseMismatch {
+            link_type: LinkTypes::TournamentRounds,
+        }
+        .invalid();
+    }
+    match must_get_tournament(tournament_round.tournament)? {
+        Ok(tournament) if tournament.organiser == action.author => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        Ok(_) => BattleshipsError::NotOrganiser.invalid(),
+        Err(error) => error.invalid(),
+    }
+}
+pub fn validate_create_link_tournament_games(
+    action: CreateLink,
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    _tag: LinkTag,
+) -> ExternResult<ValidateCallbackResult> {
+    let tournament_round =
+        match must_get_valid_app_entry_and_author(ActionHash::from(base_address))? {
+            (EntryTypes::TournamentRound(tournament_round), _) => tournament_round,
+            _ => {
+                return BattleshipsError::LinkBaseMismatch {
+                    link_type: LinkTypes::TournamentGames,
+                }
+                .invalid()
+            }
+        };
+    let game_invite = match must_get_valid_app_entry_and_author(ActionHash::from(target_address))? {
+        (EntryTypes::GameInvite(game_invite), _) => game_invite,
+        _ => return BattleshipsError::EntryNotDeserializable.invalid(),
+    };
+    if game_invite.home_player != action.author {
+        return BattleshipsError::HomePlayerMustBeAuthor.invalid();
+    }
+    let pairing = Pairing {
+        home: game_invite.home_player,
+        away: Some(game_invite.away_player),
+    };
+    if !tournament_round.pairings.contains(&pairing) {
+        return BattleshipsError::GameNotInRound.invalid();
+    }
+    // Home players only play one game per round, so one link from the round is all they need
+    if has_earlier_create_link(&action, LinkTypes::TournamentGames, |earlier| {
+        earlier.base_address == action.base_address
+    })? {
+        return BattleshipsError::PairingAlreadyStarted.invalid();
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
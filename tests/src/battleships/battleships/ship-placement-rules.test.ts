@@ -0,0 +1,76 @@
+import { assert, test } from "vitest";
+
+import { runScenario } from "@holochain/tryorama";
+import * as snarkjs from "snarkjs";
+
+const createCircuitWasm =
+  process.cwd() + "/../circuits/build/create/create_js/create.wasm";
+
+const SHIP_LENGTHS = [5, 4, 3, 3, 2];
+const BOARD_SIZE = 10;
+
+interface Ship {
+  x: number;
+  y: number;
+  horizontal: boolean;
+}
+
+// Horizontal ships on rows 0, 2, 4, 6, 8
+function stackedFleet(): Ship[] {
+  return SHIP_LENGTHS.map((_, i) => ({ x: 0, y: i * 2, horizontal: true }));
+}
+
+async function circuitAccepts(ships: Ship[]) {
+  const input = {
+    nonce: "1",
+    ships: ships.map((ship) => [
+      ship.x.toString(),
+      ship.y.toString(),
+      ship.horizontal ? "1" : "0",
+    ]),
+  };
+  try {
+    await snarkjs.wtns.calculate(input, createCircuitWasm, { type: "mem" });
+    return true;
+  } catch (e) {
+    return false;
+  }
+}
+
+test("ship placement rules agree with the create circuit on edge cases", async () => {
+  await runScenario(async (scenario) => {
+    // Construct proper paths for your app.
+    // This assumes app bundle created by the `hc app pack` command.
+    const testAppPath = process.cwd() + "/../workdir/battleships.happ";
+
+    // Set up the app to be installed
+    const appSource = { appBundleSource: { path: testAppPath } };
+
+    const [alice] = await scenario.addPlayersWithApps([appSource]);
+
+    // Slide every ship along the far edges of the board, in both orientations, including
+    // positions that hang off the edge or collide with the rest of the fleet.
+    for (let shipIndex = 0; shipIndex < SHIP_LENGTHS.length; shipIndex += 1) {
+      for (const horizontal of [true, false]) {
+        for (let offset = 0; offset <= BOARD_SIZE; offset += 1) {
+          for (const edge of [0, BOARD_SIZE - SHIP_LENGTHS[shipIndex], BOARD_SIZE - 1]) {
+            const fleet = stackedFleet();
+            fleet[shipIndex] = horizontal
+              ? { x: edge, y: offset, horizontal }
+              : { x: offset, y: edge, horizontal };
+            const placementError = await alice.cells[0].callZome({
+              zome_name: "battleships",
+              fn_name: "check_ship_placement",
+              payload: fleet,
+            });
+            assert.equal(
+              placementError === null,
+              await circuitAccepts(fleet),
+              `ship ${shipIndex} at ${JSON.stringify(fleet[shipIndex])}`
+            );
+          }
+        }
+      }
+    }
+  });
+});
@@ -19,6 +19,7 @@ import { Snackbar } from '@material/mwc-snackbar';
 
 import { clientContext } from '../../contexts';
 import { GameInvite } from './types';
+import { describeError } from './helpers';
 
 @customElement('create-game-invite')
 export class CreateGameInvite extends LitElement {
@@ -52,6 +53,7 @@ export class CreateGameInvite extends LitElement {
     const gameInvite: GameInvite = {
       home_player: this.client.myPubKey,
       away_player: this.opponent,
+      rules: { type: 'Classic' },
     };
 
     try {
@@ -76,7 +78,7 @@ export class CreateGameInvite extends LitElement {
       const errorSnackbar = this.shadowRoot?.getElementById(
         'create-error'
       ) as Snackbar;
-      errorSnackbar.labelText = `Error creating the game invite: ${e.data.data}`;
+      errorSnackbar.labelText = `Error creating the game invite: ${describeError(e)}`;
       errorSnackbar.show();
     }
     this.isSubmitting = false;
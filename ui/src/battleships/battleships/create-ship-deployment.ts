@@ -21,7 +21,7 @@ import {
   SHIP_SIZES_ENTRIES,
   SHIP_SIZE_TO_LABEL,
 } from './constants';
-import { boardWithFill } from './helpers';
+import { boardWithFill, describeError } from './helpers';
 
 interface Ships {
   '5'?: Ship;
@@ -198,7 +198,7 @@ export class CreateShipDeployment extends LitElement {
       const errorSnackbar = this.shadowRoot?.getElementById(
         'create-error'
       ) as Snackbar;
-      errorSnackbar.labelText = `Error creating the ship placements: ${e.data.data}`;
+      errorSnackbar.labelText = `Error creating the ship placements: ${describeError(e)}`;
       errorSnackbar.show();
     }
     this.isSubmitting = false;
@@ -14,6 +14,7 @@ import {
   FireShotInput,
   GameState,
   GameTranscript,
+  GameTranscriptEntry,
   HitOrMissProof,
   ProveHitOrMissInput,
   ShipDeployment,
@@ -24,14 +25,18 @@ import {
 } from './types';
 import {
   boardWithFill,
+  describeError,
+  flattenGameTranscript,
+  hitsBitmask,
   proofToCommaSeparated,
+  provenHits,
   shipToNumStrArr,
 } from './helpers';
 import { SHIP_SIZES } from './constants';
 import { CellFill } from './game-board';
 
-import circuitWasm from './circuits/move/move_js/move.wasm?url';
-import circuitZkey from './circuits/move/move_0001.zkey?url';
+import circuitWasm from './circuits/move_sunk/move_sunk_js/move_sunk.wasm?url';
+import circuitZkey from './circuits/move_sunk/move_sunk_0001.zkey?url';
 
 interface GameData {
   yourDeployment?: ShipDeployment;
@@ -137,7 +142,9 @@ export class GameBoardPair extends LitElement {
       payload: gameInviteHash,
     });
     if (!record) return undefined;
-    return decode((record.entry as any).Present.entry) as GameTranscript;
+    return flattenGameTranscript(
+      decode((record.entry as any).Present.entry) as GameTranscriptEntry
+    );
   }
 
   handleCellHover(e: CustomEvent) {
@@ -188,7 +195,7 @@ export class GameBoardPair extends LitElement {
       const errorSnackbar = this.shadowRoot?.getElementById(
         'snackbar'
       ) as Snackbar;
-      errorSnackbar.labelText = `Error firing shot: ${e?.data?.data}`;
+      errorSnackbar.labelText = `Error firing shot: ${describeError(e)}`;
       errorSnackbar.show();
     }
   }
@@ -218,6 +225,7 @@ export class GameBoardPair extends LitElement {
         this.createHitOrMissProof(
           x,
           y,
+          provenHits(away_player_shots, home_player_hit_or_miss_proofs),
           yourDeployment,
           yourDeploymentProofAndHash[1],
           yourDeploymentProofAndHash[0].commitment,
@@ -239,6 +247,7 @@ export class GameBoardPair extends LitElement {
         this.createHitOrMissProof(
           x,
           y,
+          provenHits(home_player_shots, away_player_hit_or_miss_proofs),
           yourDeployment,
           yourDeploymentProofAndHash[1],
           yourDeploymentProofAndHash[0].commitment,
@@ -252,6 +261,7 @@ export class GameBoardPair extends LitElement {
   async createHitOrMissProof(
     x: number,
     y: number,
+    priorHits: Shot[],
     yourDeployment: ShipDeployment,
     yourDeploymentProofHash: ActionHash,
     commitment: string,
@@ -260,9 +270,10 @@ export class GameBoardPair extends LitElement {
     if (this.isProving) return;
     try {
       this.isProving = true;
-      const { proofStr, isHit } = await this.proveHitOrMiss(
+      const { proofStr, isHit, sunkLength } = await this.proveHitOrMiss(
         x,
         y,
+        priorHits,
         yourDeployment,
         commitment
       );
@@ -272,6 +283,11 @@ export class GameBoardPair extends LitElement {
         shot: { x, y },
         hit: isHit,
         proof: proofStr,
+        kind: {
+          type: 'WithSunk',
+          prior_hits: priorHits,
+          sunk_ship_length: sunkLength === 0 ? null : sunkLength,
+        },
       };
       const payload: ProveHitOrMissInput = {
         hit_or_miss_proof: hitOrMissProof,
@@ -291,7 +307,7 @@ export class GameBoardPair extends LitElement {
       const errorSnackbar = this.shadowRoot?.getElementById(
         'snackbar'
       ) as Snackbar;
-      errorSnackbar.labelText = `Error proving hit/miss: ${e?.data?.data}`;
+      errorSnackbar.labelText = `Error proving hit/miss: ${describeError(e)}`;
       errorSnackbar.show();
     }
 
@@ -301,6 +317,7 @@ export class GameBoardPair extends LitElement {
   async proveHitOrMiss(
     x: number,
     y: number,
+    priorHits: Shot[],
     yourDeployment: ShipDeployment,
     commitment: string
   ) {
@@ -311,6 +328,7 @@ export class GameBoardPair extends LitElement {
       ships: yourDeployment.ships.map(shipToNumStrArr),
       boardHash: commitment,
       guess: [x.toString(), y.toString()],
+      priorHits: hitsBitmask(priorHits),
     };
     const { proof, publicSignals } = await groth16.fullProve(
       inputs,
@@ -320,10 +338,14 @@ export class GameBoardPair extends LitElement {
     // eslint-disable-next-line
     console.log(proof);
     const proofStr = proofToCommaSeparated(proof);
-    const [isHit, _boardHash, _x, _y] = publicSignals;
+    const [isHit, sunkLength] = publicSignals;
     // eslint-disable-next-line
     console.log(publicSignals);
-    return { proofStr, isHit: !!Number(isHit) };
+    return {
+      proofStr,
+      isHit: !!Number(isHit),
+      sunkLength: Number(sunkLength),
+    };
   }
 
   getTopCells({ yourDeployment, gameTranscript }: GameData) {
@@ -405,6 +427,9 @@ export class GameBoardPair extends LitElement {
     ) {
       return 'Waiting for opponent to deploy ships';
     }
+    if (this.gameState.type === 'AwaitingCoinFlip') {
+      return 'Flipping a coin to decide who fires first';
+    }
     if (this.gameState.type === 'GameStarted') {
       switch (this.viewerRole) {
         case 'home':
@@ -417,6 +442,10 @@ export class GameBoardPair extends LitElement {
               return "Opponent's turn";
             case 'AwayProof':
               return "Waiting for opponent's hit/miss proof";
+            case 'HomeWon':
+              return 'You won!';
+            case 'AwayWon':
+              return 'You lost';
             default:
               return '';
           }
@@ -430,6 +459,10 @@ export class GameBoardPair extends LitElement {
               return "Opponent's turn";
             case 'HomeProof':
               return "Waiting for opponent's hit/miss proof";
+            case 'AwayWon':
+              return 'You won!';
+            case 'HomeWon':
+              return 'You lost';
             default:
               return '';
           }
@@ -35,6 +35,19 @@ export class GameDialog extends LitElement {
       });
       const role = await this.getRole(gameInviteHash);
       const gameState = await gameStateProm;
+      if (
+        gameState.type === 'AwaitingCoinFlip' &&
+        ((role === 'home' && !gameState.home_player_revealed) ||
+          (role === 'away' && !gameState.away_player_revealed))
+      ) {
+        await this.client.callZome({
+          cap_secret: null,
+          role_name: 'battleships',
+          zome_name: 'battleships',
+          fn_name: 'reveal_coin_flip',
+          payload: gameInviteHash,
+        });
+      }
       return { gameState, role };
     },
     () => [this.gameInviteHash]
@@ -1,5 +1,12 @@
 import { BOARD_SIZE } from './constants';
-import { Ship } from './types';
+import {
+  BattleshipsError,
+  GameTranscript,
+  GameTranscriptEntry,
+  Ship,
+  Shot,
+  ShotOutcome,
+} from './types';
 
 export function boardWithFill<T>(fill: T) {
   return Array.from({ length: BOARD_SIZE }, () =>
@@ -11,6 +18,39 @@ export function shipToNumStrArr(ship: Ship) {
   return [ship.x.toString(), ship.y.toString(), ship.horizontal ? '1' : '0'];
 }
 
+// Matches the priorHits encoding in move_sunk.circom. The mask is too wide for a number, so
+// it is built up as a hex string, which snarkjs accepts as an input
+export function hitsBitmask(shots: Shot[]): string {
+  const nibbles: number[] = Array(
+    Math.ceil((BOARD_SIZE * BOARD_SIZE) / 4)
+  ).fill(0);
+  for (const { x, y } of shots) {
+    const bit = y * BOARD_SIZE + x;
+    nibbles[Math.floor(bit / 4)] |= 1 << (bit % 4);
+  }
+  return `0x${nibbles
+    .reverse()
+    .map(nibble => nibble.toString(16))
+    .join('')}`;
+}
+
+// The opponent's shots that have already been proven to hit
+export function provenHits(shots: Shot[], outcomes: ShotOutcome[]): Shot[] {
+  return shots.filter((_, i) => outcomes[i]?.hit);
+}
+
+export function flattenGameTranscript(
+  entry: GameTranscriptEntry
+): GameTranscript {
+  return {
+    ...entry,
+    home_player_shots: entry.home_player_shots.flat(),
+    away_player_shots: entry.away_player_shots.flat(),
+    home_player_hit_or_miss_proofs: entry.home_player_hit_or_miss_proofs.flat(),
+    away_player_hit_or_miss_proofs: entry.away_player_hit_or_miss_proofs.flat(),
+  };
+}
+
 export function proofToCommaSeparated(proof: any) {
   return [
     proof.pi_a[0],
@@ -23,3 +63,26 @@ export function proofToCommaSeparated(proof: any) {
     proof.pi_c[1],
   ].join(',');
 }
+
+// Extracts the structured error from a failed zome call, if the zome raised one
+export function decodeBattleshipsError(e: any): BattleshipsError | undefined {
+  const message: string | undefined = e?.data?.data ?? e?.message;
+  if (typeof message !== 'string') return undefined;
+  const start = message.indexOf('{"code"');
+  if (start === -1) return undefined;
+  const end = message.lastIndexOf('}');
+  try {
+    return JSON.parse(message.slice(start, end + 1));
+  } catch {
+    return undefined;
+  }
+}
+
+export function describeError(e: any): string {
+  const error = decodeBattleshipsError(e);
+  if (!error) return `${e?.data?.data ?? e}`;
+  const { code, ...fields } = error;
+  return Object.keys(fields).length > 0
+    ? `${code} ${JSON.stringify(fields)}`
+    : code;
+}
@@ -15,7 +15,7 @@ import '@material/mwc-circular-progress';
 import '@material/mwc-list';
 
 import { clientContext } from '../../contexts';
-import { BattleshipsSignal } from './types';
+import { BattleshipsSignal, InviteListing } from './types';
 
 import './invite-list-item';
 
@@ -40,9 +40,9 @@ export class InviteList extends LitElement {
         cap_secret: null,
         role_name: 'battleships',
         zome_name: 'battleships',
-        fn_name: 'get_invites',
+        fn_name: 'get_invite_listings',
         payload: recipient,
-      }) as Promise<Array<Record>>,
+      }) as Promise<Array<InviteListing>>,
     () => [this.recipient]
   );
 
@@ -82,10 +82,10 @@ export class InviteList extends LitElement {
     );
   }
 
-  renderRecords(records: Record[]) {
+  renderListings(listings: InviteListing[]) {
     const hashes = mergeHashes(
       this.signaledHashes,
-      records.map(r => r.signed_action.hashed.hash)
+      listings.map(l => l.game_invite_hash)
     );
     if (hashes.length === 0)
       return html`<span>No game invites found for this recipient.</span>`;
@@ -115,9 +115,9 @@ export class InviteList extends LitElement {
     return this._fetchGameInvites.render({
       pending: () =>
         this._fetchGameInvites.value
-          ? this.renderRecords(this._fetchGameInvites.value)
+          ? this.renderListings(this._fetchGameInvites.value)
           : this.renderLoading(),
-      complete: records => this.renderRecords(records),
+      complete: listings => this.renderListings(listings),
       error: (e: any) =>
         html`<span>Error fetching the game invites: ${e.data.data}.</span>`,
     });
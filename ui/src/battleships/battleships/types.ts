@@ -38,6 +38,20 @@ export type BattleshipsSignal =
       type: 'LinkDeleted';
       action: SignedActionHashed<DeleteLink>;
       link_type: string;
+    }
+  | {
+      type: 'GameMoved';
+      game_invite_hash: ActionHash;
+      game_transcript_hash: ActionHash;
+      turn: GameTurn;
+      action: SignedActionHashed;
+      game_transcript: GameTranscriptEntry;
+    }
+  | {
+      type: 'GameMessageReceived';
+      game_invite_hash: ActionHash;
+      action: SignedActionHashed<Create>;
+      game_message: GameMessage;
     };
 
 export type EntryTypes =
@@ -45,9 +59,12 @@ export type EntryTypes =
   | ({ type: 'ShipDeployment' } & ShipDeployment)
   | ({ type: 'GameInvite' } & GameInvite);
 
+export type GameRules = { type: 'Classic' } | { type: 'Salvo' };
+
 export interface GameInvite {
   home_player: AgentPubKey;
   away_player: AgentPubKey;
+  rules?: GameRules;
 }
 
 export interface Ship {
@@ -60,6 +77,8 @@ export interface ShipDeployment {
   invite: ActionHash;
   ships: Ship[];
   salt: string;
+  // Generated by the zome when omitted
+  coin_secret?: Uint8Array;
 }
 
 export interface ShipDeploymentProof {
@@ -69,19 +88,47 @@ export interface ShipDeploymentProof {
   commitment: string;
 
   proof: string;
+
+  // Filled in by the zome from the private deployment
+  coin_commitment?: Uint8Array;
+}
+
+export type Player = { type: 'Home' } | { type: 'Away' };
+
+export interface CoinFlipReveal {
+  invite: ActionHash;
+  deployment_proof: ActionHash;
+  secret: Uint8Array;
+}
+
+export interface CoinFlipRevealLinkTag {
+  revealer: AgentPubKey;
+  secret: Uint8Array;
 }
 
 export type GameTurn =
   | { type: 'AwayShot' }
   | { type: 'HomeProof' }
   | { type: 'HomeShot' }
-  | { type: 'AwayProof' };
+  | { type: 'AwayProof' }
+  | { type: 'HomeWon' }
+  | { type: 'AwayWon' };
 
 export type GameState =
   | { type: 'AwaitingBothDeployments' }
   | { type: 'AwaitingHomeDeployment' }
   | { type: 'AwaitingAwayDeployment' }
-  | { type: 'GameStarted'; turn: GameTurn };
+  | {
+      type: 'AwaitingCoinFlip';
+      home_player_revealed: boolean;
+      away_player_revealed: boolean;
+    }
+  | {
+      type: 'GameStarted';
+      turn: GameTurn;
+      home_player_sunk_ships: number[];
+      away_player_sunk_ships: number[];
+    };
 
 export type ViewerRole = 'home' | 'away' | 'spectator' | 'unknown';
 
@@ -101,25 +148,404 @@ export interface ShotOutcome {
   pending: boolean;
 }
 
+// As stored on the DHT - shots and outcomes are batched per turn
+export interface GameTranscriptEntry {
+  invite: ActionHash;
+  home_player_deployment_proof: ActionHash;
+  away_player_deployment_proof: ActionHash;
+  home_player_coin_flip_reveal: ActionHash;
+  away_player_coin_flip_reveal: ActionHash;
+  first_player: Player;
+  home_player_shots: Shot[][];
+  away_player_shots: Shot[][];
+  home_player_hit_or_miss_proofs: ShotOutcome[][];
+  away_player_hit_or_miss_proofs: ShotOutcome[][];
+}
+
 export interface GameTranscript {
   invite: ActionHash;
   home_player_deployment_proof: ActionHash;
   away_player_deployment_proof: ActionHash;
+  home_player_coin_flip_reveal: ActionHash;
+  away_player_coin_flip_reveal: ActionHash;
+  first_player: Player;
   home_player_shots: Shot[];
   away_player_shots: Shot[];
   home_player_hit_or_miss_proofs: ShotOutcome[];
   away_player_hit_or_miss_proofs: ShotOutcome[];
 }
 
+export type HitOrMissProofKind =
+  | { type: 'HitOrMiss' }
+  | {
+      type: 'WithSunk';
+      prior_hits: Shot[];
+      sunk_ship_length: number | null;
+    };
+
 export interface HitOrMissProof {
   deployment_proof: ActionHash;
   deployment_commitment: String;
   shot: Shot;
   hit: boolean;
   proof: String;
+  kind?: HitOrMissProofKind;
+}
+
+export interface FleetReveal {
+  invite: ActionHash;
+  game_transcript: ActionHash;
+  deployment_proof: ActionHash;
+  ships: Ship[];
+  salt: string;
 }
 
 export interface ProveHitOrMissInput {
   game_invite_hash: ActionHash;
   hit_or_miss_proof: HitOrMissProof;
 }
+
+export type GameOutcome =
+  | { type: 'Won' }
+  | { type: 'Lost' }
+  | { type: 'Abandoned' }
+  | { type: 'InProgress' };
+
+export interface GameSummary {
+  game_invite_hash: ActionHash;
+  opponent: AgentPubKey;
+  rules: GameRules;
+  outcome: GameOutcome;
+  shots_fired: number;
+  hits: number;
+  last_move_at: number;
+}
+
+export interface PlayerStats {
+  games_played: number;
+  games_won: number;
+  games_lost: number;
+  games_abandoned: number;
+  average_shots_to_win: number | null;
+  hit_ratio: number | null;
+}
+
+export interface GameResult {
+  invite: ActionHash;
+  game_transcript: ActionHash;
+  winner: AgentPubKey;
+  loser: AgentPubKey;
+}
+
+export interface Rating {
+  agent: AgentPubKey;
+  rating: number;
+  games: number;
+  profile?: Profile;
+}
+
+export type CellView =
+  | { type: 'NotShot' }
+  | { type: 'Pending' }
+  | { type: 'Hit' }
+  | { type: 'Miss' };
+
+export interface PublicGameView {
+  home_player: AgentPubKey;
+  away_player: AgentPubKey;
+  rules: GameRules;
+  turn: GameTurn | null;
+  move_number: number;
+  // Indexed [x][y]
+  home_board: CellView[][];
+  away_board: CellView[][];
+  home_player_sunk_ships: number[];
+  away_player_sunk_ships: number[];
+}
+
+export interface ReplayMove {
+  // Who fired
+  player: Player;
+  shot: Shot;
+  // Null until the defender proves it
+  outcome: { hit: boolean; sunk_ship_length: number | null } | null;
+  proof_hash: ActionHash | null;
+  fired_at: number;
+  proven_at: number | null;
+}
+
+export interface GameSnapshot {
+  move_index: number;
+  // Indexed [x][y]
+  home_board: CellView[][];
+  away_board: CellView[][];
+  home_player_sunk_ships: number[];
+  away_player_sunk_ships: number[];
+}
+
+// Score per square of the opponent's board, indexed [x][y]. Higher is a likelier hit and
+// squares already fired at score 0.
+export type Heatmap = number[][];
+
+export type MyGamesFilter =
+  | { type: 'All' }
+  | { type: 'MyTurn' }
+  | { type: 'Waiting' }
+  | { type: 'Finished' };
+
+export interface MyGame {
+  game_invite_hash: ActionHash;
+  game_invite: GameInvite;
+  opponent: AgentPubKey;
+  opponent_profile?: Profile;
+  state: GameState;
+  last_move_at: number;
+  my_turn: boolean;
+}
+
+export interface InviteListing {
+  game_invite_hash: ActionHash;
+  opponent: AgentPubKey;
+  is_home_player: boolean;
+  rules: GameRules;
+  created_at: number;
+  opponent_profile?: Profile;
+}
+
+export interface DeploymentProofLinkTag {
+  deployer: AgentPubKey;
+  deployed_at: number;
+}
+
+export type BattleshipsErrorCode =
+  | 'NotFound'
+  | 'WrongEntryType'
+  | 'EntryNotDeserializable'
+  | 'ExportNotSerializable'
+  | 'UpdateNotAllowed'
+  | 'DeleteNotAllowed'
+  | 'EntryTypeChanged'
+  | 'InvalidOriginalAction'
+  | 'OriginalEntryMissing'
+  | 'UnknownEntryType'
+  | 'InvalidAgentValidationPkg'
+  | 'MalformedProof'
+  | 'HomePlayerMustBeAuthor'
+  | 'CannotInviteSelf'
+  | 'NotAPlayer'
+  | 'NotDeployer'
+  | 'WrongInvite'
+  | 'InvalidPlacement'
+  | 'InvalidProof'
+  | 'WrongDeploymentCount'
+  | 'MultipleDeployments'
+  | 'DeploymentMismatch'
+  | 'DeploymentNotInGame'
+  | 'DeploymentCommitmentMismatch'
+  | 'MissingCoinCommitment'
+  | 'InvalidCoinSecretLength'
+  | 'CoinCommitmentMismatch'
+  | 'CopiedCoinCommitment'
+  | 'CoinFlipNotRevealed'
+  | 'CoinFlipRevealMismatch'
+  | 'FirstPlayerMismatch'
+  | 'NotYourTurn'
+  | 'GameFinished'
+  | 'GameNotFinished'
+  | 'CorruptTranscript'
+  | 'InvalidTranscriptStart'
+  | 'UnexpectedTranscriptChange'
+  | 'MoveIndexOutOfRange'
+  | 'WrongSalvoSize'
+  | 'InvalidShot'
+  | 'MissingSalvo'
+  | 'MissingProofs'
+  | 'WrongProofCount'
+  | 'OutcomeInconsistentWithProof'
+  | 'ProofByWrongAuthor'
+  | 'ProofForWrongDeployment'
+  | 'ProofForWrongShot'
+  | 'InvalidPriorHits'
+  | 'PriorHitsMismatch'
+  | 'InvalidSunkShipLength'
+  | 'SunkProofRequired'
+  | 'WrongFleetSize'
+  | 'FleetCommitmentMismatch'
+  | 'FleetInconsistentWithOutcomes'
+  | 'FleetAlreadyRevealed'
+  | 'GameResultMismatch'
+  | 'EmptyMessage'
+  | 'MessageTooLong'
+  | 'MessageRateLimited'
+  | 'InvalidNicknameLength'
+  | 'InvalidNickname'
+  | 'BioTooLong'
+  | 'ProfileAlreadyExists'
+  | 'NotProfileOwner'
+  | 'NicknameMismatch'
+  | 'InvalidTournamentNameLength'
+  | 'NotOrganiser'
+  | 'TooFewEntrants'
+  | 'DuplicateEntrant'
+  | 'RegistrationClosed'
+  | 'WrongRegistrationCount'
+  | 'NotRegistered'
+  | 'WrongTournament'
+  | 'WrongRoundNumber'
+  | 'EntrantsChanged'
+  | 'TournamentFinished'
+  | 'WrongResultCount'
+  | 'GameNotInRound'
+  | 'PairingAlreadyStarted'
+  | 'PairingsMismatch'
+  | 'InvalidMatchLength'
+  | 'GameNotInMatch'
+  | 'WrongMatchRoles'
+  | 'MatchGameOutOfRange'
+  | 'MatchFinished'
+  | 'MatchGameAlreadyStarted'
+  | 'WrongPreviousMatchGame'
+  | 'WrongMatchScore'
+  | 'InvalidDnaProperties'
+  | 'MockVerifierMismatch'
+  | 'MissingMembraneProof'
+  | 'MalformedMembraneProof'
+  | 'MembraneProofForWrongAgent'
+  | 'MembraneProofForWrongNetwork'
+  | 'InvalidMembraneProofSignature'
+  | 'NotProgenitor'
+  | 'LinkBaseMismatch'
+  | 'LinkTargetMismatch'
+  | 'InvalidLinkTag'
+  | 'LinkTagMismatch';
+
+// Errors from either zome are JSON tagged by code; the other fields depend on the code
+export interface BattleshipsError {
+  code: BattleshipsErrorCode;
+  [field: string]: unknown;
+}
+
+export type Emote =
+  | { type: 'Salute' }
+  | { type: 'Laugh' }
+  | { type: 'Wow' }
+  | { type: 'Sad' }
+  | { type: 'Angry' }
+  | { type: 'GoodGame' };
+
+export type GameMessageContent =
+  | { type: 'Text'; text: string }
+  | { type: 'Emote'; emote: Emote };
+
+export interface GameMessage {
+  invite: ActionHash;
+  content: GameMessageContent;
+}
+
+export interface Profile {
+  nickname: string;
+  avatar?: string;
+  bio: string;
+}
+
+export interface AgentProfile {
+  agent: AgentPubKey;
+  profile: Profile;
+}
+
+export interface DnaProperties {
+  progenitor?: string;
+}
+
+export type TournamentFormat =
+  | { type: 'SingleElimination' }
+  | { type: 'RoundRobin' };
+
+export interface Tournament {
+  name: string;
+  organiser: AgentPubKey;
+  format: TournamentFormat;
+  rules?: GameRules;
+}
+
+// A pairing without an away player is a bye
+export interface Pairing {
+  home: AgentPubKey;
+  away?: AgentPubKey;
+}
+
+export interface TournamentGameResult {
+  game_invite: ActionHash;
+  // The round's TournamentGames link to the game
+  game_link: ActionHash;
+  game_transcript: ActionHash;
+  winner: AgentPubKey;
+}
+
+export interface TournamentRound {
+  tournament: ActionHash;
+  round: number;
+  previous_round?: ActionHash;
+  entrants: AgentPubKey[];
+  // The first round's TournamentEntrants links, one per entrant
+  registrations: ActionHash[];
+  previous_results: TournamentGameResult[];
+  // Empty once the tournament is over
+  pairings: Pairing[];
+}
+
+export interface BracketGame {
+  pairing: Pairing;
+  game_invite_hash?: ActionHash;
+  winner?: AgentPubKey;
+}
+
+export interface BracketRound {
+  round_hash: ActionHash;
+  round: number;
+  games: BracketGame[];
+}
+
+export interface Standing {
+  player: AgentPubKey;
+  wins: number;
+  losses: number;
+}
+
+export interface TournamentStandings {
+  standings: Standing[];
+  finished: boolean;
+  champion?: AgentPubKey;
+}
+
+export interface Match {
+  // Home in the first game and every other one after it
+  home_player: AgentPubKey;
+  away_player: AgentPubKey;
+  best_of: number;
+  rules?: GameRules;
+}
+
+export interface MatchGame {
+  game_number: number;
+  game_invite_hash: ActionHash;
+  home_player: AgentPubKey;
+  away_player: AgentPubKey;
+  winner?: AgentPubKey;
+}
+
+export interface NextMatchGame {
+  game_number: number;
+  home_player: AgentPubKey;
+  away_player: AgentPubKey;
+}
+
+export interface MatchState {
+  game_match: Match;
+  home_player_wins: number;
+  away_player_wins: number;
+  games: MatchGame[];
+  winner?: AgentPubKey;
+  // Unset while a game is being played and once the match is over
+  next_game?: NextMatchGame;
+}